@@ -11,6 +11,7 @@
 // *****************************************************************
 #![allow(non_snake_case)]
 extern crate sdl2;
+extern crate capture;
 
 use sdl2::pixels::Color;
 use sdl2::event::Event;
@@ -19,6 +20,7 @@ use sdl2::rect::Rect;
 use sdl2::render::TextureCreator;
 use sdl2::image::LoadTexture;
 use std::time::Duration;
+use capture::Screenshot;
 
 fn main() {
     let sdl2_context = sdl2::init().unwrap();
@@ -54,6 +56,8 @@ fn main() {
     let mut monster: i32 = 0;   // モンスター種類(0-4)
     let step: i32 = 4;  // 移動ステップ値
 
+    let mut screenshot = false;     // F12でスクリーンショット
+
     let mut event_pump = sdl2_context.event_pump().unwrap();
     'running: loop {
         for event in event_pump.poll_iter() {
@@ -96,6 +100,11 @@ fn main() {
                 | Event::KeyDown {
                     keycode: Some(Keycode::Escape), ..
                 } => break 'running,
+                | Event::KeyDown {
+                    keycode: Some(Keycode::F12), ..
+                } => {
+                    screenshot = true;
+                }
                 _ => {}
             }
         }
@@ -115,6 +124,15 @@ fn main() {
         let dest: Rect = Rect::new(x, y, IMGW_DEST, IMGW_DEST);
         canvas.copy(&image_texture, Some(src), Some(dest)).expect("copy texture to canvas failed");
 
+        // F12が押されていたら表示する前に保存
+        if screenshot {
+            match Screenshot::saveScreenshot(&canvas) {
+                Ok(path) => println!("saved {}", path),
+                Err(e) => println!("screenshot failed: {}", e)
+            }
+            screenshot = false;
+        }
+
         canvas.present();
         ::std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 60));
     }
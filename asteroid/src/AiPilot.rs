@@ -0,0 +1,166 @@
+#![allow(non_snake_case)]
+
+use Game::Game;
+use Asteroid::Asteroid;
+use Input::PlayerInput;
+use Util::{deg2rad, wrapDelta};
+
+const HORIZON: f64 = 60.0;      // これだけ先のフレームまでにぶつかる小惑星を危ないとみなす
+const MARGIN: f64 = 10.0;       // ぶつかるかどうかを調べるときに小惑星の半径に足す余裕(px)
+const SHIELD_FRAMES: f64 = 6.0; // これより早くぶつかりそうならシールドを張る
+const ESCAPE_SPEED: f64 = 2.5;  // 逃げるときの速さ(px/フレーム)。これより速ければもう加速しない
+const AIM_TOLERANCE: f64 = 0.12;    // これより角度差が小さければ撃つ(rad)
+const THRUST_TOLERANCE: f64 = 0.6;  // 逃げる方向と機首（逆噴射なら機尾）の角度差がこれより小さければ噴射する(rad)
+
+// ---------------------------------
+//  自動操縦（アトラクトモードのデモプレイ、asteroid-simの操縦用）
+//  ぶつかりそうな小惑星のうち一番早くぶつかるものに機首を向けて撃ち、
+//  逃げる先が空いていればそちらへ噴射する。ぶつかる直前ならシールドを張る
+//  危ない小惑星がなければ一番近い小惑星を狙って撃つ
+// ---------------------------------
+pub struct AiPilot {
+    fireCount: i32  // 次に撃てるまでのフレーム数
+}
+
+// 角度の差を -π..π にする
+fn angleDiff(a: f64, b: f64) -> f64 {
+    let pi = ::std::f64::consts::PI;
+    let mut d = (a - b) % (pi * 2.0);
+    if d > pi {
+        d -= pi * 2.0;
+    } else if d < -pi {
+        d += pi * 2.0;
+    }
+    return d;
+}
+
+// 相対位置(dx, dy)、相対速度(vx, vy)で近づいてくる点が、半径rの円に入るまでのフレーム数
+// もう入っていれば0、HORIZONまでに入らなければNone
+fn contactTime(dx: f64, dy: f64, vx: f64, vy: f64, r: f64) -> Option<f64> {
+    let c = dx*dx + dy*dy - r*r;
+    if c <= 0.0 {
+        return Some(0.0);
+    }
+    let a = vx*vx + vy*vy;
+    let b = 2.0 * (dx*vx + dy*vy);
+    let disc = b*b - 4.0*a*c;
+    if a == 0.0 || b >= 0.0 || disc < 0.0 {
+        return None;    // 止まっているか、離れていくか、かすりもしない
+    }
+    let t = (-b - disc.sqrt()) / (2.0 * a);
+    return if t <= HORIZON { Some(t) } else { None };
+}
+
+impl AiPilot {
+    pub fn new() -> Self {
+        return AiPilot {
+            fireCount: 0
+        };
+    }
+
+    // i番目のプレイヤーの操作を決める
+    pub fn think(&mut self, game: &Game, i: usize, width: u32, height: u32) -> PlayerInput {
+        let mut input = PlayerInput::default();
+
+        let myShip = match game.getShip(i) {
+            Some(s) => s,
+            None => { return input; }
+        };
+        let tuning = game.getTuning();
+        let (ww, wh) = game.getWorldSize(width, height);
+        let wf = ww as f64;
+        let hf = wh as f64;
+        let sx = myShip.getX();
+        let sy = myShip.getY();
+        let svx = myShip.getVx();
+        let svy = myShip.getVy();
+
+        // 宇宙船から見た小惑星の位置
+        let rocks: Vec<(&Asteroid, f64, f64)> = game.getAsteroids().iter()
+            .filter(|a| a.getValid())
+            .map(|a| (a, wrapDelta(a.getX() - sx, wf), wrapDelta(a.getY() - sy, hf)))
+            .collect();
+
+        // 今の速度のままだと一番早くぶつかる小惑星と、一番近い小惑星（表面までの距離で比べる）
+        let mut threat = None;
+        let mut threatTime = ::std::f64::MAX;
+        let mut nearest = None;
+        let mut nearestDist = ::std::f64::MAX;
+        for (k, &(a, dx, dy)) in rocks.iter().enumerate() {
+            if let Some(t) = contactTime(dx, dy, a.getVx() - svx, a.getVy() - svy, a.getRadius() + MARGIN) {
+                if t < threatTime {
+                    threatTime = t;
+                    threat = Some(k);
+                }
+            }
+            let dist = (dx*dx + dy*dy).sqrt() - a.getRadius();
+            if dist < nearestDist {
+                nearestDist = dist;
+                nearest = Some(k);
+            }
+        }
+
+        let k = match threat.or(nearest) {
+            Some(k) => k,
+            None => { return input; }
+        };
+        let (target, dx, dy) = rocks[k];
+
+        // 弾が届くまでの時間だけ先の位置を狙う
+        let t = (dx*dx + dy*dy).sqrt() / tuning.bulletSpeed;
+        let px = dx + (target.getVx() - svx) * t;
+        let py = dy + (target.getVy() - svy) * t;
+        let desired = py.atan2(px);
+
+        // 回転は角加速度で止まるので、止まるまでに回る角度が残りより大きければ離して止める
+        let rot = myShip.getRotation();
+        let d = angleDiff(desired, rot);
+        let w = myShip.getRotationSpeed();
+        let braking = w * w / (2.0 * deg2rad(tuning.shipRotationAccelDeg));
+        if d * w > 0.0 && d.abs() <= braking {
+            // 離して止まるのを待つ
+        } else if d > 0.05 {
+            input.right = true;
+        } else if d < -0.05 {
+            input.left = true;
+        }
+
+        // 狙いが合ったら撃つ（押しっぱなしでは連射にならないので間隔をあける）
+        if self.fireCount > 0 {
+            self.fireCount -= 1;
+        } else if d.abs() < AIM_TOLERANCE {
+            input.fire = true;
+            self.fireCount = tuning.fireCooldown.max(1);
+        }
+
+        if threat.is_some() {
+            if threatTime <= SHIELD_FRAMES {
+                // もう逃げられない
+                input.shield = true;
+            } else if self.isClear(&rocks, k, -dx, -dy, svx, svy) {
+                // 危ない小惑星と反対の方向が空いていれば、機首か機尾がそちらを向いているときに噴射する
+                let away = (-dy).atan2(-dx);
+                let speed = svx * away.cos() + svy * away.sin();
+                if speed < ESCAPE_SPEED {
+                    if angleDiff(away, rot).abs() < THRUST_TOLERANCE {
+                        input.up = true;
+                    } else if tuning.reverseThrust > 0.0 && angleDiff(away, rot + ::std::f64::consts::PI).abs() < THRUST_TOLERANCE {
+                        input.down = true;
+                    }
+                }
+            }
+        }
+
+        return input;
+    }
+
+    // (ex, ey)の方向にESCAPE_SPEEDで逃げたとき、k番目以外の小惑星にぶつからなければtrue
+    fn isClear(&self, rocks: &[(&Asteroid, f64, f64)], k: usize, ex: f64, ey: f64, svx: f64, svy: f64) -> bool {
+        let len = (ex*ex + ey*ey).sqrt().max(1e-6);
+        let vx = svx + ex / len * ESCAPE_SPEED;
+        let vy = svy + ey / len * ESCAPE_SPEED;
+        return rocks.iter().enumerate().all(|(j, &(a, dx, dy))| {
+            j == k || contactTime(dx, dy, a.getVx() - vx, a.getVy() - vy, a.getRadius() + MARGIN).is_none()
+        });
+    }
+}
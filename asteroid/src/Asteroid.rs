@@ -1,10 +1,15 @@
 #![allow(non_snake_case)]
 
-use sdl2::video::Window;
-use sdl2::render::Canvas;
-use sdl2::pixels::Color;
 use sdl2::rect::Point;
 
+use Random::Random;
+use AsteroidShape::AsteroidShape;
+use Draw::Draw;
+use Util;
+use Camera::View;
+use Theme::Theme;
+use SaveGame::{SaveWriter, SaveReader};
+
 // ---------------------------------
 //  小惑星
 // ---------------------------------
@@ -16,50 +21,55 @@ pub struct Asteroid {
     vy: f64,
     rot: f64, // 回転角
     drot: f64,    // 回転速度
-    size: i32,  // サイズ
+    size: i32,  // サイズ（基準の半径の1/2、分裂すると半分になる）
     shape: Vec<(f64, f64)>, // 小惑星の形
     meanRadius: f64,    // 半径の平均値（衝突判定用）
+    mass: f64,  // 質量（輪郭の面積）
     valid: bool // 有効ならtrue
 }
 
 impl Asteroid {
-    pub fn new(size: i32, x: f64, y: f64) -> Self {
-        use rand::{self, Rng};  // ここにselfがないと下の(1)でエラーになる
-        // https://stackoverflow.com/questions/52216426/access-to-external-crates-from-module
-
-        // 小惑星を生成
-        // 位置、速度、形状は乱数で決める
-        let mut shape: Vec<(f64, f64)> = Vec::new();
+    // 乱数はゲーム側から渡す（同じシードなら同じ小惑星になる）
+    // 形の種類はランダムに選ぶ
+    pub fn new(size: i32, x: f64, y: f64, rng: &mut Random) -> Self {
+        let shape = AsteroidShape::random((size * 2) as f64, rng);
+        return Asteroid::withShape(&shape, size, x, y, rng);
+    }
 
-        let mut rng = rand::thread_rng();   // (1)
+    // 形のパラメータを指定して生成
+    // 位置以外（速度、回転）は乱数で決める
+    pub fn withShape(params: &AsteroidShape, size: i32, x: f64, y: f64, rng: &mut Random) -> Self {
+        use rand::Rng;
 
         // 速度を生成
         let vx = rng.gen::<f64>() * 4.0 - 2.0;
         let vy = rng.gen::<f64>() * 4.0 - 2.0;
 
         // 形を生成
-        let r = (size * 2) as f64;
-        let mut rsum: f64 = 0.0;
-        for i in 0..size {
-            let theta = (i as f64) * 3.14159265 * 2.0 / (size as f64);
-            let x: f64 = (r + (rng.gen::<f64>() * 20.0 - 10.0)) * theta.cos();
-            let y: f64 = (r + (rng.gen::<f64>() * 20.0 - 10.0)) * theta.sin();
-            shape.push((x, y));
-            rsum += (x*x+y*y).sqrt();
-        }
-        let meanRadius = rsum/(size as f64); // 半径の平均値
+        let shape = params.generate(rng);
 
         let rot = rng.gen::<f64>() * 3.14159265 * 2.0;
         let drot = (rng.gen::<f64>() - 0.5) * 3.14159265 / 180.0 * 5.0;
 
+        let mut asteroid = Asteroid::fromOutline(shape, size, x, y, vx, vy, drot);
+        asteroid.rot = rot;
+        return asteroid;
+    }
+
+    // 輪郭（中心からの相対座標、回転なし）を指定して生成（破片用）
+    pub fn fromOutline(shape: Vec<(f64, f64)>, size: i32, x: f64, y: f64, vx: f64, vy: f64, drot: f64) -> Self {
+        let rsum: f64 = shape.iter().map(|&(x, y)| (x*x+y*y).sqrt()).sum();
+        let meanRadius = rsum/(shape.len() as f64); // 半径の平均値
+
         return Asteroid {
             x: x,
             y: y,
             vx: vx,
             vy: vy,
-            rot: rot,
+            rot: 0.0,
             drot: drot,
             size: size,
+            mass: polygonArea(&shape).abs(),
             shape: shape,
             meanRadius: meanRadius,
             valid: true
@@ -67,14 +77,8 @@ impl Asteroid {
     }
 
     // ランダムに生成
-    pub fn genRand(_width: u32, _height: u32) -> Self {
-        use rand::{self, Rng};
-
-        // 小惑星を生成
-        // 位置、速度、形状は乱数で決める
-        let mut shape: Vec<(f64, f64)> = Vec::new();
-
-        let mut rng = rand::thread_rng();
+    pub fn genRand(_width: u32, _height: u32, rng: &mut Random) -> Self {
+        use rand::Rng;
 
         // サイズを生成
         let size = (((rng.gen::<f64>() * 15.0) as i32) + 3) * 2;
@@ -83,37 +87,7 @@ impl Asteroid {
         let x = rng.gen::<f64>() * (_width as f64);
         let y = rng.gen::<f64>() * (_height as f64);
 
-        // 速度を生成
-        let vx = rng.gen::<f64>() * 4.0 - 2.0;
-        let vy = rng.gen::<f64>() * 4.0 - 2.0;
-
-        // 形を生成
-        let r = (size * 2) as f64;
-        let mut rsum: f64 = 0.0;
-        for i in 0..size {
-            let theta = (i as f64) * 3.14159265 * 2.0 / (size as f64);
-            let x: f64 = (r + (rng.gen::<f64>() * 20.0 - 10.0)) * theta.cos();
-            let y: f64 = (r + (rng.gen::<f64>() * 20.0 - 10.0)) * theta.sin();
-            shape.push((x, y));
-            rsum += (x*x+y*y).sqrt();
-        }
-        let meanRadius = rsum/(size as f64); // 半径の平均値
-
-        let rot = rng.gen::<f64>() * 3.14159265 * 2.0;
-        let drot = (rng.gen::<f64>() - 0.5) * 3.14159265 / 180.0 * 5.0;
-
-        return Asteroid {
-            x: x,
-            y: y,
-            vx: vx,
-            vy: vy,
-            rot: rot,
-            drot: drot,
-            size: size,
-            shape: shape,
-            meanRadius: meanRadius,
-            valid: true
-        };
+        return Asteroid::new(size, x, y, rng);
     }
 
     pub fn getX(&self) -> f64 {
@@ -124,6 +98,42 @@ impl Asteroid {
         return self.y;
     }
 
+    pub fn getVx(&self) -> f64 {
+        return self.vx;
+    }
+
+    pub fn getVy(&self) -> f64 {
+        return self.vy;
+    }
+
+    pub fn setPos(&mut self, x: f64, y: f64) {
+        self.x = x;
+        self.y = y;
+    }
+
+    pub fn setVelocity(&mut self, vx: f64, vy: f64) {
+        self.vx = vx;
+        self.vy = vy;
+    }
+
+    // 回転速度(rad/フレーム)
+    pub fn getSpin(&self) -> f64 {
+        return self.drot;
+    }
+
+    pub fn setSpin(&mut self, v: f64) {
+        self.drot = v;
+    }
+
+    pub fn getMass(&self) -> f64 {
+        return self.mass;
+    }
+
+    // 衝突判定に使う半径
+    pub fn getRadius(&self) -> f64 {
+        return self.meanRadius;
+    }
+
     pub fn getSize(&self) -> i32 {
         return self.size;
     }
@@ -176,10 +186,15 @@ impl Asteroid {
         }
     }
 
-    pub fn draw(&self, canvas: &mut Canvas<Window>) {
-        canvas.set_draw_color(Color::RGB(255, 255, 255));
+    // 今の向きに回転させた輪郭（中心からの相対座標）
+    pub fn getShape(&self) -> Vec<(f64, f64)> {
+        let c = self.rot.cos();
+        let s = self.rot.sin();
+        return self.shape.iter().map(|&(x, y)| (x * c - y * s, x * s + y * c)).collect();
+    }
 
-        // draw_lines()で隕石を描く
+    // 小惑星の形（閉じた折れ線）
+    pub fn getOutline(&self) -> Vec<Point> {
         let mut ps = Vec::new();
 
         for i in 0..self.shape.len()+1 {
@@ -188,17 +203,82 @@ impl Asteroid {
             let y = self.y + self.shape[j].0 * self.rot.sin() + self.shape[j].1 * self.rot.cos();
             ps.push(Point::new(x as i32, y as i32));
         }
+        return ps;
+    }
+
+    // 質量と半径の平均は輪郭から計算し直す
+    pub fn save(&self, w: &mut SaveWriter) {
+        w.line("asteroid");
+        w.f64(self.x);
+        w.f64(self.y);
+        w.f64(self.vx);
+        w.f64(self.vy);
+        w.f64(self.rot);
+        w.f64(self.drot);
+        w.i32(self.size);
+        w.bool(self.valid);
+        w.u64(self.shape.len() as u64);
+        for &(x, y) in &self.shape {
+            w.f64(x);
+            w.f64(y);
+        }
+    }
+
+    pub fn load(r: &mut SaveReader) -> Result<Self, String> {
+        r.expect("asteroid")?;
+        let (x, y, vx, vy) = (r.f64()?, r.f64()?, r.f64()?, r.f64()?);
+        let (rot, drot) = (r.f64()?, r.f64()?);
+        let size = r.i32()?;
+        let valid = r.bool()?;
+        let n = r.count(1000)?;
+        if n < 3 {
+            return Err(r.error("an asteroid outline needs at least 3 vertices"));
+        }
+        let mut shape = Vec::new();
+        for _ in 0..n {
+            shape.push((r.f64()?, r.f64()?));
+        }
+        let mut asteroid = Asteroid::fromOutline(shape, size, x, y, vx, vy, drot);
+        asteroid.rot = rot;
+        asteroid.valid = valid;
+        return Ok(asteroid);
+    }
 
-        let _ = canvas.draw_lines(ps.as_ref()); // [Point]から&[Point]を生成する
+    // 中心から一番遠い頂点までの距離
+    pub fn getOuterRadius(&self) -> f64 {
+        return self.shape.iter().map(|&(x, y)| (x*x+y*y).sqrt()).fold(0.0, f64::max);
     }
 
-    // 衝突判定
+    // 画面の端にかかっていたら反対側にも描く
+    pub fn draw(&self, canvas: &mut dyn Draw, view: &View, theme: &Theme) {
+        canvas.setDrawColor(theme.asteroid);
+
+        // draw_lines()で隕石を描く
+        let ps = self.getOutline();
+        for (ox, oy) in view.offsets(self.x, self.y, self.getOuterRadius()) {
+            let shifted: Vec<Point> = ps.iter().map(|p| p.offset(ox as i32, oy as i32)).collect();
+            canvas.drawLines(shifted.as_ref()); // [Point]から&[Point]を生成する
+        }
+    }
+
+    // 衝突判定（画面の端をまたいでいても当たる）
     // (tx, ty) ... 衝突判定対象座標
-    pub fn hitTest(&self, tx: f64, ty: f64) -> bool {
-        let tdx = tx - self.x;
-        let tdy = ty - self.y;
+    pub fn hitTest(&self, tx: f64, ty: f64, width: u32, height: u32) -> bool {
+        let tdx = Util::wrapDelta(tx - self.x, width as f64);
+        let tdy = Util::wrapDelta(ty - self.y, height as f64);
         let tr = (tdx*tdx + tdy*tdy).sqrt();
 
         return if tr < self.meanRadius { true } else { false };
     }
 }
+
+// 多角形の符号付き面積（頂点が反時計回りなら正）
+pub fn polygonArea(shape: &[(f64, f64)]) -> f64 {
+    let mut area: f64 = 0.0;
+    for i in 0..shape.len() {
+        let (x1, y1) = shape[i];
+        let (x2, y2) = shape[(i+1) % shape.len()];
+        area += x1 * y2 - x2 * y1;
+    }
+    return area / 2.0;
+}
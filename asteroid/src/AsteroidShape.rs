@@ -0,0 +1,102 @@
+#![allow(non_snake_case)]
+
+use std::f64::consts::PI;
+
+use rand::Rng;
+
+use Random::Random;
+
+const MIN_VERTICES: usize = 5;
+const MAX_VERTICES: usize = 24;
+const DENT_DEPTH: f64 = 0.4;        // へこませる頂点を半径の何割内側に寄せるか
+const MIN_RADIUS_RATIO: f64 = 0.3;  // 頂点が中心にこれ以上近づかないようにする
+
+// ---------------------------------
+//  小惑星の形の種類
+// ---------------------------------
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Archetype {
+    ROUND,      // 丸っこい
+    JAGGED,     // ごつごつ
+    CRATERED,   // ところどころへこんでいる
+    SHARD       // 頂点が少なく尖っている
+}
+
+pub const ARCHETYPES: [Archetype; 4] = [Archetype::ROUND, Archetype::JAGGED, Archetype::CRATERED, Archetype::SHARD];
+
+// ---------------------------------
+//  小惑星の形の生成パラメータ
+//
+//  頂点は中心から見た角度の順に並べ、角度のずれは隣の頂点との間隔の半分未満、
+//  半径は常に正にしているので、輪郭は自己交差しない（中心から星形の多角形）
+// ---------------------------------
+#[derive(Clone, Copy, Debug)]
+pub struct AsteroidShape {
+    pub radius: f64,        // 基準の半径(px)
+    pub vertices: usize,    // 頂点の数
+    pub roughness: f64,     // 半径のばらつき（0.0-1.0、半径に対する割合）
+    pub concavity: f64      // 頂点をへこませる確率（0.0-1.0）
+}
+
+impl AsteroidShape {
+    pub fn new(radius: f64, vertices: usize, roughness: f64, concavity: f64) -> Self {
+        return AsteroidShape {
+            radius: radius,
+            vertices: vertices,
+            roughness: roughness,
+            concavity: concavity
+        };
+    }
+
+    // 種類ごとの頂点数の範囲（最小, 最大）
+    // 大きさによらず同じなので、分裂して小さくなっても同じ種類の形に見える
+    pub fn vertexRange(kind: Archetype) -> (usize, usize) {
+        return match kind {
+            Archetype::ROUND => (10, 14),
+            Archetype::JAGGED => (7, 11),
+            Archetype::CRATERED => (9, 13),
+            Archetype::SHARD => (5, 7)
+        };
+    }
+
+    // 種類と半径から作る（頂点数は種類の範囲から乱数で選ぶ）
+    pub fn archetype(kind: Archetype, radius: f64, rng: &mut Random) -> Self {
+        let (min, max) = AsteroidShape::vertexRange(kind);
+        let vertices = rng.gen_range(min, max + 1);
+        return match kind {
+            Archetype::ROUND => AsteroidShape::new(radius, vertices, 0.1, 0.0),
+            Archetype::JAGGED => AsteroidShape::new(radius, vertices, 0.35, 0.1),
+            Archetype::CRATERED => AsteroidShape::new(radius, vertices, 0.15, 0.3),
+            Archetype::SHARD => AsteroidShape::new(radius, vertices, 0.45, 0.0)
+        };
+    }
+
+    // 種類をランダムに選んで作る
+    pub fn random(radius: f64, rng: &mut Random) -> Self {
+        let kind = ARCHETYPES[rng.gen_range(0, ARCHETYPES.len())];
+        return AsteroidShape::archetype(kind, radius, rng);
+    }
+
+    // 輪郭の頂点（中心からの相対座標）を生成
+    pub fn generate(&self, rng: &mut Random) -> Vec<(f64, f64)> {
+        let n = self.vertices.max(MIN_VERTICES).min(MAX_VERTICES);
+        let roughness = self.roughness.max(0.0).min(1.0);
+        let concavity = self.concavity.max(0.0).min(1.0);
+        let step = PI * 2.0 / (n as f64);
+
+        let mut shape: Vec<(f64, f64)> = Vec::new();
+        for i in 0..n {
+            // 角度は自分の区間の中だけでずらす
+            let theta = ((i as f64) + (rng.gen::<f64>() - 0.5) * 0.8) * step;
+
+            let mut r = self.radius * (1.0 + (rng.gen::<f64>() * 2.0 - 1.0) * roughness);
+            if rng.gen::<f64>() < concavity {
+                r -= self.radius * DENT_DEPTH;
+            }
+            let r = r.max(self.radius * MIN_RADIUS_RATIO);
+
+            shape.push((r * theta.cos(), r * theta.sin()));
+        }
+        return shape;
+    }
+}
@@ -1,10 +1,12 @@
 #![allow(non_snake_case)]
 
-use sdl2::video::Window;
-use sdl2::render::Canvas;
-use sdl2::pixels::Color;
 use sdl2::rect::Point;
 
+use Draw::Draw;
+use Camera::View;
+use Theme::Theme;
+use SaveGame::{SaveWriter, SaveReader};
+
 // ---------------------------------
 //  弾丸
 // ---------------------------------
@@ -14,16 +16,20 @@ pub struct Bullet {
     y: f64,
     vx: f64,    // 速度ベクトル
     vy: f64,    // 速度ベクトル
+    owner: usize,   // 撃ったプレイヤーの番号
+    life: i32,  // 残りフレーム数（0になったら消える）
     valid: bool // 有効ならtrue
 }
 
 impl Bullet {
-    pub fn new(_x: f64, _y: f64, _vx: f64, _vy: f64) -> Self {
+    pub fn new(_x: f64, _y: f64, _vx: f64, _vy: f64, _life: i32) -> Self {
         return Bullet {
             x: _x,
             y: _y,
             vx: _vx,
             vy: _vy,
+            owner: 0,
+            life: _life,
             valid: true
         };
     }
@@ -60,6 +66,22 @@ impl Bullet {
         self.vy = v;
     }
 
+    pub fn getOwner(&self) -> usize {
+        return self.owner;
+    }
+
+    pub fn setOwner(&mut self, v: usize) -> () {
+        self.owner = v;
+    }
+
+    // 1フレーム分年を取らせる（寿命が尽きたら無効にする）
+    pub fn age(&mut self) {
+        self.life -= 1;
+        if self.life <= 0 {
+            self.valid = false;
+        }
+    }
+
     pub fn getValid(&self) -> bool {
         return self.valid;
     }
@@ -68,11 +90,32 @@ impl Bullet {
         self.valid = v;
     }
 
-    pub fn draw(&self, canvas: &mut Canvas<Window>) {
-        canvas.set_draw_color(Color::RGB(255, 255, 255));
+    pub fn save(&self, w: &mut SaveWriter) {
+        w.line("bullet");
+        w.f64(self.x);
+        w.f64(self.y);
+        w.f64(self.vx);
+        w.f64(self.vy);
+        w.u64(self.owner as u64);
+        w.bool(self.valid);
+        w.i32(self.life);
+    }
+
+    pub fn load(r: &mut SaveReader) -> Result<Self, String> {
+        r.expect("bullet")?;
+        let mut bullet = Bullet::new(r.f64()?, r.f64()?, r.f64()?, r.f64()?, 0);
+        bullet.owner = r.u64()? as usize;
+        bullet.valid = r.bool()?;
+        bullet.life = r.i32()?;
+        return Ok(bullet);
+    }
+
+    pub fn draw(&self, canvas: &mut dyn Draw, view: &View, theme: &Theme) {
+        canvas.setDrawColor(theme.bullet);
 
-        let p = Point::new(self.x as i32, self.y as i32);
+        let (sx, sy) = view.toScreen(self.x, self.y);
+        let p = Point::new(sx as i32, sy as i32);
 
-        let _ = canvas.draw_point(p);
+        canvas.drawPoint(p);
     }
 }
@@ -0,0 +1,121 @@
+#![allow(non_snake_case)]
+
+use Util;
+
+const SMOOTHING: f64 = 0.1;     // 1フレームで目標に近づく割合
+
+// ---------------------------------
+//  カメラ（広い宇宙で宇宙船を追いかける）
+//  位置は画面の中心に映る世界座標
+// ---------------------------------
+#[derive(Clone, Copy)]
+pub struct Camera {
+    x: f64,
+    y: f64,
+    ready: bool     // falseなら次のfollow()で目標にすぐ合わせる
+}
+
+impl Camera {
+    pub fn new() -> Self {
+        return Camera {
+            x: 0.0,
+            y: 0.0,
+            ready: false
+        };
+    }
+
+    pub fn getX(&self) -> f64 {
+        return self.x;
+    }
+
+    pub fn getY(&self) -> f64 {
+        return self.y;
+    }
+
+    // 次のfollow()で目標にすぐ合わせる（ゲーム開始、宇宙船の出現）
+    pub fn reset(&mut self) {
+        self.ready = false;
+    }
+
+    // 目標(tx, ty)になめらかに近づく
+    // 世界の端はつながっているので、近い方の向きに動く
+    pub fn follow(&mut self, tx: f64, ty: f64, worldWidth: u32, worldHeight: u32) {
+        let wf = worldWidth as f64;
+        let hf = worldHeight as f64;
+        if !self.ready {
+            self.x = tx;
+            self.y = ty;
+            self.ready = true;
+            return;
+        }
+        self.x = Util::wrap(self.x + Util::wrapDelta(tx - self.x, wf) * SMOOTHING, wf);
+        self.y = Util::wrap(self.y + Util::wrapDelta(ty - self.y, hf) * SMOOTHING, hf);
+    }
+}
+
+// ---------------------------------
+//  世界座標から画面座標への変換
+//  世界が画面と同じ大きさなら、世界座標がそのまま画面座標になる
+// ---------------------------------
+pub struct View {
+    pub x: f64,     // 画面の中心に映る世界座標
+    pub y: f64,
+    pub screenWidth: u32,
+    pub screenHeight: u32,
+    pub worldWidth: u32,
+    pub worldHeight: u32
+}
+
+impl View {
+    // 画面全体が世界（カメラは動かない）
+    pub fn fixed(width: u32, height: u32) -> Self {
+        return View {
+            x: (width as f64) / 2.0,
+            y: (height as f64) / 2.0,
+            screenWidth: width,
+            screenHeight: height,
+            worldWidth: width,
+            worldHeight: height
+        };
+    }
+
+    // 世界座標(x, y)を画面座標にする（世界の端をまたぐときは近い方）
+    pub fn toScreen(&self, x: f64, y: f64) -> (f64, f64) {
+        let sx = Util::wrapDelta(x - self.x, self.worldWidth as f64) + (self.screenWidth as f64) / 2.0;
+        let sy = Util::wrapDelta(y - self.y, self.worldHeight as f64) + (self.screenHeight as f64) / 2.0;
+        return (sx, sy);
+    }
+
+    // 中心(x, y)、半径rの物体を描くときに世界座標に足すずらし量
+    // 画面の端にかかっていたら、世界の反対側に映る分も加える（角では最大4つ）
+    pub fn offsets(&self, x: f64, y: f64, r: f64) -> Vec<(f64, f64)> {
+        let (sx, sy) = self.toScreen(x, y);
+        let sw = self.screenWidth as f64;
+        let sh = self.screenHeight as f64;
+        let ww = self.worldWidth as f64;
+        let wh = self.worldHeight as f64;
+
+        let mut xs = vec![sx - x];
+        if sx - r < 0.0 {
+            xs.push(sx - x + ww);
+        }
+        if sx + r > sw {
+            xs.push(sx - x - ww);
+        }
+        let mut ys = vec![sy - y];
+        if sy - r < 0.0 {
+            ys.push(sy - y + wh);
+        }
+        if sy + r > sh {
+            ys.push(sy - y - wh);
+        }
+
+        let mut offsets = Vec::new();
+        for &oy in &ys {
+            for &ox in &xs {
+                offsets.push((ox, oy));
+            }
+        }
+        return offsets;
+    }
+}
@@ -0,0 +1,114 @@
+#![allow(non_snake_case)]
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use Scene::Settings;
+use Tuning::{TuningTable, Difficulty};
+use Lang::Language;
+use Theme::ThemeKind;
+
+const CONFIG_FILE: &str = "config.txt";
+
+// ---------------------------------
+//  ユーザーごとの設定ファイル
+//    Windows   : %APPDATA%\asteroid\config.txt
+//    それ以外  : $XDG_CONFIG_HOME/asteroid/config.txt （なければ ~/.config/asteroid/config.txt）
+//  1行に "名前 値" の形で保存する
+// ---------------------------------
+pub fn configPath() -> PathBuf {
+    let base = env::var_os("APPDATA")
+        .or_else(|| env::var_os("XDG_CONFIG_HOME"))
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")));
+    return match base {
+        Some(dir) => dir.join("asteroid").join(CONFIG_FILE),
+        None => PathBuf::from(CONFIG_FILE)
+    };
+}
+
+// 設定ファイルを読んでsettingsに反映する（ファイルがなければそのまま）
+// 難易度を先に反映してから、個別に変えた宇宙船と小惑星の個数を読む
+pub fn load(settings: &mut Settings, tunings: &TuningTable) {
+    let text = match fs::read_to_string(configPath()) {
+        Ok(text) => text,
+        Err(_) => { return; }
+    };
+    let entries: Vec<(&str, &str)> = text.lines()
+        .filter_map(|line| {
+            let mut it = line.split_whitespace();
+            match (it.next(), it.next()) {
+                (Some(k), Some(v)) => Some((k, v)),
+                _ => None
+            }
+        })
+        .collect();
+
+    for &(key, value) in &entries {
+        if key == "difficulty" {
+            if let Some(d) = Difficulty::fromName(value) {
+                settings.setDifficulty(d, tunings);
+            }
+        }
+    }
+
+    // 読めない値は無視してデフォルトのまま
+    for &(key, value) in &entries {
+        let n = value.parse::<u32>().ok();
+        let b = n.map(|n| n != 0);
+        match key {
+            "ships" => if let Some(n) = n { settings.nShips = (n as i32).max(1).min(9); },
+            "asteroids" => if let Some(n) = n { settings.nAsteroids = (n as i32).max(1).min(30); },
+            "friendly_fire" => if let Some(b) = b { settings.friendlyFire = b; },
+            "asteroid_collisions" => if let Some(b) = b { settings.asteroidCollisions = b; },
+            "bounciness" => if let Some(n) = n { settings.restitution = (n.min(100) as f64) / 100.0; },
+            "cut_fragments" => if let Some(b) = b { settings.cutFragments = b; },
+            "open_space" => if let Some(b) = b { settings.openSpace = b; },
+            "volume" => if let Some(n) = n { settings.volume = n.min(100); },
+            "fullscreen" => if let Some(b) = b { settings.fullscreen = b; },
+            "vector_fx" => if let Some(b) = b { settings.vectorFx = b; },
+            "swap_controls" => if let Some(b) = b { settings.swapControls = b; },
+            "language" => if let Some(l) = Language::fromCode(value) { settings.language = l; },
+            "theme" => if let Some(t) = ThemeKind::fromCode(value) { settings.theme = t; },
+            "reduced_flashing" => if let Some(b) = b { settings.reducedFlashing = b; },
+            "large_text" => if let Some(b) = b { settings.largeText = b; },
+            "game_speed" => if let Some(n) = n { settings.gameSpeed = n.max(50).min(100); },
+            "net_latency_ms" => if let Some(n) = n { settings.netLatencyMs = n.min(500); },
+            "net_loss_percent" => if let Some(n) = n { settings.netLossPercent = n.min(50); },
+            _ => {}
+        }
+    }
+}
+
+pub fn save(settings: &Settings) {
+    let path = configPath();
+    let flag = |b: bool| if b { 1 } else { 0 };
+    let mut text = String::new();
+    text.push_str(&format!("difficulty {}\n", settings.difficulty.getName()));
+    text.push_str(&format!("ships {}\n", settings.nShips));
+    text.push_str(&format!("asteroids {}\n", settings.nAsteroids));
+    text.push_str(&format!("friendly_fire {}\n", flag(settings.friendlyFire)));
+    text.push_str(&format!("asteroid_collisions {}\n", flag(settings.asteroidCollisions)));
+    text.push_str(&format!("bounciness {}\n", (settings.restitution * 100.0).round()));
+    text.push_str(&format!("cut_fragments {}\n", flag(settings.cutFragments)));
+    text.push_str(&format!("open_space {}\n", flag(settings.openSpace)));
+    text.push_str(&format!("volume {}\n", settings.volume));
+    text.push_str(&format!("fullscreen {}\n", flag(settings.fullscreen)));
+    text.push_str(&format!("vector_fx {}\n", flag(settings.vectorFx)));
+    text.push_str(&format!("swap_controls {}\n", flag(settings.swapControls)));
+    text.push_str(&format!("language {}\n", settings.language.getCode()));
+    text.push_str(&format!("theme {}\n", settings.theme.getCode()));
+    text.push_str(&format!("reduced_flashing {}\n", flag(settings.reducedFlashing)));
+    text.push_str(&format!("large_text {}\n", flag(settings.largeText)));
+    text.push_str(&format!("game_speed {}\n", settings.gameSpeed));
+    text.push_str(&format!("net_latency_ms {}\n", settings.netLatencyMs));
+    text.push_str(&format!("net_loss_percent {}\n", settings.netLossPercent));
+
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    if let Err(e) = fs::write(&path, text) {
+        println!("failed to save settings to {}: {}", path.display(), e);
+    }
+}
@@ -0,0 +1,158 @@
+#![allow(non_snake_case)]
+
+use sdl2::pixels::Color;
+use sdl2::rect::{Point, Rect};
+use sdl2::render::BlendMode;
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use Game::Game;
+use Draw::Draw;
+
+const HISTORY: usize = 120;         // グラフに出すフレーム数
+const GRAPH_HEIGHT: i32 = 80;       // グラフの高さ(px)
+const GRAPH_MAX_MS: f64 = 50.0;     // グラフの上端のフレーム時間(ms)
+const TARGET_MS: f64 = 1000.0 / 60.0;
+const VELOCITY_SCALE: f64 = 10.0;   // 速度ベクトルの表示倍率
+
+// ---------------------------------
+//  デバッグ表示（F3で切り替え）
+//  FPS、フレーム時間のグラフ、オブジェクト数、当たり判定の円と速度
+// ---------------------------------
+pub struct DebugOverlay {
+    visible: bool,
+    frameTimes: VecDeque<f64>,  // 最近のフレーム時間(ms)
+    last: Option<Instant>
+}
+
+impl DebugOverlay {
+    pub fn new() -> Self {
+        return DebugOverlay {
+            visible: false,
+            frameTimes: VecDeque::new(),
+            last: None
+        };
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    pub fn isVisible(&self) -> bool {
+        return self.visible;
+    }
+
+    // メインループで1フレームに1回呼ぶ
+    pub fn frame(&mut self) {
+        let now = Instant::now();
+        if let Some(last) = self.last {
+            let d = now.duration_since(last);
+            let ms = d.as_secs() as f64 * 1000.0 + d.subsec_nanos() as f64 / 1_000_000.0;
+            self.frameTimes.push_back(ms);
+            if self.frameTimes.len() > HISTORY {
+                self.frameTimes.pop_front();
+            }
+        }
+        self.last = Some(now);
+    }
+
+    pub fn getFps(&self) -> f64 {
+        if self.frameTimes.is_empty() {
+            return 0.0;
+        }
+        let total: f64 = self.frameTimes.iter().sum();
+        return if total > 0.0 { 1000.0 * (self.frameTimes.len() as f64) / total } else { 0.0 };
+    }
+
+    pub fn draw(&self, canvas: &mut dyn Draw, game: Option<&Game>, width: u32, height: u32) {
+        if !self.visible {
+            return;
+        }
+
+        let maxMs = self.frameTimes.iter().cloned().fold(0.0, f64::max);
+        let lastMs = self.frameTimes.back().cloned().unwrap_or(0.0);
+        let mut lines: Vec<String> = Vec::new();
+        lines.push(format!("FPS: {:.1}", self.getFps()));
+        lines.push(format!("Frame: {:.1} ms (max {:.1})", lastMs, maxMs));
+
+        if let Some(game) = game {
+            let bullets = game.getBullets();
+            let asteroids = game.getAsteroids();
+            let liveBullets = bullets.iter().filter(|b| b.getValid()).count();
+            let liveAsteroids = asteroids.iter().filter(|a| a.getValid()).count();
+            lines.push(format!("State: {:?}", game.getState()));
+            lines.push(format!("Bullets: {} valid / {} dead", liveBullets, bullets.len() - liveBullets));
+            lines.push(format!("Asteroids: {} valid / {} dead", liveAsteroids, asteroids.len() - liveAsteroids));
+
+            self.drawShapes(canvas, game, width, height);
+        }
+
+        for (i, line) in lines.iter().enumerate() {
+            canvas.textOut(line, 255, 255, 0, 10, 90 + (i as i32) * 24, 10, 20);
+        }
+        self.drawGraph(canvas, 10, (height as i32) - GRAPH_HEIGHT - 10, (width as i32).min(HISTORY as i32 * 2));
+    }
+
+    // 当たり判定の円と速度ベクトル（ゲームと同じく世界座標を画面座標にして描く）
+    fn drawShapes(&self, canvas: &mut dyn Draw, game: &Game, width: u32, height: u32) {
+        let view = game.getView(width, height);
+        for a in game.getAsteroids() {
+            if !a.getValid() {
+                continue;
+            }
+            // 当たり判定は画面の端をまたぐので、円も反対側に描く
+            canvas.setDrawColor(Color::RGB(255, 0, 255));
+            for (ox, oy) in view.offsets(a.getX(), a.getY(), a.getRadius()) {
+                canvas.drawCircle(a.getX() + ox, a.getY() + oy, a.getRadius());
+            }
+            let (ax, ay) = view.toScreen(a.getX(), a.getY());
+            canvas.setDrawColor(Color::RGB(0, 255, 0));
+            canvas.drawLines(&[
+                Point::new(ax as i32, ay as i32),
+                Point::new((ax + a.getVx() * VELOCITY_SCALE) as i32, (ay + a.getVy() * VELOCITY_SCALE) as i32)
+            ]);
+        }
+
+        for i in 0..game.getPlayerCount() {
+            if let Some(s) = game.getShip(i) {
+                let (sx, sy) = view.toScreen(s.getX(), s.getY());
+                let x = sx as i32;
+                let y = sy as i32;
+                // 弾との判定は円、小惑星との判定は中心の1点
+                canvas.setDrawColor(Color::RGB(0, 255, 255));
+                canvas.drawCircle(sx, sy, s.getRadius());
+                canvas.drawLines(&[Point::new(x - 4, y), Point::new(x + 4, y)]);
+                canvas.drawLines(&[Point::new(x, y - 4), Point::new(x, y + 4)]);
+                canvas.setDrawColor(Color::RGB(0, 255, 0));
+                canvas.drawLines(&[
+                    Point::new(x, y),
+                    Point::new((sx + s.getVx() * VELOCITY_SCALE) as i32, (sy + s.getVy() * VELOCITY_SCALE) as i32)
+                ]);
+            }
+        }
+    }
+
+    // フレーム時間のグラフ（赤線が60fps）
+    fn drawGraph(&self, canvas: &mut dyn Draw, x: i32, y: i32, w: i32) {
+        canvas.setBlendMode(BlendMode::Blend);
+        canvas.setDrawColor(Color::RGBA(0, 0, 0, 160));
+        canvas.fillRect(Some(Rect::new(x, y, w as u32, GRAPH_HEIGHT as u32)));
+        canvas.setBlendMode(BlendMode::None);
+
+        let barW = (w / HISTORY as i32).max(1);
+        for (i, ms) in self.frameTimes.iter().enumerate() {
+            let h = ((ms / GRAPH_MAX_MS).min(1.0) * GRAPH_HEIGHT as f64) as i32;
+            let bx = x + (i as i32) * barW;
+            if *ms > TARGET_MS * 1.5 {
+                canvas.setDrawColor(Color::RGB(255, 128, 0));
+            } else {
+                canvas.setDrawColor(Color::RGB(0, 200, 0));
+            }
+            canvas.drawLines(&[Point::new(bx, y + GRAPH_HEIGHT - 1), Point::new(bx, y + GRAPH_HEIGHT - 1 - h)]);
+        }
+
+        let ty = y + GRAPH_HEIGHT - 1 - (TARGET_MS / GRAPH_MAX_MS * GRAPH_HEIGHT as f64) as i32;
+        canvas.setDrawColor(Color::RGB(255, 0, 0));
+        canvas.drawLines(&[Point::new(x, ty), Point::new(x + w - 1, ty)]);
+    }
+}
@@ -0,0 +1,121 @@
+#![allow(non_snake_case)]
+
+use std::f64::consts::PI;
+
+use sdl2::render::{Canvas, RenderTarget, TextureCreator, BlendMode};
+use sdl2::surface::Surface;
+use sdl2::pixels::{Color, PixelFormatEnum};
+use sdl2::rect::{Point, Rect};
+use sdl2::ttf::Font;
+
+use Util;
+
+// ---------------------------------
+//  描画先
+//  ゲームの描画はすべてこれを通して行うので、
+//  ウィンドウでもオフスクリーンのサーフェスでも同じコードで描ける
+// ---------------------------------
+pub trait Draw {
+    // 描画先の大きさ
+    fn size(&self) -> (u32, u32);
+
+    fn setDrawColor(&mut self, c: Color);
+
+    fn setBlendMode(&mut self, mode: BlendMode);
+
+    fn clear(&mut self);
+
+    fn drawPoint(&mut self, p: Point);
+
+    fn drawLines(&mut self, ps: &[Point]);
+
+    // rectがNoneなら全体を塗る
+    fn fillRect(&mut self, rect: Option<Rect>);
+
+    // テキストを描く（フォントがなければ何もしない）
+    // w, h ... 1文字分の幅と高さ
+    fn textOut(&mut self, text: &str, r: u8, g: u8, b: u8, x: i32, y: i32, w: u32, h: u32);
+
+    // 色をColorで渡す版
+    fn textOutColor(&mut self, text: &str, c: Color, x: i32, y: i32, w: u32, h: u32) {
+        self.textOut(text, c.r, c.g, c.b, x, y, w, h);
+    }
+
+    // 円を描く（折れ線で近似）
+    fn drawCircle(&mut self, x: f64, y: f64, r: f64) {
+        let n = 32;
+        let ps: Vec<Point> = (0..n+1).map(|i| {
+            let t = PI * 2.0 * (i as f64) / (n as f64);
+            Point::new((x + r * t.cos()) as i32, (y + r * t.sin()) as i32)
+        }).collect();
+        self.drawLines(&ps);
+    }
+}
+
+// ---------------------------------
+//  Canvasへの描画
+//  Canvas<Window>でもCanvas<Surface>でもよい
+//  texture_creatorは同じCanvasから作ったものを渡すこと
+// ---------------------------------
+pub struct CanvasDraw<'a, 'f: 'a, T: RenderTarget + 'a, C: 'a> {
+    canvas: &'a mut Canvas<T>,
+    texture_creator: &'a TextureCreator<C>,
+    font: Option<&'a Font<'f, 'f>>
+}
+
+impl<'a, 'f, T: RenderTarget, C> CanvasDraw<'a, 'f, T, C> {
+    pub fn new(canvas: &'a mut Canvas<T>, texture_creator: &'a TextureCreator<C>, font: Option<&'a Font<'f, 'f>>) -> Self {
+        return CanvasDraw {
+            canvas: canvas,
+            texture_creator: texture_creator,
+            font: font
+        };
+    }
+
+    pub fn getCanvas(&mut self) -> &mut Canvas<T> {
+        return self.canvas;
+    }
+}
+
+impl<'a, 'f, T: RenderTarget, C> Draw for CanvasDraw<'a, 'f, T, C> {
+    fn size(&self) -> (u32, u32) {
+        return self.canvas.output_size().unwrap_or((0, 0));
+    }
+
+    fn setDrawColor(&mut self, c: Color) {
+        self.canvas.set_draw_color(c);
+    }
+
+    fn setBlendMode(&mut self, mode: BlendMode) {
+        self.canvas.set_blend_mode(mode);
+    }
+
+    fn clear(&mut self) {
+        self.canvas.clear();
+    }
+
+    fn drawPoint(&mut self, p: Point) {
+        let _ = self.canvas.draw_point(p);
+    }
+
+    fn drawLines(&mut self, ps: &[Point]) {
+        let _ = self.canvas.draw_lines(ps);
+    }
+
+    fn fillRect(&mut self, rect: Option<Rect>) {
+        let _ = self.canvas.fill_rect(rect);
+    }
+
+    fn textOut(&mut self, text: &str, r: u8, g: u8, b: u8, x: i32, y: i32, w: u32, h: u32) {
+        if let Some(font) = self.font {
+            Util::textOut(self.canvas, font, self.texture_creator, text, r, g, b, x, y, w, h);
+        }
+    }
+}
+
+// オフスクリーン描画用のソフトウェアCanvasを作る
+// （ウィンドウなしでスクリーンショット、テスト、録画に使う）
+pub fn newSoftwareCanvas(width: u32, height: u32) -> Result<Canvas<Surface<'static>>, String> {
+    let surface = Surface::new(width, height, PixelFormatEnum::RGB888)?;
+    return surface.into_canvas();
+}
@@ -0,0 +1,318 @@
+#![allow(non_snake_case)]
+
+use sdl2::surface::Surface;
+use sdl2::render::Canvas;
+use sdl2::pixels::PixelFormatEnum;
+
+use Game::{Game, GameState, PlayMode};
+use Input::PlayerInput;
+use Scene::Settings;
+use Draw;
+use Draw::CanvasDraw;
+use Util::wrapDelta;
+
+pub const N_ACTIONS: usize = 64;    // 行動の数（PlayerInputの6ビットの組み合わせ）
+const VECTOR_SHIP: usize = 6;       // 観測ベクトルの宇宙船部分の長さ
+const VECTOR_ASTEROID: usize = 6;   // 観測ベクトルの小惑星1個分の長さ
+const DEATH_REWARD: f64 = -50.0;    // 宇宙船を失った時の報酬
+
+// ---------------------------------
+//  強化学習用の環境の設定
+// ---------------------------------
+#[derive(Clone)]
+pub struct EnvConfig {
+    pub width: u32,         // 画面（ゲーム空間）の大きさ
+    pub height: u32,
+    pub settings: Settings, // 宇宙船の個数、小惑星の個数など
+    pub frameSkip: u32,     // 1回のstep()で進めるフレーム数
+    pub nearest: usize,     // 観測ベクトルに入れる近い小惑星の個数
+    pub pixels: bool,       // trueなら画像の観測も作る
+    pub pixelScale: u32,    // 画像の縮小率（2なら縦横1/2、0は1として扱う）
+    pub maxSteps: u32       // これだけstepしたら終わり（0なら無制限）
+}
+
+impl EnvConfig {
+    pub fn new() -> Self {
+        return EnvConfig {
+            width: 1280,
+            height: 900,
+            settings: Settings::new(),
+            frameSkip: 4,
+            nearest: 8,
+            pixels: false,
+            pixelScale: 4,
+            maxSteps: 0
+        };
+    }
+}
+
+// ---------------------------------
+//  観測
+// ---------------------------------
+pub struct Observation {
+    // [宇宙船 x, y, vx, vy, cos(向き), sin(向き)] + 近い順に小惑星 [dx, dy, vx, vy, 半径, 距離]*nearest
+    // 位置は画面の大きさ、宇宙船の速度は最大の速さで割って正規化、宇宙船がいない時と小惑星が足りない分は0
+    pub vector: Vec<f32>,
+    // グレースケール画像（pixelWidth * pixelHeight、1画素1バイト）
+    pub pixels: Option<Vec<u8>>,
+    pub pixelWidth: u32,
+    pub pixelHeight: u32
+}
+
+// ---------------------------------
+//  Gym風の強化学習環境
+//  ウィンドウを作らずにゲームを進める
+//
+//    let mut env = Env::new(EnvConfig::new());
+//    let mut obs = env.reset(seed);
+//    loop {
+//        let (obs, reward, done) = env.step(action);
+//        if done { break; }
+//    }
+// ---------------------------------
+pub struct Env {
+    config: EnvConfig,
+    game: Game,
+    steps: u32,
+    canvas: Option<Canvas<Surface<'static>>>    // 画像の観測用（ソフトウェア描画）
+}
+
+impl Env {
+    pub fn new(config: EnvConfig) -> Self {
+        let canvas = if config.pixels {
+            let w = config.width / config.pixelScale.max(1);
+            let h = config.height / config.pixelScale.max(1);
+            let mut canvas = Draw::newSoftwareCanvas(w, h).expect("failed to create software canvas");
+            let scale = 1.0 / (config.pixelScale.max(1) as f32);
+            let _ = canvas.set_scale(scale, scale);     // ゲームの座標のまま縮小して描く
+            Some(canvas)
+        } else {
+            None
+        };
+
+        let game = Game::new(&config.settings, PlayMode::SINGLE, 0);
+        return Env {
+            config: config,
+            game: game,
+            steps: 0,
+            canvas: canvas
+        };
+    }
+
+    pub fn getGame(&self) -> &Game {
+        return &self.game;
+    }
+
+    // 新しいゲームを始めて最初の観測を返す
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        self.game = Game::new(&self.config.settings, PlayMode::SINGLE, seed);
+        self.game.update(self.config.width, self.config.height);    // START -> PLAY
+        self.steps = 0;
+        return self.observe();
+    }
+
+    // action (0..N_ACTIONS) を frameSkip フレーム続けて (観測, 報酬, 終了) を返す
+    pub fn step(&mut self, action: usize) -> (Observation, f64, bool) {
+        let input = PlayerInput::fromBits((action % N_ACTIONS) as u8);
+        let mut reward = 0.0;
+
+        for i in 0..self.config.frameSkip.max(1) {
+            let score = self.game.getScore(0);
+            let ships = self.game.getShipsLeft(0);
+
+            // 発射は押した瞬間だけ有効なので、最初のフレームだけ押す
+            let mut frameInput = input;
+            frameInput.fire = input.fire && i == 0;
+            self.game.setInput(0, frameInput);
+            self.game.update(self.config.width, self.config.height);
+
+            reward += (self.game.getScore(0) - score) as f64;
+            if self.game.getShipsLeft(0) < ships {
+                reward += DEATH_REWARD;
+            }
+            if self.isDone() {
+                break;
+            }
+        }
+
+        self.steps += 1;
+        let done = self.isDone() || (self.config.maxSteps > 0 && self.steps >= self.config.maxSteps);
+        return (self.observe(), reward, done);
+    }
+
+    fn isDone(&self) -> bool {
+        return match *self.game.getState() {
+            GameState::GAMEOVER => true,
+            _ => false
+        };
+    }
+
+    fn observe(&mut self) -> Observation {
+        let vector = self.observeVector();
+        let w = self.config.width / self.config.pixelScale.max(1);
+        let h = self.config.height / self.config.pixelScale.max(1);
+        let pixels = if self.canvas.is_some() { Some(self.observePixels()) } else { None };
+        return Observation {
+            vector: vector,
+            pixels: pixels,
+            pixelWidth: w,
+            pixelHeight: h
+        };
+    }
+
+    fn observeVector(&self) -> Vec<f32> {
+        let (ww, wh) = self.game.getWorldSize(self.config.width, self.config.height);
+        let wf = ww as f64;
+        let hf = wh as f64;
+        let maxSpeed = self.game.getTuning().shipMaxSpeed;
+        let mut v: Vec<f32> = Vec::new();
+
+        let (sx, sy) = match self.game.getShip(0) {
+            Some(s) => {
+                v.extend_from_slice(&[
+                    (s.getX() / wf) as f32,
+                    (s.getY() / hf) as f32,
+                    (s.getVx() / maxSpeed) as f32,
+                    (s.getVy() / maxSpeed) as f32,
+                    s.getRotation().cos() as f32,
+                    s.getRotation().sin() as f32
+                ]);
+                (s.getX(), s.getY())
+            }
+            None => {
+                v.extend_from_slice(&[0.0; VECTOR_SHIP]);
+                (wf / 2.0, hf / 2.0)
+            }
+        };
+
+        // 宇宙船から近い順に並べる
+        let mut rocks: Vec<(f64, f64, f64, f64, f64, f64)> = Vec::new();
+        for a in self.game.getAsteroids() {
+            if !a.getValid() {
+                continue;
+            }
+            let dx = wrapDelta(a.getX() - sx, wf);
+            let dy = wrapDelta(a.getY() - sy, hf);
+            let dist = (dx*dx + dy*dy).sqrt();
+            rocks.push((dx, dy, a.getVx(), a.getVy(), a.getRadius(), dist));
+        }
+        rocks.sort_by(|a, b| a.5.partial_cmp(&b.5).unwrap_or(::std::cmp::Ordering::Equal));
+
+        for i in 0..self.config.nearest {
+            match rocks.get(i) {
+                Some(r) => {
+                    v.extend_from_slice(&[
+                        (r.0 / wf) as f32,
+                        (r.1 / hf) as f32,
+                        (r.2 / 2.0) as f32,
+                        (r.3 / 2.0) as f32,
+                        (r.4 / 100.0) as f32,
+                        (r.5 / wf) as f32
+                    ]);
+                }
+                None => {
+                    v.extend_from_slice(&[0.0; VECTOR_ASTEROID]);
+                }
+            }
+        }
+        return v;
+    }
+
+    // ソフトウェア描画で画面を作ってグレースケールで返す
+    // （テキストはフォントがないので描かない）
+    fn observePixels(&mut self) -> Vec<u8> {
+        let game = &self.game;
+        let canvas = match self.canvas {
+            Some(ref mut c) => c,
+            None => { return Vec::new(); }
+        };
+
+        let texture_creator = canvas.texture_creator();
+        {
+            let mut draw = CanvasDraw::new(canvas, &texture_creator, None);
+            game.draw(&mut draw, self.config.width, self.config.height);
+        }
+
+        let rgb = canvas.read_pixels(None, PixelFormatEnum::RGB24).unwrap_or_default();
+        return rgb.chunks(3).map(|c| ((c[0] as u32 + c[1] as u32 + c[2] as u32) / 3) as u8).collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIRE: usize = 8;  // PlayerInput::toBits()の発射のビット
+
+    fn vectorLen(config: &EnvConfig) -> usize {
+        return VECTOR_SHIP + VECTOR_ASTEROID * config.nearest;
+    }
+
+    #[test]
+    fn observation_has_the_configured_size() {
+        let mut config = EnvConfig::new();
+        config.nearest = 3;
+        let mut env = Env::new(config.clone());
+        let obs = env.reset(1);
+        assert_eq!(obs.vector.len(), vectorLen(&config));
+        assert!(obs.pixels.is_none());
+        assert_eq!((obs.pixelWidth, obs.pixelHeight), (1280 / 4, 900 / 4));
+        let (obs, _, _) = env.step(0);
+        assert_eq!(obs.vector.len(), vectorLen(&config));
+    }
+
+    #[test]
+    fn zero_pixel_scale_is_treated_as_one() {
+        let mut config = EnvConfig::new();
+        config.pixelScale = 0;
+        let mut env = Env::new(config);
+        let obs = env.reset(1);
+        assert_eq!((obs.pixelWidth, obs.pixelHeight), (1280, 900));
+    }
+
+    #[test]
+    fn reset_starts_a_fresh_game() {
+        let mut env = Env::new(EnvConfig::new());
+        env.reset(1);
+        for _ in 0..50 {
+            env.step(FIRE);
+        }
+        let obs = env.reset(1);
+        assert_eq!(env.getGame().getScore(0), 0);
+        assert_eq!(env.getGame().getShipsLeft(0), env.getGame().getTuning().ships);
+        assert!(match *env.getGame().getState() { GameState::PLAY => true, _ => false });
+        assert_eq!(obs.vector, Env::new(EnvConfig::new()).reset(1).vector);
+    }
+
+    #[test]
+    fn step_ends_when_max_steps_is_reached() {
+        let mut config = EnvConfig::new();
+        config.maxSteps = 5;
+        let mut env = Env::new(config);
+        env.reset(1);
+        for i in 0..5 {
+            let (_, _, done) = env.step(0);
+            assert_eq!(done, i == 4);
+        }
+    }
+
+    #[test]
+    fn same_seed_and_actions_give_the_same_episode() {
+        let run = |seed: u64| {
+            let mut env = Env::new(EnvConfig::new());
+            let mut trace = vec![env.reset(seed).vector];
+            let mut rewards = Vec::new();
+            for t in 0..300 {
+                let (obs, reward, done) = env.step((t * 7) % N_ACTIONS);
+                trace.push(obs.vector);
+                rewards.push(reward);
+                if done {
+                    break;
+                }
+            }
+            return (trace, rewards);
+        };
+        assert!(run(3) == run(3));
+        assert!(run(3) != run(4));
+    }
+}
@@ -0,0 +1,246 @@
+#![allow(non_snake_case)]
+
+use std::f64::consts::PI;
+
+use Asteroid::{Asteroid, polygonArea};
+use Random::Random;
+use Util;
+
+const N_FRAGMENTS: usize = 3;   // 1個の小惑星が割れる数
+const BULLET_MASS: f64 = 40.0;  // 弾の質量（小惑星の質量は面積 px^2）
+const SPREAD_SPEED: f64 = 0.8;  // 破片が離れていく速さ(px/フレーム)
+
+// ---------------------------------
+//  小惑星の分裂
+//  破片は親の速度と弾の運動量を受け継ぎ（運動量保存）、
+//  互いに重ならない位置から放射状に離れていく
+//  画面の端はつながっているので、破片の位置は画面の中に折り返す
+//
+//  cut ... trueなら親の輪郭を弾の向きを起点に中心から3つに切り分けて破片にする
+//          （切れない形のときは新しい形の破片にする）
+// ---------------------------------
+pub fn split(parent: &Asteroid, bulletVx: f64, bulletVy: f64, cut: bool, width: u32, height: u32, rng: &mut Random) -> Vec<Asteroid> {
+    let newSize = parent.getSize() / 2;
+    let angle = bulletVy.atan2(bulletVx);
+
+    let mut pieces = Vec::new();
+    if cut {
+        if let Some(shapes) = cutShape(&parent.getShape(), angle) {
+            for shape in shapes {
+                // 破片の重心を中心にする
+                let (cx, cy) = centroid(&shape);
+                let local: Vec<(f64, f64)> = shape.iter().map(|&(x, y)| (x - cx, y - cy)).collect();
+                pieces.push(Asteroid::fromOutline(local, newSize, parent.getX() + cx, parent.getY() + cy, 0.0, 0.0, parent.getSpin()));
+            }
+        }
+    }
+    if pieces.is_empty() {
+        // 新しい形の破片を、重ならないように正三角形の頂点に置く
+        for _ in 0..N_FRAGMENTS {
+            pieces.push(Asteroid::new(newSize, parent.getX(), parent.getY(), rng));
+        }
+        let rmax = pieces.iter().map(|p| p.getRadius()).fold(0.0, f64::max);
+        let d = rmax / (PI / 3.0).sin() * 1.05;
+        for (k, piece) in pieces.iter_mut().enumerate() {
+            let a = angle + PI * 2.0 * (k as f64) / (N_FRAGMENTS as f64);
+            piece.setPos(parent.getX() + d * a.cos(), parent.getY() + d * a.sin());
+        }
+    }
+
+    // 破片全体の重心の速度 = (親の運動量 + 弾の運動量) / 破片の質量の合計
+    // （弾は小惑星に吸収される。新しい形の破片は親と質量が違うので、運動量が同じになる速さにする）
+    let total: f64 = pieces.iter().map(|p| p.getMass().max(1.0)).sum();
+    let px = parent.getMass().max(1.0) * parent.getVx() + BULLET_MASS * bulletVx;
+    let py = parent.getMass().max(1.0) * parent.getVy() + BULLET_MASS * bulletVy;
+    let vx = px / total;
+    let vy = py / total;
+
+    // 親の中心から放射状に離れる速度（重み付き平均を引いて運動量を変えないようにする）
+    let mut spread: Vec<(f64, f64)> = Vec::new();
+    for p in &pieces {
+        let dx = p.getX() - parent.getX();
+        let dy = p.getY() - parent.getY();
+        let d = (dx*dx + dy*dy).sqrt().max(1e-9);
+        spread.push((dx / d * SPREAD_SPEED, dy / d * SPREAD_SPEED));
+    }
+    let mut mx = 0.0;
+    let mut my = 0.0;
+    for (p, s) in pieces.iter().zip(spread.iter()) {
+        mx += p.getMass().max(1.0) * s.0 / total;
+        my += p.getMass().max(1.0) * s.1 / total;
+    }
+    let wf = width as f64;
+    let hf = height as f64;
+    for (p, s) in pieces.iter_mut().zip(spread.iter()) {
+        p.setVelocity(vx + s.0 - mx, vy + s.1 - my);
+        let (x, y) = (Util::wrap(p.getX(), wf), Util::wrap(p.getY(), hf));
+        p.setPos(x, y);
+    }
+    return pieces;
+}
+
+// 中心から角度angle、angle+120°、angle+240°の3本の線で輪郭を切り分ける
+// 輪郭が中心から見て反時計回りに並んでいない（中心から星形でない）ときはNone
+fn cutShape(shape: &[(f64, f64)], angle: f64) -> Option<Vec<Vec<(f64, f64)>>> {
+    let n = shape.len();
+    if n < 3 {
+        return None;
+    }
+    for i in 0..n {
+        if cross(shape[i], shape[(i+1) % n]) <= 0.0 {
+            return None;
+        }
+    }
+
+    // 各線が輪郭と交わる辺と点
+    let mut hits: Vec<(usize, (f64, f64))> = Vec::new();
+    for k in 0..N_FRAGMENTS {
+        let a = angle + PI * 2.0 * (k as f64) / (N_FRAGMENTS as f64);
+        hits.push(rayHit(shape, (a.cos(), a.sin()))?);
+    }
+
+    let mut pieces = Vec::new();
+    for k in 0..N_FRAGMENTS {
+        let (e0, p0) = hits[k];
+        let (e1, p1) = hits[(k+1) % N_FRAGMENTS];
+        let mut piece = vec![(0.0, 0.0), p0];
+        let count = (e1 + n - e0) % n;
+        for m in 1..(count+1) {
+            piece.push(shape[(e0 + m) % n]);
+        }
+        piece.push(p1);
+        if polygonArea(&piece) <= 1.0 {
+            return None;
+        }
+        pieces.push(piece);
+    }
+    return Some(pieces);
+}
+
+// 中心からdの向きの半直線が交わる辺の番号と交点
+fn rayHit(shape: &[(f64, f64)], d: (f64, f64)) -> Option<(usize, (f64, f64))> {
+    let n = shape.len();
+    for i in 0..n {
+        let a = shape[i];
+        let b = shape[(i+1) % n];
+        if cross(a, d) >= 0.0 && cross(d, b) > 0.0 {
+            let e = (b.0 - a.0, b.1 - a.1);
+            let u = cross(a, d) / cross(d, e);
+            return Some((i, (a.0 + u * e.0, a.1 + u * e.1)));
+        }
+    }
+    return None;
+}
+
+// 多角形の重心
+fn centroid(shape: &[(f64, f64)]) -> (f64, f64) {
+    let area = polygonArea(shape);
+    let mut cx = 0.0;
+    let mut cy = 0.0;
+    for i in 0..shape.len() {
+        let (x1, y1) = shape[i];
+        let (x2, y2) = shape[(i+1) % shape.len()];
+        let c = x1 * y2 - x2 * y1;
+        cx += (x1 + x2) * c;
+        cy += (y1 + y2) * c;
+    }
+    return (cx / (6.0 * area), cy / (6.0 * area));
+}
+
+fn cross(a: (f64, f64), b: (f64, f64)) -> f64 {
+    return a.0 * b.1 - a.1 * b.0;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 半径rの正n角形（反時計回り）
+    fn regular(n: usize, r: f64) -> Vec<(f64, f64)> {
+        return (0..n).map(|i| {
+            let t = PI * 2.0 * (i as f64) / (n as f64);
+            return (r * t.cos(), r * t.sin());
+        }).collect();
+    }
+
+    fn close(a: (f64, f64), b: (f64, f64)) -> bool {
+        return (a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9;
+    }
+
+    #[test]
+    fn ray_hits_the_edge_it_points_at() {
+        // (10,0) (0,10) (-10,0) (0,-10) のひし形
+        let shape = regular(4, 10.0);
+        let (edge, p) = rayHit(&shape, (1.0, 1.0)).unwrap();
+        assert_eq!(edge, 0);
+        assert!(close(p, (5.0, 5.0)), "{:?}", p);
+        let (edge, p) = rayHit(&shape, (-1.0, -1.0)).unwrap();
+        assert_eq!(edge, 2);
+        assert!(close(p, (-5.0, -5.0)), "{:?}", p);
+    }
+
+    #[test]
+    fn ray_through_a_vertex_hits_the_edge_starting_there() {
+        let shape = regular(4, 10.0);
+        let (edge, p) = rayHit(&shape, (0.0, 1.0)).unwrap();
+        assert_eq!(edge, 1);
+        assert!(close(p, (0.0, 10.0)), "{:?}", p);
+    }
+
+    #[test]
+    fn cut_pieces_cover_the_outline_exactly() {
+        let shape = regular(12, 20.0);
+        for &angle in &[0.0, 0.3, 1.0, 2.5, -2.0] {
+            let pieces = cutShape(&shape, angle).unwrap();
+            assert_eq!(pieces.len(), N_FRAGMENTS);
+            let sum: f64 = pieces.iter().map(|p| polygonArea(p)).sum();
+            assert!((sum - polygonArea(&shape)).abs() < 1e-6, "pieces cover {} of {}", sum, polygonArea(&shape));
+            for piece in &pieces {
+                assert!(close(piece[0], (0.0, 0.0)));
+                assert!(polygonArea(piece) > 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn clockwise_or_degenerate_outlines_are_not_cut() {
+        let mut shape = regular(12, 20.0);
+        shape.reverse();
+        assert!(cutShape(&shape, 0.0).is_none());
+        assert!(cutShape(&[(1.0, 0.0), (0.0, 1.0)], 0.0).is_none());
+    }
+
+    // 破片の運動量の合計 = 親の運動量 + 弾の運動量
+    fn assertMomentum(parent: &Asteroid, pieces: &Vec<Asteroid>, bvx: f64, bvy: f64) {
+        let px: f64 = pieces.iter().map(|p| p.getMass().max(1.0) * p.getVx()).sum();
+        let py: f64 = pieces.iter().map(|p| p.getMass().max(1.0) * p.getVy()).sum();
+        let ex = parent.getMass().max(1.0) * parent.getVx() + BULLET_MASS * bvx;
+        let ey = parent.getMass().max(1.0) * parent.getVy() + BULLET_MASS * bvy;
+        assert!((px - ex).abs() < 1e-6 && (py - ey).abs() < 1e-6, "momentum ({}, {}) != ({}, {})", px, py, ex, ey);
+    }
+
+    #[test]
+    fn split_conserves_momentum() {
+        let mut rng = Random::new(1);
+        let parent = Asteroid::fromOutline(regular(12, 24.0), 12, 400.0, 300.0, 1.5, -0.5, 0.01);
+        for &cut in &[true, false] {
+            let pieces = split(&parent, 3.0, 4.0, cut, 800, 600, &mut rng);
+            assert_eq!(pieces.len(), N_FRAGMENTS);
+            assertMomentum(&parent, &pieces, 3.0, 4.0);
+        }
+    }
+
+    #[test]
+    fn pieces_near_the_edge_stay_inside_the_world() {
+        let mut rng = Random::new(2);
+        for &(x, y) in &[(1.0, 300.0), (799.0, 300.0), (400.0, 1.0), (400.0, 599.0), (0.0, 0.0)] {
+            let parent = Asteroid::fromOutline(regular(12, 24.0), 12, x, y, 0.0, 0.0, 0.0);
+            for &cut in &[true, false] {
+                for p in split(&parent, 1.0, 0.0, cut, 800, 600, &mut rng) {
+                    assert!(p.getX() >= 0.0 && p.getX() < 800.0 && p.getY() >= 0.0 && p.getY() < 600.0,
+                            "piece at ({}, {}) from ({}, {})", p.getX(), p.getY(), x, y);
+                }
+            }
+        }
+    }
+}
@@ -1,20 +1,32 @@
 #![allow(non_snake_case)]
 
-use sdl2::video::{Window, WindowContext};
-use sdl2::render::{Canvas, TextureCreator};
 use sdl2::pixels::Color;
-use sdl2::ttf::Font;
+use sdl2::rect::{Point, Rect};
+use sdl2::render::BlendMode;
 
 use MyShip;
 use Asteroid;
 use Bullet;
 use Util;
+use Scene;
+use Input::PlayerInput;
+use Player::Player;
+use Random::Random;
+use Draw::Draw;
+use Physics;
+use Fragment;
+use Tuning::Tuning;
+use SaveGame::{SaveWriter, SaveReader};
+use Net::MAX_PLAYERS;
+use Camera::{Camera, View};
+use Theme::{Theme, ThemeKind};
+use Lang::{Language, tr};
 
 // ---------------------------------
 // ゲーム本体制御
 // ---------------------------------
+#[derive(Debug)]
 pub enum GameState {
-    TITLE,
     START,
     PLAY,
     EXPLODED,
@@ -22,162 +34,520 @@ pub enum GameState {
     GAMEOVER
 }
 
+const SAFE_DIST: f64 = 150.0;   // 新しい波の小惑星は宇宙船からこれ以上離して出す
+const WORLD_SCREENS: u32 = 3;   // 広い宇宙モードの世界の大きさ（縦横それぞれ画面の何倍か）
+const RADAR_WIDTH: i32 = 180;   // レーダーの幅(px)
+const ENERGY_BAR_WIDTH: u32 = 150;  // シールドのエネルギー計の幅(px)
+
+// ---------------------------------
+// 宇宙船を失った原因
+// ---------------------------------
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DeathCause {
+    ASTEROID,   // 小惑星にぶつかった
+    BULLET      // 味方の弾に当たった
+}
+
+// ---------------------------------
+// 1フレームの間に起きたこと（統計や実績用）
+// update()のたびに作り直すので、update()の後にgetEvents()で読む
+// ---------------------------------
+#[derive(Clone, Copy, Debug)]
+pub enum GameEvent {
+    SHOT(usize),            // プレイヤーが弾を撃った
+    DESTROYED(usize, i32),  // プレイヤーが小惑星を壊した（小惑星のサイズ）
+    LOST(usize, DeathCause, i32),   // プレイヤーが宇宙船を失った（原因、生き延びたフレーム数）
+    WAVE(i32),              // 新しい波が始まった（何番目か）
+    GAMEOVER
+}
+
+// ---------------------------------
+// プレイ人数と遊び方
+// ---------------------------------
+#[derive(Clone, Copy, PartialEq)]
+pub enum PlayMode {
+    SINGLE,     // 1人プレイ
+    ALTERNATE,  // 2人交代プレイ（宇宙船が壊れたら交代）
+    COOP        // 同時協力プレイ（ネットワーク対戦も含む）
+}
+
 pub struct Game {
     state: GameState,
-    score: i32,
-    nShips: i32,
+    mode: PlayMode,
     nAsteroids: i32,
-
-    D_ROT: f64,
-    MAX_SPEED: f64,
-
-    myShip: Option<MyShip::MyShip>,
+    friendlyFire: bool, // trueなら味方の弾にも当たる（協力プレイ用）
+    asteroidCollisions: bool,   // trueなら小惑星どうしの衝突を計算する
+    restitution: f64,
+    cutFragments: bool, // trueなら小惑星の輪郭を切り分けて破片にする
+
+    tuning: Tuning,     // 速さや分裂のしきい値などの調整値
+    wave: i32,          // 今の波（小惑星を全部壊すと次の波）
+    events: Vec<GameEvent>, // 今回のupdate()で起きたこと
+    worldScale: u32,    // 世界の大きさ（画面の何倍か、1なら画面がそのまま世界）
+    camera: Camera,     // 広い宇宙で宇宙船を追うカメラ
+    viewPlayer: usize,  // カメラが追うプレイヤー（協力プレイ用）
+
+    players: Vec<Player>,
+    current: usize, // 操作中のプレイヤー（交代プレイ用）
+    inputs: Vec<PlayerInput>,   // プレイヤーごとの今回の操作
     bullets: Vec<Bullet::Bullet>,
     asteroids: Vec<Asteroid::Asteroid>,
+    rng: Random,    // ゲーム中の乱数はすべてこれを使う
+    theme: Theme,   // 描画に使う配色（保存しない）
+    lang: Language, // 表示する言語（保存しない）
 
     gameOverDisplayCount: i32
 }
 
 impl Game {
-    pub fn new() -> Self {
+    pub fn new(settings: &Scene::Settings, mode: PlayMode, seed: u64) -> Self {
+        let nPlayers = if mode == PlayMode::SINGLE { 1 } else { 2 };
+        return Game::withPlayers(settings, mode, nPlayers, seed);
+    }
+
+    // ネットワーク対戦用（nPlayers人の同時協力プレイ）
+    pub fn newNetwork(settings: &Scene::Settings, nPlayers: usize, seed: u64) -> Self {
+        return Game::withPlayers(settings, PlayMode::COOP, nPlayers, seed);
+    }
+
+    fn withPlayers(settings: &Scene::Settings, mode: PlayMode, nPlayers: usize, seed: u64) -> Self {
+        let theme = settings.getTheme();
+        let mut players = Vec::new();
+        for i in 0..nPlayers {
+            players.push(Player::new(settings.nShips, theme.shipColor(i)));
+        }
+
         return Game {
-            state: GameState::TITLE,
-            score: 0,
-            nShips: 3,  // 宇宙船の個数
-            nAsteroids: 0,
-            MAX_SPEED: 15.0,    // 最大速度
-            D_ROT: Util::deg2rad(5.0),  // 回転変位
-            myShip: None,
+            state: GameState::START,
+            mode: mode,
+            nAsteroids: settings.nAsteroids,
+            friendlyFire: settings.friendlyFire,
+            asteroidCollisions: settings.asteroidCollisions,
+            restitution: settings.restitution,
+            cutFragments: settings.cutFragments,
+            tuning: settings.tuning.clone(),
+            wave: 1,
+            events: Vec::new(),
+            worldScale: if settings.openSpace { WORLD_SCREENS } else { 1 },
+            camera: Camera::new(),
+            viewPlayer: 0,
+            players: players,
+            current: 0,
+            inputs: vec![PlayerInput::default(); nPlayers],
             bullets: Vec::new(),
             asteroids: Vec::new(),
+            rng: Random::new(seed),
+            theme: theme,
+            lang: settings.language,
             gameOverDisplayCount: 0
         };
     }
 
-    pub fn upPressed(&mut self) {
-        match self.state {
-            GameState::PLAY => {
-                if let Some(ref mut myShip) = self.myShip {
-                    myShip.updateDir(); // 加速
-                }
-            }
-            _ => {
-                // do nothing
-            }
+    // 画面の大きさから世界の大きさを求める
+    pub fn getWorldSize(&self, width: u32, height: u32) -> (u32, u32) {
+        return (width * self.worldScale, height * self.worldScale);
+    }
+
+    // 世界座標を画面座標にする変換（画面の大きさを渡す）
+    pub fn getView(&self, width: u32, height: u32) -> View {
+        if self.worldScale == 1 {
+            return View::fixed(width, height);
         }
+        let (ww, wh) = self.getWorldSize(width, height);
+        return View {
+            x: self.camera.getX(),
+            y: self.camera.getY(),
+            screenWidth: width,
+            screenHeight: height,
+            worldWidth: ww,
+            worldHeight: wh
+        };
     }
 
-    pub fn leftPressed(&mut self) {
-        match self.state {
-            GameState::PLAY => {
-                if let Some(ref mut myShip) = self.myShip {
-                    myShip.incrRotation(-self.D_ROT);    // 左回転
-                }
-            }
-            _ => {
-                // do nothing
+    // 協力プレイでカメラが追うプレイヤー（ネットワーク対戦では自分）
+    pub fn setViewPlayer(&mut self, i: usize) {
+        if i < self.players.len() {
+            self.viewPlayer = i;
+        }
+    }
+
+    // 操作を設定（次のupdate()で使われる）
+    // 交代プレイでは0番の操作が現在のプレイヤーに使われる
+    pub fn setInput(&mut self, i: usize, input: PlayerInput) {
+        if i < self.inputs.len() {
+            self.inputs[i] = input;
+        }
+    }
+
+    pub fn init(&mut self, width: u32, height: u32) {
+        // 宇宙船初期化
+        for i in 0..self.players.len() {
+            if self.mode == PlayMode::COOP || i == self.current {
+                self.spawnShip(i, width, height);
             }
         }
+
+        // 弾丸を保持するベクトル
+        self.bullets = Vec::new();
+        self.camera.reset();
+
+        // 小惑星を保持するベクトル
+        self.asteroids = Vec::new();
+
+        // 小惑星を生成
+        self.wave = 1;
+        self.spawnAsteroids(width, height, false);
+        self.events.push(GameEvent::WAVE(self.wave));
     }
 
-    pub fn rightPressed(&mut self) {
-        match self.state {
-            GameState::PLAY => {
-                if let Some(ref mut myShip) = self.myShip {
-                    myShip.incrRotation(self.D_ROT);     // 右回転
+    // 波の小惑星を生成（広い宇宙では世界の幅に合わせて増やす、波ごとに1個ずつ増やす）
+    // avoidShips ... trueなら宇宙船の近くには出さない（途中の波用）
+    fn spawnAsteroids(&mut self, width: u32, height: u32, avoidShips: bool) {
+        let n = (self.nAsteroids + self.wave - 1) * (self.worldScale as i32);
+        for _ in 0..n {
+            let mut asteroid = Asteroid::Asteroid::genRand(
+                width,
+                height,
+                &mut self.rng
+            );
+            // 宇宙船に近すぎたら何回か作り直す
+            for _ in 0..10 {
+                if !avoidShips || !self.nearShip(asteroid.getX(), asteroid.getY(), asteroid.getRadius() + SAFE_DIST, width, height) {
+                    break;
                 }
+                asteroid = Asteroid::Asteroid::genRand(width, height, &mut self.rng);
             }
-            _ => {
-                // do nothing
-            }
+            self.asteroids.push(asteroid);
         }
     }
 
-    pub fn spacePressed(&mut self) {
-        match self.state {
-            GameState::PLAY => {
-                if let Some(ref mut myShip) = self.myShip {
-                    self.bullets.push(myShip.fire());  // 弾発射
+    // (x, y)から距離d以内に宇宙船があればtrue
+    fn nearShip(&self, x: f64, y: f64, d: f64, width: u32, height: u32) -> bool {
+        for player in &self.players {
+            if let Some(ref myShip) = player.ship {
+                let dx = Util::wrapDelta(myShip.getX() - x, width as f64);
+                let dy = Util::wrapDelta(myShip.getY() - y, height as f64);
+                if dx*dx + dy*dy < d*d {
+                    return true;
                 }
             }
-            _ => {
-                // do nothing
-            }
         }
+        return false;
     }
 
-    pub fn enterPressed(&mut self) {
-        match self.state {
-            GameState::TITLE => {
-                // ゲーム開始
-                self.state = GameState::START;
+    // 小惑星を全部壊したら次の波
+    fn checkWave(&mut self, width: u32, height: u32) {
+        if self.asteroids.iter().any(|a| a.getValid()) {
+            return;
+        }
+        self.wave += 1;
+        self.asteroids = Vec::new();
+        self.spawnAsteroids(width, height, true);
+        self.events.push(GameEvent::WAVE(self.wave));
+    }
+
+    // i番目のプレイヤーの宇宙船を出す
+    fn spawnShip(&mut self, i: usize, width: u32, height: u32) {
+        // 協力プレイでは横に並べる
+        let x = if self.mode == PlayMode::COOP {
+            (width as f64) * ((i as f64) + 1.0) / ((self.players.len() as f64) + 1.0)
+        } else {
+            (width/2) as f64
+        };
+
+        let myShip = MyShip::MyShip::new(
+            x,   // x
+            (height/2) as f64,  // y
+            10.0,   // 半径（宇宙船の大きさ）
+            0.0,    // 初期回転角
+            &self.tuning    // 速さ、加速、シールド
+        );
+        self.setShip(i, myShip);
+    }
+
+    // i番目のプレイヤーの宇宙船を置く（出したばかりの状態にする）
+    // シナリオテストで位置や速度を決めた宇宙船を置くのにも使う
+    pub fn setShip(&mut self, i: usize, mut myShip: MyShip::MyShip) {
+        myShip.setColor(self.players[i].color);
+
+        let player = &mut self.players[i];
+        player.ship = Some(myShip);
+        player.lifeFrames = 0;
+        player.exploding = false;
+        player.prevFire = false;
+    }
+
+    pub fn getState(&self) -> &GameState {
+        return &self.state;
+    }
+
+    pub fn getMode(&self) -> PlayMode {
+        return self.mode;
+    }
+
+    pub fn getTuning(&self) -> &Tuning {
+        return &self.tuning;
+    }
+
+    pub fn getWave(&self) -> i32 {
+        return self.wave;
+    }
+
+    // 直前のupdate()で起きたこと
+    pub fn getEvents(&self) -> &Vec<GameEvent> {
+        return &self.events;
+    }
+
+    // 途中のゲームを保存（操作は保存しない）
+    pub fn save(&self, w: &mut SaveWriter) {
+        w.line("game");
+        w.word(match self.state {
+            GameState::START => "START",
+            GameState::PLAY => "PLAY",
+            GameState::EXPLODED => "EXPLODED",
+            GameState::DEAD => "DEAD",
+            GameState::GAMEOVER => "GAMEOVER"
+        });
+        w.word(match self.mode {
+            PlayMode::SINGLE => "SINGLE",
+            PlayMode::ALTERNATE => "ALTERNATE",
+            PlayMode::COOP => "COOP"
+        });
+        w.i32(self.nAsteroids);
+        w.bool(self.friendlyFire);
+        w.bool(self.asteroidCollisions);
+        w.f64(self.restitution);
+        w.bool(self.cutFragments);
+        w.u64(self.current as u64);
+        w.i32(self.gameOverDisplayCount);
+        w.u64(self.worldScale as u64);
+        w.i32(self.wave);
+        self.tuning.save(w);
+        self.rng.save(w);
+
+        w.line("players");
+        w.u64(self.players.len() as u64);
+        for player in &self.players {
+            player.save(w);
+        }
+        // 無効になった弾や小惑星は書かない
+        let bullets: Vec<&Bullet::Bullet> = self.bullets.iter().filter(|b| b.getValid()).collect();
+        w.line("bullets");
+        w.u64(bullets.len() as u64);
+        for bullet in bullets {
+            bullet.save(w);
+        }
+        let asteroids: Vec<&Asteroid::Asteroid> = self.asteroids.iter().filter(|a| a.getValid()).collect();
+        w.line("asteroids");
+        w.u64(asteroids.len() as u64);
+        for asteroid in asteroids {
+            asteroid.save(w);
+        }
+    }
+
+    pub fn load(r: &mut SaveReader) -> Result<Self, String> {
+        r.expect("game")?;
+        let state = match r.word()? {
+            "START" => GameState::START,
+            "PLAY" => GameState::PLAY,
+            "EXPLODED" => GameState::EXPLODED,
+            "DEAD" => GameState::DEAD,
+            "GAMEOVER" => GameState::GAMEOVER,
+            s => { return Err(format!("unknown game state '{}'", s)); }
+        };
+        let mode = match r.word()? {
+            "SINGLE" => PlayMode::SINGLE,
+            "ALTERNATE" => PlayMode::ALTERNATE,
+            "COOP" => PlayMode::COOP,
+            s => { return Err(format!("unknown play mode '{}'", s)); }
+        };
+        let nAsteroids = r.i32()?;
+        let friendlyFire = r.bool()?;
+        let asteroidCollisions = r.bool()?;
+        let restitution = r.f64()?;
+        let cutFragments = r.bool()?;
+        let current = r.u64()? as usize;
+        let gameOverDisplayCount = r.i32()?;
+        let worldScale = r.count(16)? as u32;
+        if worldScale == 0 {
+            return Err(r.error("world scale must be at least 1"));
+        }
+        let wave = r.i32()?;
+        let tuning = Tuning::load(r)?;
+        let rng = Random::load(r)?;
+
+        r.expect("players")?;
+        let nPlayers = r.count(MAX_PLAYERS)?;
+        let mut players = Vec::new();
+        for _ in 0..nPlayers {
+            players.push(Player::load(r)?);
+        }
+        if players.is_empty() || current >= players.len() {
+            return Err(format!("current player {} does not exist", current));
+        }
+
+        r.expect("bullets")?;
+        let n = r.count(10000)?;
+        let mut bullets = Vec::new();
+        for _ in 0..n {
+            let bullet = Bullet::Bullet::load(r)?;
+            if bullet.getOwner() >= players.len() {
+                return Err(format!("bullet owner {} does not exist", bullet.getOwner()));
             }
-            _ => {
+            bullets.push(bullet);
+        }
 
+        r.expect("asteroids")?;
+        let n = r.count(10000)?;
+        let mut asteroids = Vec::new();
+        for _ in 0..n {
+            asteroids.push(Asteroid::Asteroid::load(r)?);
+        }
+
+        return Ok(Game {
+            state: state,
+            mode: mode,
+            nAsteroids: nAsteroids,
+            friendlyFire: friendlyFire,
+            asteroidCollisions: asteroidCollisions,
+            restitution: restitution,
+            cutFragments: cutFragments,
+            tuning: tuning,
+            wave: wave,
+            events: Vec::new(),
+            worldScale: worldScale,
+            camera: Camera::new(),
+            viewPlayer: 0,
+            current: current,
+            inputs: vec![PlayerInput::default(); players.len()],
+            players: players,
+            bullets: bullets,
+            asteroids: asteroids,
+            rng: rng,
+            theme: Theme::new(ThemeKind::COLOR, false, false),
+            lang: Language::ENGLISH,
+            gameOverDisplayCount: gameOverDisplayCount
+        });
+    }
+
+    // 配色を変える（プレイヤーと宇宙船の色も塗り直す）
+    // 保存したゲームは配色を持たないので、続きから遊ぶときにも呼ぶ
+    pub fn setTheme(&mut self, theme: &Theme) {
+        self.theme = *theme;
+        for (i, player) in self.players.iter_mut().enumerate() {
+            player.color = theme.shipColor(i);
+            if let Some(ref mut myShip) = player.ship {
+                myShip.setColor(player.color);
             }
         }
     }
 
-    pub fn escapePressed(&mut self) {
-        
+    // 表示する言語を変える（保存したゲームは言語を持たないので、続きから遊ぶときにも呼ぶ）
+    pub fn setLanguage(&mut self, lang: Language) {
+        self.lang = lang;
     }
 
-    pub fn init(&mut self, width: u32, height: u32) {
-        // 宇宙船初期化
-        self.myShip = Some(MyShip::MyShip::new(
-            (width/2) as f64,   // x
-            (height/2) as f64,  // y
-            10.0,   // 半径（宇宙船の大きさ）
-            0.0,    // 初期回転角
-            self.MAX_SPEED   // 最大速さ
-        ));
- 
-        // 弾丸を保持するベクトル
-        self.bullets = Vec::new();
+    // 全プレイヤーのスコア
+    pub fn getScores(&self) -> Vec<i32> {
+        return self.players.iter().map(|p| p.score).collect();
+    }
 
-        // 小惑星を保持するベクトル
-        self.asteroids = Vec::new();
-    
-        // 小惑星を生成
-        self.nAsteroids = 10;
-        for _ in 0..self.nAsteroids {
-            let asteroid = Asteroid::Asteroid::genRand(
-                width,
-                height
-            );
-            self.asteroids.push(asteroid);
+    // i番目のプレイヤーの宇宙船（破壊中・出番でない時はNone）
+    pub fn getShip(&self, i: usize) -> Option<&MyShip::MyShip> {
+        let player = &self.players[i];
+        let active = match self.state {
+            GameState::PLAY => !player.exploding && (self.mode == PlayMode::COOP || i == self.current),
+            _ => false
+        };
+        return if active { player.ship.as_ref() } else { None };
+    }
+
+    pub fn getBullets(&self) -> &Vec<Bullet::Bullet> {
+        return &self.bullets;
+    }
+
+    // i番目のプレイヤーのスコアと残り宇宙船
+    pub fn getScore(&self, i: usize) -> i32 {
+        return self.players[i].score;
+    }
+
+    pub fn getShipsLeft(&self, i: usize) -> i32 {
+        return self.players[i].nShips;
+    }
+
+    pub fn getAsteroids(&self) -> &Vec<Asteroid::Asteroid> {
+        return &self.asteroids;
+    }
+
+    pub fn getPlayerCount(&self) -> usize {
+        return self.players.len();
+    }
+
+    // 以下はシナリオテスト用（乱数で作った最初の状態を決まった状態に置き換える）
+
+    pub fn setShipsLeft(&mut self, i: usize, n: i32) {
+        self.players[i].nShips = n;
+    }
+
+    pub fn setAsteroids(&mut self, asteroids: Vec<Asteroid::Asteroid>) {
+        self.asteroids = asteroids;
+    }
+
+    pub fn setBullets(&mut self, bullets: Vec<Bullet::Bullet>) {
+        self.bullets = bullets;
+    }
+
+    // ゲーム状態のハッシュ値（ネットワーク対戦で同期ずれを見つけるため）
+    pub fn checksum(&self) -> u64 {
+        // FNV-1a
+        let mut h: u64 = 0xcbf29ce484222325;
+        {
+            let mut mix = |v: u64| {
+                for i in 0..8 {
+                    h ^= (v >> (i * 8)) & 0xff;
+                    h = h.wrapping_mul(0x100000001b3);
+                }
+            };
+            for player in &self.players {
+                mix(player.score as u64);
+                mix(player.nShips as u64);
+                if let Some(ref myShip) = player.ship {
+                    mix(myShip.getX().to_bits());
+                    mix(myShip.getY().to_bits());
+                }
+            }
+            for bullet in &self.bullets {
+                if bullet.getValid() {
+                    mix(bullet.getX().to_bits());
+                    mix(bullet.getY().to_bits());
+                }
+            }
+            for asteroid in &self.asteroids {
+                if asteroid.getValid() {
+                    mix(asteroid.getX().to_bits());
+                    mix(asteroid.getY().to_bits());
+                }
+            }
         }
+        return h;
     }
 
-    pub fn update<'a>(
-        &mut self,
-        canvas: &mut Canvas<Window>,
-        font: &Font,
-        texture_creator: &'a TextureCreator<WindowContext>,
-        width: u32, height: u32
-    ) {
+    // ゲームオーバー表示が終わったらtrue
+    pub fn isOver(&self) -> bool {
+        return match self.state {
+            GameState::GAMEOVER => self.gameOverDisplayCount >= 300,
+            _ => false
+        };
+    }
+
+    // 1フレーム分ゲームを進める（描画はしない）
+    // 画面の大きさを渡す（ゲームの中は世界の大きさで動く）
+    pub fn update(&mut self, width: u32, height: u32) {
+        let (width, height) = self.getWorldSize(width, height);
+        self.events.clear();
+
         match self.state {
-            GameState::TITLE => {
-                // ゲーム開始(Enter)または終了(Escape)まで待機
-
-                // clear canvas
-                canvas.set_draw_color(Color::RGB(0, 0, 0)); // black
-                canvas.clear();
-
-                let wl: u32 = 120;
-                let hl: u32 = 150;
-                let wm: u32 = 20;
-                let hm: u32 = 30;
-
-                Util::textOut(canvas, &font, &texture_creator, "Asteroid", 0, 255, 128, (width as i32)/2-(wl as i32)*4, (height as i32)/2-250, wl, hl);
-                Util::textOut(canvas, &font, &texture_creator, "Arrow Keys: Ship Control", 0, 255, 128, (width as i32)/2-250, (height as i32)/2+20, wm, hm);
-                Util::textOut(canvas, &font, &texture_creator, "Space Bar: Fire Bullet", 0, 255, 128, (width as i32)/2-230, (height as i32)/2+60, wm, hm);
-                Util::textOut(canvas, &font, &texture_creator, "Press Enter to start", 0, 255, 128, (width as i32)/2-200, (height as i32)/2+250, wm, hm);
-                Util::textOut(canvas, &font, &texture_creator, "Escape to exit", 0, 255, 128, (width as i32)/2-140, (height as i32)/2+300, wm, hm);
-            }
             GameState::START => {
-                // スコアをクリア
-                self.score = 0;
                 // ゲーム初期化
                 self.init(width, height);
                 // ゲーム開始
@@ -186,107 +556,25 @@ impl Game {
             GameState::PLAY => {
                 // ゲームプレイ中
 
-                // clear canvas
-                canvas.set_draw_color(Color::RGB(0, 0, 0)); // black
-                canvas.clear();
-                
                 // 宇宙船更新
-                if let Some(ref mut myShip) = self.myShip {
-                    // update position
-                    myShip.updatePos(width, height);
-
-                    let shipX = myShip.getX();
-                    let shipY = myShip.getY();
-
-                    // 衝突判定
-                    for asteroid in &mut self.asteroids {
-                        if asteroid.getValid() && asteroid.hitTest(shipX, shipY) {
-                            // 小惑星に当たった
-                            myShip.clearExplosionAnimCount();
-                            self.state = GameState::EXPLODED;
-                            break;
-                        }
-                    }
-                    
-                    // draw ship
-                    myShip.draw(canvas);
-                }
-        
-                // update bullets
-                let wf = (width as i32) as f64;
-                let hf = (height as i32) as f64;
-                for bullet in &mut self.bullets {
-                    if bullet.getValid() {
-                        // 有効なら進める
-                        let newx = bullet.getX() + bullet.getVx();
-                        let newy = bullet.getY() + bullet.getVy();
-        
-                        if (newx < 0.0) || (newy < 0.0) || (newx > wf) || (newy > hf) {
-                            // 画面から外れた．無効にする
-                            // （本来は消すべきだが、dropの仕方がよくわからないのでフラグを使って無効にする）
-                            bullet.setValid(false);
-                        } else {
-                            bullet.setX(newx);
-                            bullet.setY(newy);
-        
-                            // 衝突判定
-                            let mut newAsteroids = Vec::new();
-                            for asteroid in &mut self.asteroids {
-                                if asteroid.getValid() && asteroid.hitTest(newx, newy) {
-                                    // 小惑星に当たった
-                                    asteroid.setValid(false);
-                                    bullet.setValid(false);
-
-                                    // スコア加算
-                                    self.score += 10;
-        
-                                    // 小惑星を分裂させる
-                                    if asteroid.getSize() >= 6 {
-                                        let newSize = asteroid.getSize() / 2;
-                                        for _ in 0..3 {
-                                            let newAstroid = Asteroid::Asteroid::new(newSize, asteroid.getX(), asteroid.getY());
-                                            newAsteroids.push(newAstroid);
-                                        }
-                                    }
-                                }
-                            }
-        
-                            for newAsteroid in newAsteroids {
-                                self.asteroids.push(newAsteroid);
-                            }
-                        }
-                    }
-                }
-        
-                // draw bullets
-                for bullet in &self.bullets {
-                    if bullet.getValid() {
-                        bullet.draw(canvas);
-                    }
-                }
-        
-                // update & draw asteroids
-                for asteroid in &mut self.asteroids {
-                    if asteroid.getValid() {
-                        asteroid.update(width, height);
-                        asteroid.draw(canvas);
-                    }
+                for i in 0..self.players.len() {
+                    self.updatePlayer(i, width, height);
                 }
 
-                // draw score
-                Util::textOut(canvas, &font, &texture_creator, &format!("Score: {}", self.score), 0, 255, 128, 100, 10, 15, 30);
-                Util::textOut(canvas, &font, &texture_creator, &format!("Ships Left: {}", self.nShips), 0, 255, 128, (width as i32) - 250, 10, 15, 30);
+                self.updateBullets(width, height);
+                self.updateAsteroids(width, height);
+                self.checkWave(width, height);
+
+                // 協力プレイは全員の宇宙船がなくなったら終わり
+                if self.mode == PlayMode::COOP && self.players.iter().all(|p| p.ship.is_none()) {
+                    self.state = GameState::GAMEOVER;
+                    self.gameOverDisplayCount = 0;
+                    self.events.push(GameEvent::GAMEOVER);
+                }
             }
             GameState::EXPLODED => {
-                // clear canvas
-                canvas.set_draw_color(Color::RGB(0, 0, 0)); // black
-                canvas.clear();
-
-                if let Some(ref mut myShip) = self.myShip {
-                    // 宇宙船破壊アニメーション
-                    // draw ship
-                    myShip.drawExplosion(canvas);
-
+                let current = self.current;
+                if let Some(ref mut myShip) = self.players[current].ship {
                     // 破壊アニメーションカウント更新
                     let newAnimCount = myShip.updateExplosionAnimCount();
                     if newAnimCount >= 50 {
@@ -295,57 +583,406 @@ impl Game {
                     }
                 }
 
-                // update & draw asteroids
-                for asteroid in &mut self.asteroids {
-                    if asteroid.getValid() {
-                        asteroid.update(width, height);
-                        asteroid.draw(canvas);
+                self.updateBullets(width, height);
+                self.updateAsteroids(width, height);
+            }
+            GameState::DEAD => {
+                // 宇宙船破壊された
+                let current = self.current;
+                self.players[current].nShips -= 1;
+                self.players[current].ship = None;
+
+                // 交代プレイなら次のプレイヤーへ
+                let n = self.players.len();
+                let next = (1..n+1)
+                    .map(|d| (current + d) % n)
+                    .find(|&i| self.players[i].isAlive());
+
+                match next {
+                    Some(i) => {
+                        // まだ残りある．リスタート
+                        self.current = i;
+                        self.spawnShip(i, width, height);
+                        self.state = GameState::PLAY;
+                    }
+                    None => {
+                        // 残り宇宙船なし．ゲームオーバー
+                        self.state = GameState::GAMEOVER;
+                        self.gameOverDisplayCount = 0;
+                        self.events.push(GameEvent::GAMEOVER);
                     }
                 }
+            }
+            GameState::GAMEOVER => {
+                self.updateAsteroids(width, height);
 
-                // draw score
-                Util::textOut(canvas, &font, &texture_creator, &format!("Score: {}", self.score), 0, 255, 128, 100, 10, 15, 30);
-                Util::textOut(canvas, &font, &texture_creator, &format!("Ships Left: {}", self.nShips), 0, 255, 128, (width as i32) - 250, 10, 15, 30);
+                self.gameOverDisplayCount += 1;
             }
-            GameState::DEAD => {
-                // 宇宙船破壊された
-                self.nShips -= 1;
-                if self.nShips <=0 {
-                    // 残り宇宙船なし．ゲームオーバー
-                    self.state = GameState::GAMEOVER;
-                    self.gameOverDisplayCount = 0;
+        }
+
+        self.updateCamera(width, height);
+    }
+
+    // カメラを操作中の宇宙船に近づける（宇宙船がなければそのまま）
+    fn updateCamera(&mut self, worldWidth: u32, worldHeight: u32) {
+        let i = if self.mode == PlayMode::COOP { self.viewPlayer } else { self.current };
+        if let Some(ref myShip) = self.players[i].ship {
+            self.camera.follow(myShip.getX(), myShip.getY(), worldWidth, worldHeight);
+        }
+    }
+
+    // i番目のプレイヤーの操作と宇宙船を更新
+    fn updatePlayer(&mut self, i: usize, width: u32, height: u32) {
+        let coop = self.mode == PlayMode::COOP;
+        if !coop && i != self.current {
+            return;
+        }
+
+        // 交代プレイでは0番の操作を使う
+        let input = if coop { self.inputs[i] } else { self.inputs[0] };
+
+        let player = &mut self.players[i];
+        let myShip = match player.ship {
+            Some(ref mut myShip) => myShip,
+            None => { return; }
+        };
+
+        if player.exploding {
+            // 協力プレイでの破壊アニメーション
+            let newAnimCount = myShip.updateExplosionAnimCount();
+            if newAnimCount >= 50 {
+                player.nShips -= 1;
+                if player.isAlive() {
+                    self.spawnShip(i, width, height);
                 } else {
-                    // まだ残りある．リスタート
-                    // init();
-                    self.state = GameState::PLAY;
+                    self.players[i].ship = None;
+                    self.players[i].exploding = false;
                 }
             }
-            GameState::GAMEOVER => {
-                // clear canvas
-                canvas.set_draw_color(Color::RGB(0, 0, 0)); // black
-                canvas.clear();
+            return;
+        }
+        player.lifeFrames += 1;
+
+        // 操作
+        let turn = if input.left == input.right { 0 } else if input.left { -1 } else { 1 };
+        myShip.updateRotation(turn);    // 左右回転
+        myShip.updateDir(input.up, input.down); // 加速、逆噴射
+        if player.fireCooldown > 0 {
+            player.fireCooldown -= 1;
+        }
+        let alive = self.bullets.iter().filter(|b| b.getValid() && b.getOwner() == i).count() as i32;
+        if input.fire && !player.prevFire && player.fireCooldown == 0 && alive < self.tuning.maxBullets {
+            // 押した瞬間だけ弾発射（撃ってすぐと、弾が多すぎるときは撃てない）
+            player.fireCooldown = self.tuning.fireCooldown;
+            let mut bullet = myShip.fire(self.tuning.bulletLifetime);
+            bullet.setOwner(i);
+            self.bullets.push(bullet);
+            self.events.push(GameEvent::SHOT(i));
+        }
+        player.prevFire = input.fire;
+        myShip.updateShield(input.shield);
+
+        // update position
+        myShip.updatePos(width, height);
+
+        let shipX = myShip.getX();
+        let shipY = myShip.getY();
+
+        if myShip.isShielded() {
+            // シールド中は小惑星に当たらない。分裂しない小さな小惑星は泡で跳ね返す
+            let (cx, cy) = myShip.getCenter();
+            let sr = myShip.getShieldRadius();
+            for asteroid in &mut self.asteroids {
+                if asteroid.getValid() && asteroid.getSize() < self.tuning.splitMinSize {
+                    bounceOffShield(asteroid, cx, cy, sr, myShip.getVx(), myShip.getVy(), width, height);
+                }
+            }
+            return;
+        }
 
-                let wl: i32 = 100;
-                let hl: i32 = 130;
-                let wm: i32 = 20;
-                let hm: i32 = 30;
-                Util::textOut(canvas, &font, &texture_creator, "Game Over", 0, 255, 128, (width as i32)/2-wl*5, (height as i32)/2-hl/2, wl as u32, hl as u32);
-                Util::textOut(canvas, &font, &texture_creator, &format!("Score: {}", self.score), 0, 255, 128, (width as i32)/2-wm*5, (height as i32)/2+hm*2, 15, 30);
+        // 衝突判定
+        for asteroid in &self.asteroids {
+            if asteroid.getValid() && asteroid.hitTest(shipX, shipY, width, height) {
+                // 小惑星に当たった
+                myShip.clearExplosionAnimCount();
+                self.events.push(GameEvent::LOST(i, DeathCause::ASTEROID, player.lifeFrames));
+                if coop {
+                    player.exploding = true;
+                } else {
+                    self.state = GameState::EXPLODED;
+                }
+                break;
+            }
+        }
+    }
+
+    // update bullets
+    fn updateBullets(&mut self, width: u32, height: u32) {
+        let wf = (width as i32) as f64;
+        let hf = (height as i32) as f64;
+        let mut newAsteroids = Vec::new();
+        for bullet in &mut self.bullets {
+            if bullet.getValid() {
+                // 寿命が尽きたら無効にする（消すのは最後にまとめて）
+                bullet.age();
+                if !bullet.getValid() {
+                    continue;
+                }
 
-                // update & draw asteroids
+                // 有効なら進める（画面の端から出たら反対側から出てくる）
+                let newx = Util::wrap(bullet.getX() + bullet.getVx(), wf);
+                let newy = Util::wrap(bullet.getY() + bullet.getVy(), hf);
+
+                bullet.setX(newx);
+                bullet.setY(newy);
+
+                // 衝突判定
                 for asteroid in &mut self.asteroids {
-                    if asteroid.getValid() {
-                        asteroid.update(width, height);
-                        asteroid.draw(canvas);
+                    if asteroid.getValid() && asteroid.hitTest(newx, newy, width, height) {
+                        // 小惑星に当たった
+                        asteroid.setValid(false);
+                        bullet.setValid(false);
+
+                        // 撃ったプレイヤーのスコアに加算
+                        self.players[bullet.getOwner()].score += 10;
+                        self.events.push(GameEvent::DESTROYED(bullet.getOwner(), asteroid.getSize()));
+
+                        // 小惑星を分裂させる
+                        if asteroid.getSize() >= self.tuning.splitMinSize {
+                            let pieces = Fragment::split(asteroid, bullet.getVx(), bullet.getVy(), self.cutFragments, width, height, &mut self.rng);
+                            newAsteroids.extend(pieces);
+                        }
+                        break;
                     }
                 }
 
-                self.gameOverDisplayCount += 1;
-                if self.gameOverDisplayCount >= 300 {
-                    // タイトル画面に戻る
-                    self.state = GameState::TITLE;
+                // 同士討ち
+                if bullet.getValid() && self.friendlyFire && self.mode == PlayMode::COOP {
+                    for (i, player) in self.players.iter_mut().enumerate() {
+                        if i == bullet.getOwner() || player.exploding {
+                            continue;
+                        }
+                        if let Some(ref mut myShip) = player.ship {
+                            if myShip.hitTest(newx, newy, width, height) {
+                                bullet.setValid(false);
+                                myShip.clearExplosionAnimCount();
+                                player.exploding = true;
+                                self.events.push(GameEvent::LOST(i, DeathCause::BULLET, player.lifeFrames));
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // 無効になった弾を消す（残すと撃つたびに配列が伸び続ける）
+        self.bullets.retain(|b| b.getValid());
+
+        for newAsteroid in newAsteroids {
+            self.asteroids.push(newAsteroid);
+        }
+    }
+
+    // 現在の状態を描画
+    pub fn draw(&self, canvas: &mut dyn Draw, width: u32, height: u32) {
+        let view = self.getView(width, height);
+        // clear canvas
+        canvas.setDrawColor(Color::RGB(0, 0, 0)); // black
+        canvas.clear();
+
+        match self.state {
+            GameState::START | GameState::DEAD => {
+                // 一瞬で次の状態に移るので何も描かない
+            }
+            GameState::PLAY | GameState::EXPLODED => {
+                // draw ships
+                for player in &self.players {
+                    if let Some(ref myShip) = player.ship {
+                        let exploding = player.exploding || match self.state {
+                            GameState::EXPLODED => true,
+                            _ => false
+                        };
+                        if exploding {
+                            // 宇宙船破壊アニメーション
+                            myShip.drawExplosion(canvas, &view, &self.theme);
+                        } else {
+                            myShip.draw(canvas, &view, &self.theme);
+                        }
+                    }
+                }
+
+                // draw bullets
+                for bullet in &self.bullets {
+                    if bullet.getValid() {
+                        bullet.draw(canvas, &view, &self.theme);
+                    }
+                }
+
+                self.drawAsteroids(canvas, &view);
+                self.drawScore(canvas, width);
+                if self.worldScale > 1 {
+                    self.drawRadar(canvas, &view);
+                }
+            }
+            GameState::GAMEOVER => {
+                let wl: i32 = 100;
+                let hl: i32 = 130;
+                let hm: i32 = 30;
+                let over = tr(self.lang, "Game Over");
+                canvas.textOutColor(over, self.theme.text, (width as i32)/2-(over.chars().count() as i32)*wl/2, (height as i32)/2-hl/2, wl as u32, hl as u32);
+                for (i, player) in self.players.iter().enumerate() {
+                    let label = if self.players.len() > 1 {
+                        format!("{} {} {}: {}", tr(self.lang, "Player"), i+1, tr(self.lang, "Score"), player.score)
+                    } else {
+                        format!("{}: {}", tr(self.lang, "Score"), player.score)
+                    };
+                    let c = if self.players.len() > 1 { player.color } else { self.theme.text };
+                    canvas.textOutColor(&label, c, (width as i32)/2-(label.chars().count() as i32)*15/2, (height as i32)/2+hm*2+(i as i32)*40, 15, 30);
                 }
+
+                self.drawAsteroids(canvas, &view);
+            }
+        }
+    }
+
+    fn updateAsteroids(&mut self, width: u32, height: u32) {
+        for asteroid in &mut self.asteroids {
+            if asteroid.getValid() {
+                asteroid.update(width, height);
+            }
+        }
+
+        if self.asteroidCollisions {
+            Physics::collideAsteroids(&mut self.asteroids, self.restitution, width, height);
+        }
+    }
+
+    fn drawAsteroids(&self, canvas: &mut dyn Draw, view: &View) {
+        for asteroid in &self.asteroids {
+            if asteroid.getValid() {
+                asteroid.draw(canvas, view, &self.theme);
             }
         }
     }
-}
\ No newline at end of file
+
+    // レーダー（右下）
+    // 世界全体をカメラを中心にして縮小して描く。小惑星は小惑星の色、宇宙船はプレイヤーの色
+    fn drawRadar(&self, canvas: &mut dyn Draw, view: &View) {
+        let rw = RADAR_WIDTH;
+        let rh = RADAR_WIDTH * (view.worldHeight as i32) / (view.worldWidth as i32);
+        let x0 = (view.screenWidth as i32) - rw - 10;
+        let y0 = (view.screenHeight as i32) - rh - 10;
+        let sx = (rw as f64) / (view.worldWidth as f64);
+        let sy = (rh as f64) / (view.worldHeight as f64);
+
+        // 世界座標をレーダー上の位置にする
+        let toRadar = |x: f64, y: f64| -> Point {
+            let dx = Util::wrapDelta(x - view.x, view.worldWidth as f64);
+            let dy = Util::wrapDelta(y - view.y, view.worldHeight as f64);
+            return Point::new(x0 + rw / 2 + (dx * sx) as i32, y0 + rh / 2 + (dy * sy) as i32);
+        };
+
+        canvas.setBlendMode(BlendMode::Blend);
+        canvas.setDrawColor(Color::RGBA(0, 0, 0, 160));
+        canvas.fillRect(Some(Rect::new(x0, y0, rw as u32, rh as u32)));
+        canvas.setBlendMode(BlendMode::None);
+
+        // 枠と、画面に映っている範囲
+        canvas.setDrawColor(self.theme.text);
+        canvas.drawLines(&[Point::new(x0, y0), Point::new(x0 + rw, y0), Point::new(x0 + rw, y0 + rh), Point::new(x0, y0 + rh), Point::new(x0, y0)]);
+        let vw = ((view.screenWidth as f64) * sx) as i32 / 2;
+        let vh = ((view.screenHeight as f64) * sy) as i32 / 2;
+        let (cx, cy) = (x0 + rw / 2, y0 + rh / 2);
+        canvas.setDrawColor(self.theme.dim);
+        canvas.drawLines(&[Point::new(cx - vw, cy - vh), Point::new(cx + vw, cy - vh), Point::new(cx + vw, cy + vh), Point::new(cx - vw, cy + vh), Point::new(cx - vw, cy - vh)]);
+
+        canvas.setDrawColor(self.theme.asteroid);
+        for asteroid in &self.asteroids {
+            if asteroid.getValid() {
+                let p = toRadar(asteroid.getX(), asteroid.getY());
+                let s = if asteroid.getSize() >= self.tuning.splitMinSize { 2 } else { 1 };
+                canvas.fillRect(Some(Rect::new(p.x() - s / 2, p.y() - s / 2, s as u32, s as u32)));
+            }
+        }
+
+        for i in 0..self.players.len() {
+            if let Some(myShip) = self.getShip(i) {
+                let p = toRadar(myShip.getX(), myShip.getY());
+                canvas.setDrawColor(self.players[i].color);
+                canvas.fillRect(Some(Rect::new(p.x() - 2, p.y() - 2, 4, 4)));
+            }
+        }
+    }
+
+    // 文字の大きさと位置は配色のhudScale倍（文字を大きくする設定）
+    fn drawScore(&self, canvas: &mut dyn Draw, width: u32) {
+        let t = &self.theme;
+        let (cw, ch) = (t.hud(15) as u32, t.hud(30) as u32);
+        let score = tr(self.lang, "Score");
+        let shipsLeft = tr(self.lang, "Ships Left");
+        let wave = format!("{} {}", tr(self.lang, "Wave"), self.wave);
+        let waveX = (width as i32)/2 - (wave.chars().count() as i32)*(cw as i32)/2;
+        if self.players.len() == 1 {
+            let player = &self.players[0];
+            let ships = format!("{}: {}", shipsLeft, player.nShips);
+            canvas.textOutColor(&format!("{}: {}", score, player.score), t.text, 100, 10, cw, ch);
+            canvas.textOutColor(&ships, t.text, (width as i32) - ((ships.chars().count() as i32) + 3)*(cw as i32), 10, cw, ch);
+            canvas.textOutColor(&wave, t.text, waveX, 10, cw, ch);
+            self.drawEnergy(canvas, 0, 100, t.hud(45));
+            return;
+        }
+
+        // 2人以上 : 画面上部に左から並べる
+        let n = self.players.len() as i32;
+        for (i, player) in self.players.iter().enumerate() {
+            let c = player.color;
+            let x = if n == 2 && i == 1 { (width as i32) - t.hud(300) } else { 100 + (i as i32) * ((width as i32) - 200) / n };
+            let turn = if self.mode == PlayMode::ALTERNATE && i == self.current { "*" } else { " " };
+            canvas.textOutColor(&format!("{}P{} {}: {}", turn, i+1, score, player.score), c, x, 10, cw, ch);
+            canvas.textOutColor(&format!(" {}: {}", shipsLeft, player.nShips), c, x, t.hud(45), cw, ch);
+            if self.mode == PlayMode::COOP || i == self.current {
+                self.drawEnergy(canvas, i, x + 15, t.hud(80));
+            }
+        }
+        canvas.textOutColor(&wave, t.text, waveX, t.hud(95), cw, ch);
+    }
+
+    // シールドのエネルギー計（宇宙船がなければ描かない）
+    fn drawEnergy(&self, canvas: &mut dyn Draw, i: usize, x: i32, y: i32) {
+        let myShip = match self.getShip(i) {
+            Some(myShip) => myShip,
+            None => { return; }
+        };
+        let w = ENERGY_BAR_WIDTH;
+        let filled = ((w as f64) * myShip.getEnergy() / MyShip::MAX_ENERGY) as u32;
+        let c = self.theme.shield;
+        canvas.setDrawColor(if myShip.isShielded() { c } else { Color::RGB(c.r / 4 * 3, c.g / 4 * 3, c.b / 4 * 3) });
+        canvas.fillRect(Some(Rect::new(x, y, filled, 8)));
+        canvas.drawLines(&[Point::new(x, y), Point::new(x + w as i32, y), Point::new(x + w as i32, y + 8), Point::new(x, y + 8), Point::new(x, y)]);
+    }
+}
+
+// シールドの泡（中心(cx, cy)、半径r）に入ってきた小惑星を外向きに跳ね返す
+// 宇宙船の速度(svx, svy)から見て泡に向かっているときだけ反射し、泡の外に押し出す
+fn bounceOffShield(asteroid: &mut Asteroid::Asteroid, cx: f64, cy: f64, r: f64, svx: f64, svy: f64, width: u32, height: u32) {
+    let dx = Util::wrapDelta(asteroid.getX() - cx, width as f64);
+    let dy = Util::wrapDelta(asteroid.getY() - cy, height as f64);
+    let d = (dx*dx + dy*dy).sqrt();
+    let minDist = r + asteroid.getRadius();
+    if d >= minDist || d < 1e-6 {
+        return;
+    }
+    let nx = dx / d;
+    let ny = dy / d;
+
+    let rvx = asteroid.getVx() - svx;
+    let rvy = asteroid.getVy() - svy;
+    let vn = rvx * nx + rvy * ny;
+    if vn < 0.0 {
+        asteroid.setVelocity(asteroid.getVx() - 2.0 * vn * nx, asteroid.getVy() - 2.0 * vn * ny);
+    }
+    let x = Util::wrap(cx + nx * minDist, width as f64);
+    let y = Util::wrap(cy + ny * minDist, height as f64);
+    asteroid.setPos(x, y);
+}
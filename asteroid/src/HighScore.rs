@@ -0,0 +1,83 @@
+#![allow(non_snake_case)]
+
+use std::fs;
+
+pub const HIGHSCORE_FILE: &str = "highscore.txt";
+const MAX_ENTRIES: usize = 10;  // 保存する件数
+
+// ---------------------------------
+//  ハイスコア1件
+// ---------------------------------
+pub struct HighScoreEntry {
+    pub name: String,
+    pub score: i32
+}
+
+// ---------------------------------
+//  ハイスコア表
+//  ファイルには1行に "名前 スコア" の形で保存する
+// ---------------------------------
+pub struct HighScoreTable {
+    path: String,
+    entries: Vec<HighScoreEntry>   // スコアの高い順
+}
+
+impl HighScoreTable {
+    pub fn load(path: &str) -> Self {
+        let mut entries = Vec::new();
+
+        // ファイルがなければ空の表で始める
+        if let Ok(text) = fs::read_to_string(path) {
+            for line in text.lines() {
+                let mut it = line.split_whitespace();
+                if let (Some(name), Some(score)) = (it.next(), it.next()) {
+                    if let Ok(score) = score.parse::<i32>() {
+                        entries.push(HighScoreEntry { name: name.to_string(), score: score });
+                    }
+                }
+            }
+        }
+
+        let mut table = HighScoreTable {
+            path: path.to_string(),
+            entries: entries
+        };
+        table.sort();
+        return table;
+    }
+
+    pub fn save(&self) {
+        let mut text = String::new();
+        for e in &self.entries {
+            text.push_str(&format!("{} {}\n", e.name, e.score));
+        }
+        if let Err(e) = fs::write(&self.path, text) {
+            println!("failed to save high scores: {}", e);
+        }
+    }
+
+    pub fn getEntries(&self) -> &Vec<HighScoreEntry> {
+        return &self.entries;
+    }
+
+    // 表に載るスコアならtrue
+    pub fn qualifies(&self, score: i32) -> bool {
+        if score <= 0 {
+            return false;
+        }
+        if self.entries.len() < MAX_ENTRIES {
+            return true;
+        }
+        return score > self.entries[self.entries.len()-1].score;
+    }
+
+    pub fn insert(&mut self, name: &str, score: i32) {
+        self.entries.push(HighScoreEntry { name: name.to_string(), score: score });
+        self.sort();
+    }
+
+    fn sort(&mut self) {
+        self.entries.sort_by(|a, b| b.score.cmp(&a.score));
+        self.entries.truncate(MAX_ENTRIES);
+    }
+}
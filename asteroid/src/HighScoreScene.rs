@@ -0,0 +1,158 @@
+#![allow(non_snake_case)]
+
+use sdl2::pixels::Color;
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+
+use Scene::{Scene, SceneContext, Transition};
+use Draw::Draw;
+use TitleScene;
+use Lang::tr;
+
+const NAME_LEN: usize = 3;  // 名前の文字数（アーケード風に3文字）
+
+// ---------------------------------
+//  ハイスコア入力画面
+//  上下キーで文字を選び、左右キーで桁を移動、Enterで決定
+// ---------------------------------
+pub struct HighScoreScene {
+    scores: Vec<(usize, i32)>,  // 入力待ちの(プレイヤー番号, スコア)
+    score: i32,
+    player: usize,
+    name: [u8; NAME_LEN],
+    cursor: usize,      // 入力中の桁
+    entered: bool,      // 入力済みならtrue（表を表示中）
+    displayCount: i32   // 表を表示しているフレーム数
+}
+
+impl HighScoreScene {
+    pub fn new(mut scores: Vec<(usize, i32)>) -> Self {
+        // 先頭から順に入力してもらう
+        scores.reverse();
+        let (player, score) = scores.pop().unwrap_or((0, 0));
+        return HighScoreScene {
+            scores: scores,
+            score: score,
+            player: player,
+            name: [b'A'; NAME_LEN],
+            cursor: 0,
+            entered: false,
+            displayCount: 0
+        };
+    }
+
+    fn nameString(&self) -> String {
+        return self.name.iter().map(|c| *c as char).collect();
+    }
+}
+
+impl Scene for HighScoreScene {
+    fn handleInput(&mut self, ctx: &mut SceneContext, event: &Event) -> Transition {
+        let keycode = match *event {
+            Event::KeyDown { keycode: Some(k), .. } => k,
+            _ => { return Transition::NONE; }
+        };
+
+        if self.entered {
+            // 何かキーを押したらタイトルへ
+            return Transition::REPLACE(Box::new(TitleScene::TitleScene::new()));
+        }
+
+        match keycode {
+            Keycode::Up => {
+                let c = self.name[self.cursor];
+                self.name[self.cursor] = if c >= b'Z' { b'A' } else { c + 1 };
+            }
+            Keycode::Down => {
+                let c = self.name[self.cursor];
+                self.name[self.cursor] = if c <= b'A' { b'Z' } else { c - 1 };
+            }
+            Keycode::Left => {
+                if self.cursor > 0 {
+                    self.cursor -= 1;
+                }
+            }
+            Keycode::Right => {
+                if self.cursor < NAME_LEN-1 {
+                    self.cursor += 1;
+                }
+            }
+            Keycode::Return => {
+                // 決定．表に登録して保存
+                let name = self.nameString();
+                ctx.highScores.insert(&name, self.score);
+                ctx.highScores.save();
+
+                // 次のプレイヤーがいればその入力へ
+                match self.scores.pop() {
+                    Some((player, score)) => {
+                        self.player = player;
+                        self.score = score;
+                        self.name = [b'A'; NAME_LEN];
+                        self.cursor = 0;
+                    }
+                    None => {
+                        self.entered = true;
+                    }
+                }
+            }
+            _ => {}
+        }
+        return Transition::NONE;
+    }
+
+    fn update(&mut self, _ctx: &mut SceneContext) -> Transition {
+        if self.entered {
+            self.displayCount += 1;
+            if self.displayCount >= 300 {
+                return Transition::REPLACE(Box::new(TitleScene::TitleScene::new()));
+            }
+        }
+        return Transition::NONE;
+    }
+
+    fn render(&self, canvas: &mut dyn Draw, ctx: &SceneContext) {
+        let theme = ctx.settings.getTheme();
+        let width = ctx.width as i32;
+        let height = ctx.height as i32;
+
+        // clear canvas
+        canvas.setDrawColor(Color::RGB(0, 0, 0)); // black
+        canvas.clear();
+
+        if !self.entered {
+            let lang = ctx.settings.language;
+            let title = tr(lang, "New High Score!");
+            canvas.textOutColor(title, theme.text, width/2-(title.chars().count() as i32)*25, height/2-250, 50, 80);
+            let label = format!("{} {}  {}: {}", tr(lang, "Player"), self.player+1, tr(lang, "Score"), self.score);
+            canvas.textOutColor(&label, theme.text, width/2-(label.chars().count() as i32)*10, height/2-100, 20, 30);
+
+            // 名前（入力中の桁は強調色）
+            for i in 0..NAME_LEN {
+                let c = (self.name[i] as char).to_string();
+                let color = if i == self.cursor { theme.highlight } else { theme.text };
+                canvas.textOutColor(&c, color, width/2-105+(i as i32)*75, height/2-20, 60, 90);
+            }
+
+            let hint = tr(lang, "Up/Down: Letter  Left/Right: Move  Enter: OK");
+            canvas.textOutColor(hint, theme.text, width/2-(hint.chars().count() as i32)*10, height/2+150, 20, 30);
+        } else {
+            drawTable(canvas, ctx);
+        }
+    }
+}
+
+// ハイスコア表を描く（タイトル画面のアトラクトモードでも使う）
+pub fn drawTable(canvas: &mut dyn Draw, ctx: &SceneContext) {
+    let theme = ctx.settings.getTheme();
+    let width = ctx.width as i32;
+    let height = ctx.height as i32;
+
+    let title = tr(ctx.settings.language, "High Scores");
+    canvas.textOutColor(title, theme.text, width/2-(title.chars().count() as i32)*25, height/2-350, 50, 80);
+
+    for (i, e) in ctx.highScores.getEntries().iter().enumerate() {
+        let line = format!("{:>2}. {} {:>6}", i+1, e.name, e.score);
+        canvas.textOutColor(&line, theme.text, width/2-150, height/2-220+(i as i32)*45, 20, 35);
+    }
+}
@@ -0,0 +1,134 @@
+#![allow(non_snake_case)]
+
+use std::collections::HashSet;
+
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+
+// ---------------------------------
+//  1フレーム分の操作（プレイヤー1人分）
+// ---------------------------------
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct PlayerInput {
+    pub up: bool,       // 加速
+    pub down: bool,     // 逆噴射
+    pub left: bool,     // 左回転
+    pub right: bool,    // 右回転
+    pub fire: bool,     // 弾発射（押している間true）
+    pub shield: bool    // シールド（押している間true）
+}
+
+impl PlayerInput {
+    // 1バイトにまとめる（ネットワーク送信用）
+    pub fn toBits(&self) -> u8 {
+        let mut b = 0;
+        if self.up { b |= 1; }
+        if self.left { b |= 2; }
+        if self.right { b |= 4; }
+        if self.fire { b |= 8; }
+        if self.shield { b |= 16; }
+        if self.down { b |= 32; }
+        return b;
+    }
+
+    pub fn fromBits(b: u8) -> Self {
+        return PlayerInput {
+            up: b & 1 != 0,
+            down: b & 32 != 0,
+            left: b & 2 != 0,
+            right: b & 4 != 0,
+            fire: b & 8 != 0,
+            shield: b & 16 != 0
+        };
+    }
+}
+
+// ---------------------------------
+//  押されているキーの一覧
+//  KeyDown/KeyUpイベントから作る
+// ---------------------------------
+pub struct KeyState {
+    pressed: HashSet<Keycode>
+}
+
+impl KeyState {
+    pub fn new() -> Self {
+        return KeyState {
+            pressed: HashSet::new()
+        };
+    }
+
+    pub fn handleEvent(&mut self, event: &Event) {
+        match *event {
+            Event::KeyDown { keycode: Some(k), .. } => {
+                self.pressed.insert(k);
+            }
+            Event::KeyUp { keycode: Some(k), .. } => {
+                self.pressed.remove(&k);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn isPressed(&self, k: Keycode) -> bool {
+        return self.pressed.contains(&k);
+    }
+
+    pub fn clear(&mut self) {
+        self.pressed.clear();
+    }
+}
+
+// ---------------------------------
+//  プレイヤーごとのキー割り当て
+// ---------------------------------
+pub struct KeySet {
+    up: Keycode,
+    down: Keycode,
+    left: Keycode,
+    right: Keycode,
+    fire: Keycode,
+    shield: Keycode
+}
+
+impl KeySet {
+    // プレイヤー1 : カーソルキー（下で逆噴射） + スペース、シールドは右シフト
+    pub fn player1() -> Self {
+        return KeySet {
+            up: Keycode::Up,
+            down: Keycode::Down,
+            left: Keycode::Left,
+            right: Keycode::Right,
+            fire: Keycode::Space,
+            shield: Keycode::RShift
+        };
+    }
+
+    // プレイヤー2 : W/A/S/D + 左シフト、シールドは左Ctrl
+    pub fn player2() -> Self {
+        return KeySet {
+            up: Keycode::W,
+            down: Keycode::S,
+            left: Keycode::A,
+            right: Keycode::D,
+            fire: Keycode::LShift,
+            shield: Keycode::LCtrl
+        };
+    }
+
+    // i番目のプレイヤーのキー（swapがtrueなら1Pと2Pを入れ替える）
+    pub fn forPlayer(i: usize, swap: bool) -> Self {
+        return if (i == 0) != swap { KeySet::player1() } else { KeySet::player2() };
+    }
+
+    pub fn read(&self, keys: &KeyState) -> PlayerInput {
+        return PlayerInput {
+            up: keys.isPressed(self.up),
+            down: keys.isPressed(self.down),
+            left: keys.isPressed(self.left),
+            right: keys.isPressed(self.right),
+            fire: keys.isPressed(self.fire),
+            shield: keys.isPressed(self.shield)
+        };
+    }
+}
@@ -0,0 +1,162 @@
+#![allow(non_snake_case)]
+
+// ---------------------------------
+//  表示する言語
+// ---------------------------------
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Language {
+    ENGLISH,
+    SPANISH
+}
+
+pub const LANGUAGES: [Language; 2] = [Language::ENGLISH, Language::SPANISH];
+
+impl Language {
+    // 設定ファイルに書く名前
+    pub fn getCode(&self) -> &'static str {
+        return match *self {
+            Language::ENGLISH => "en",
+            Language::SPANISH => "es"
+        };
+    }
+
+    // 言語の名前（その言語で書く）
+    pub fn getName(&self) -> &'static str {
+        return match *self {
+            Language::ENGLISH => "English",
+            Language::SPANISH => "Español"
+        };
+    }
+
+    pub fn fromCode(code: &str) -> Option<Language> {
+        return LANGUAGES.iter().cloned().find(|l| l.getCode() == code);
+    }
+}
+
+// 英語の文言とスペイン語訳
+const SPANISH: [(&str, &str); 116] = [
+    ("Play", "Jugar"),
+    ("Mode", "Modo"),
+    ("Single Player", "Un jugador"),
+    ("Two Players (Alternate)", "Dos jugadores (por turnos)"),
+    ("Co-op", "Cooperativo"),
+    ("Resume Saved Game", "Continuar partida guardada"),
+    ("Network Game", "Partida en red"),
+    ("High Scores", "Récords"),
+    ("Stats & Achievements", "Estadísticas y logros"),
+    ("Settings", "Configuración"),
+    ("Quit", "Salir"),
+    ("Back", "Volver"),
+    ("Up/Down: Select  Enter/Click: Choose  Left/Right: Change", "Arriba/Abajo: elegir  Enter/Clic: aceptar  Izq/Der: cambiar"),
+    ("Up/Down: Select  Left/Right/Click: Change  Escape: Back", "Arriba/Abajo: elegir  Izq/Der/Clic: cambiar  Escape: volver"),
+    ("Arrow Keys: Ship Control   Space Bar: Fire   Right Shift: Shield", "Flechas: mover   Espacio: disparar   Mayús der.: escudo"),
+    ("W/A/D: Ship Control   Left Shift: Fire   Left Ctrl: Shield", "W/A/D: mover   Mayús izq.: disparar   Ctrl izq.: escudo"),
+    ("Difficulty", "Dificultad"),
+    ("Easy", "Fácil"),
+    ("Normal", "Normal"),
+    ("Hard", "Difícil"),
+    ("Ships", "Naves"),
+    ("Asteroids", "Asteroides"),
+    ("Friendly Fire (Co-op)", "Fuego amigo (coop.)"),
+    ("On", "Sí"),
+    ("Off", "No"),
+    ("Asteroid Collisions", "Choques entre asteroides"),
+    ("Bounciness", "Rebote"),
+    ("Fragments", "Fragmentos"),
+    ("Cut from rock", "Cortados de la roca"),
+    ("New rocks", "Rocas nuevas"),
+    ("Open Space", "Espacio abierto"),
+    ("Volume", "Volumen"),
+    ("Window", "Ventana"),
+    ("Windowed", "En ventana"),
+    ("Fullscreen", "Pantalla completa"),
+    ("Vector Glow", "Brillo vectorial"),
+    ("Controls", "Controles"),
+    ("Language", "Idioma"),
+    ("Theme", "Tema"),
+    ("Tuning file error, using built-in values", "Error en el archivo de ajustes, se usan los valores de fábrica"),
+    ("Color", "Color"),
+    ("Classic Mono", "Monocromo clásico"),
+    ("High Contrast", "Alto contraste"),
+    ("Colorblind Friendly", "Apto para daltónicos"),
+    ("Reduce Flashing", "Reducir destellos"),
+    ("Large HUD Text", "Texto grande"),
+    ("Game Speed", "Velocidad del juego"),
+    ("Net Test Latency", "Latencia de prueba (red)"),
+    ("Net Test Packet Loss", "Pérdida de paquetes (red)"),
+    ("Cannot resume", "No se puede continuar"),
+    ("DEMO  -  Press any key", "DEMO  -  Pulsa una tecla"),
+    ("Paused", "Pausa"),
+    ("Escape / P: Resume", "Escape / P: continuar"),
+    ("Q: Quit to Title", "Q: volver al título"),
+    ("Score", "Puntos"),
+    ("Ships Left", "Naves restantes"),
+    ("Wave", "Oleada"),
+    ("Game Over", "Fin del juego"),
+    ("Player", "Jugador"),
+    ("New High Score!", "¡Nuevo récord!"),
+    ("Up/Down: Letter  Left/Right: Move  Enter: OK", "Arriba/Abajo: letra  Izq/Der: mover  Enter: aceptar"),
+    ("Stats", "Estadísticas"),
+    ("This Session", "Esta sesión"),
+    ("All Time", "En total"),
+    ("Games Played", "Partidas jugadas"),
+    ("Shots Fired", "Disparos"),
+    ("Accuracy", "Precisión"),
+    ("Asteroids L/M/S", "Asteroides G/M/P"),
+    ("Longest Survival", "Mayor supervivencia"),
+    ("Lost to Asteroids", "Perdidas por asteroides"),
+    ("Lost to Teammates", "Perdidas por compañeros"),
+    ("Best Wave", "Mejor oleada"),
+    ("Achievements", "Logros"),
+    ("Achievement Unlocked", "Logro desbloqueado"),
+    ("Enter / Escape: Back", "Enter / Escape: volver"),
+    ("First Rock", "Primera roca"),
+    ("Destroy an asteroid", "Destruye un asteroide"),
+    ("Big Game Hunter", "Cazador de gigantes"),
+    ("Destroy 50 large asteroids", "Destruye 50 asteroides grandes"),
+    ("Demolition", "Demolición"),
+    ("Destroy 500 asteroids", "Destruye 500 asteroides"),
+    ("Sharpshooter", "Francotirador"),
+    ("50% accuracy over 200 shots", "50% de precisión en 200 disparos"),
+    ("Survivor", "Superviviente"),
+    ("Keep one ship alive for 2 minutes", "Mantén una nave viva 2 minutos"),
+    ("Wave Rider", "Surfista de oleadas"),
+    ("Reach wave 5", "Llega a la oleada 5"),
+    ("Oops", "Uy"),
+    ("Lose a ship to a teammate's bullet", "Pierde una nave por una bala de un compañero"),
+    ("Veteran", "Veterano"),
+    ("Play 10 games", "Juega 10 partidas"),
+    ("H: Host a game", "H: crear una partida"),
+    ("J: Join a game", "J: unirse a una partida"),
+    ("L: Loopback test (127.0.0.1)", "L: prueba local (127.0.0.1)"),
+    ("Simulated latency", "Latencia simulada"),
+    ("loss", "pérdida"),
+    ("Escape: Back", "Escape: volver"),
+    ("Host address:", "Dirección del anfitrión:"),
+    ("Enter: Connect  Escape: Back", "Enter: conectar  Escape: volver"),
+    ("Hosting on port", "Esperando en el puerto"),
+    ("Players", "Jugadores"),
+    ("Enter: Start", "Enter: empezar"),
+    ("Waiting for players...", "Esperando jugadores..."),
+    ("Escape: Cancel", "Escape: cancelar"),
+    ("Connecting to", "Conectando con"),
+    ("Joined as Player", "Unido como jugador"),
+    ("Waiting for host...", "Esperando al anfitrión..."),
+    ("Waiting for answer...", "Esperando respuesta..."),
+    ("Starting loopback test...", "Iniciando la prueba local..."),
+    ("You: Player", "Tú: jugador"),
+    ("Tick", "Tick"),
+    ("Delay", "Retardo"),
+    ("Loopback", "Local"),
+    ("DESYNC at tick", "DESINCRONIZACIÓN en el tick"),
+    ("Connection lost", "Conexión perdida"),
+    ("Waiting for other players...", "Esperando a los demás jugadores...")
+];
+
+// 英語の文言を選んでいる言語にする（訳がなければ英語のまま）
+pub fn tr(lang: Language, text: &'static str) -> &'static str {
+    return match lang {
+        Language::ENGLISH => text,
+        Language::SPANISH => SPANISH.iter().find(|t| t.0 == text).map_or(text, |t| t.1)
+    };
+}
@@ -0,0 +1,130 @@
+#![allow(non_snake_case)]
+
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use sdl2::mouse::MouseButton;
+use sdl2::rect::Rect;
+
+use Draw::Draw;
+use Theme::Theme;
+
+// ---------------------------------
+//  メニューの操作結果
+// ---------------------------------
+pub enum MenuAction {
+    NONE,
+    CHOOSE(usize),      // Enter、または左クリック
+    CHANGE(usize, i32), // 左右キー（-1, +1）、または右クリック(-1)
+    BACK                // Escape
+}
+
+// ---------------------------------
+//  縦に並んだメニュー（キーボードとマウスで選ぶ）
+//  項目は中央寄せ、1行の高さはrowHeight
+// ---------------------------------
+pub struct Menu {
+    selected: usize,
+    x: i32,         // 中央のx座標
+    y: i32,         // 1行目の上端
+    width: i32,     // クリックを受け付ける幅
+    rowHeight: i32,
+    charWidth: u32,
+    charHeight: u32
+}
+
+impl Menu {
+    pub fn new(x: i32, y: i32, width: i32, rowHeight: i32, charWidth: u32, charHeight: u32) -> Self {
+        return Menu {
+            selected: 0,
+            x: x,
+            y: y,
+            width: width,
+            rowHeight: rowHeight,
+            charWidth: charWidth,
+            charHeight: charHeight
+        };
+    }
+
+    pub fn getSelected(&self) -> usize {
+        return self.selected;
+    }
+
+    pub fn setSelected(&mut self, i: usize) {
+        self.selected = i;
+    }
+
+    // (x, y)にある項目
+    fn rowAt(&self, x: i32, y: i32, count: usize) -> Option<usize> {
+        if x < self.x - self.width / 2 || x >= self.x + self.width / 2 || y < self.y {
+            return None;
+        }
+        let i = ((y - self.y) / self.rowHeight) as usize;
+        return if i < count { Some(i) } else { None };
+    }
+
+    // count ... 今の項目数
+    pub fn handleEvent(&mut self, event: &Event, count: usize) -> MenuAction {
+        if count == 0 {
+            return MenuAction::NONE;
+        }
+        if self.selected >= count {
+            self.selected = count - 1;
+        }
+
+        match *event {
+            Event::KeyDown { keycode: Some(Keycode::Up), .. } => {
+                self.selected = (self.selected + count - 1) % count;
+            }
+            Event::KeyDown { keycode: Some(Keycode::Down), .. } => {
+                self.selected = (self.selected + 1) % count;
+            }
+            Event::KeyDown { keycode: Some(Keycode::Left), .. } => {
+                return MenuAction::CHANGE(self.selected, -1);
+            }
+            Event::KeyDown { keycode: Some(Keycode::Right), .. } => {
+                return MenuAction::CHANGE(self.selected, 1);
+            }
+            Event::KeyDown { keycode: Some(Keycode::Return), .. }
+            | Event::KeyDown { keycode: Some(Keycode::KpEnter), .. } => {
+                return MenuAction::CHOOSE(self.selected);
+            }
+            Event::KeyDown { keycode: Some(Keycode::Escape), .. } => {
+                return MenuAction::BACK;
+            }
+            Event::MouseMotion { x, y, .. } => {
+                // マウスを乗せた項目を選ぶ
+                if let Some(i) = self.rowAt(x, y, count) {
+                    self.selected = i;
+                }
+            }
+            Event::MouseButtonDown { mouse_btn, x, y, .. } => {
+                if let Some(i) = self.rowAt(x, y, count) {
+                    self.selected = i;
+                    match mouse_btn {
+                        MouseButton::Left => { return MenuAction::CHOOSE(i); }
+                        MouseButton::Right => { return MenuAction::CHANGE(i, -1); }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+        return MenuAction::NONE;
+    }
+
+    // 選んでいる項目は黄色で、後ろに帯を付ける
+    pub fn draw(&self, canvas: &mut dyn Draw, items: &[String], theme: &Theme) {
+        for (i, item) in items.iter().enumerate() {
+            let y = self.y + (i as i32) * self.rowHeight;
+            let w = (item.chars().count() as i32) * (self.charWidth as i32);
+            let x = self.x - w / 2;
+            if i == self.selected {
+                canvas.setDrawColor(theme.highlightBar);
+                canvas.fillRect(Some(Rect::new(self.x - self.width / 2, y, self.width as u32, (self.rowHeight - 4) as u32)));
+                canvas.textOutColor(item, theme.highlight, x, y, self.charWidth, self.charHeight);
+            } else {
+                canvas.textOutColor(item, theme.text, x, y, self.charWidth, self.charHeight);
+            }
+        }
+    }
+}
@@ -1,12 +1,18 @@
 #![allow(non_snake_case)]
 
-use sdl2::video::Window;
-use sdl2::render::Canvas;
 use sdl2::pixels::Color;
 use sdl2::rect::Point;
 
 use Util;
 use Bullet;
+use Draw::Draw;
+use Camera::View;
+use Theme::Theme;
+use SaveGame::{SaveWriter, SaveReader};
+use Tuning::Tuning;
+
+pub const MAX_ENERGY: f64 = 100.0;      // シールドのエネルギーの最大値
+const SHIELD_MIN_ENERGY: f64 = 20.0;    // シールドを張り直すのに必要なエネルギー
 
 // ---------------------------------
 // 宇宙船
@@ -19,14 +25,29 @@ pub struct MyShip {
     vy: f64,    // 速度ベクトル
     radius: f64,    // 半径
     rotation: f64,  // 回転角
+    rotationSpeed: f64, // 回転速度(ラジアン/フレーム)
+    maxRotationSpeed: f64,  // 最大回転速度(ラジアン/フレーム)
+    rotationAccel: f64, // 回転の角加速度(ラジアン/フレーム^2)
     maxSpeed: f64, // 最大速さ
-    explosionAnimCount: i32 // 爆発アニメーションカウント
+    thrust: f64,    // 加速係数
+    reverseThrust: f64, // 逆噴射の強さ（thrustに対する割合）
+    drag: f64,      // 1フレームで失う速度の割合
+    thrusting: i32, // 噴射中の向き（1:前進、-1:逆噴射、0:なし）
+    flameCount: i32,    // 噴射の炎のアニメーションカウント
+    bulletSpeed: f64,   // 弾の速さ
+    shieldDrain: f64,   // シールドを張っている間に減るエネルギー(/フレーム)
+    shieldRecharge: f64,    // シールドを張っていない間に溜まるエネルギー(/フレーム)
+    energy: f64,    // シールドのエネルギー（0〜MAX_ENERGY）
+    shielded: bool, // シールドを張っていればtrue
+    explosionAnimCount: i32, // 爆発アニメーションカウント
+    color: Color    // 描画色
 }
 
 impl MyShip {
     // 第1引数に &self, self を取らないメソッドはクラスメソッド 
     // (Rust用語では Associated Method)とみなされる
-    pub fn new(_x: f64, _y: f64, _radius: f64, _rot: f64, _maxSpeed: f64) -> Self {
+    // 速さや加速、回転、シールドの減り方はtuningの値を使う
+    pub fn new(_x: f64, _y: f64, _radius: f64, _rot: f64, tuning: &Tuning) -> Self {
         return MyShip {
             x: _x,
             y: _y,
@@ -34,8 +55,22 @@ impl MyShip {
             vy: 0.0,
             radius: _radius,
             rotation: _rot,
-            maxSpeed: _maxSpeed,
-            explosionAnimCount: 0
+            rotationSpeed: 0.0,
+            maxRotationSpeed: Util::deg2rad(tuning.shipRotationDeg),
+            rotationAccel: Util::deg2rad(tuning.shipRotationAccelDeg),
+            maxSpeed: tuning.shipMaxSpeed,
+            thrust: tuning.shipThrust,
+            reverseThrust: tuning.reverseThrust,
+            drag: tuning.shipDrag,
+            thrusting: 0,
+            flameCount: 0,
+            bulletSpeed: tuning.bulletSpeed,
+            shieldDrain: tuning.shieldDrain,
+            shieldRecharge: tuning.shieldRecharge,
+            energy: MAX_ENERGY,
+            shielded: false,
+            explosionAnimCount: 0,
+            color: Color::RGB(255, 255, 0)
         };
     }
 
@@ -47,6 +82,66 @@ impl MyShip {
         return self.y;
     }
 
+    pub fn getVx(&self) -> f64 {
+        return self.vx;
+    }
+
+    pub fn getVy(&self) -> f64 {
+        return self.vy;
+    }
+
+    pub fn getRadius(&self) -> f64 {
+        return self.radius;
+    }
+
+    pub fn getRotation(&self) -> f64 {
+        return self.rotation;
+    }
+
+    pub fn getRotationSpeed(&self) -> f64 {
+        return self.rotationSpeed;
+    }
+
+    pub fn getEnergy(&self) -> f64 {
+        return self.energy;
+    }
+
+    pub fn isShielded(&self) -> bool {
+        return self.shielded;
+    }
+
+    // 3角形の中心（シールドの中心）
+    pub fn getCenter(&self) -> (f64, f64) {
+        return (self.x + self.radius * self.rotation.cos(), self.y + self.radius * self.rotation.sin());
+    }
+
+    // シールドの半径
+    pub fn getShieldRadius(&self) -> f64 {
+        return self.radius * 1.6;
+    }
+
+    // シールドの操作（hold ... シールドキーを押していればtrue）
+    // 押している間はエネルギーを使い、切れたら消える。張り直すにはSHIELD_MIN_ENERGYまで溜める
+    pub fn updateShield(&mut self, hold: bool) {
+        if hold && (self.shielded || self.energy >= SHIELD_MIN_ENERGY) {
+            self.energy -= self.shieldDrain;
+            self.shielded = self.energy > 0.0;
+            self.energy = self.energy.max(0.0);
+        } else {
+            self.shielded = false;
+            self.energy = (self.energy + self.shieldRecharge).min(MAX_ENERGY);
+        }
+    }
+
+    pub fn setVelocity(&mut self, vx: f64, vy: f64) {
+        self.vx = vx;
+        self.vy = vy;
+    }
+
+    pub fn setColor(&mut self, c: Color) {
+        self.color = c;
+    }
+
     pub fn clearExplosionAnimCount(&mut self) {
         self.explosionAnimCount = 0;
     }
@@ -60,6 +155,10 @@ impl MyShip {
         let wf = (width as i32) as f64;
         let hf = (height as i32) as f64;
 
+        // 抵抗で少しずつ遅くなる
+        self.vx *= 1.0 - self.drag;
+        self.vy *= 1.0 - self.drag;
+
         self.x += self.vx;
         self.y += self.vy;
 
@@ -80,33 +179,60 @@ impl MyShip {
         };
     }
 
-    pub fn updateDir(&mut self) {
+    // 加速（forward ... 前進、reverse ... 逆噴射）
+    // 最大速さを超えたら速さだけ最大に揃えるので、最高速でも向きは変えられる
+    pub fn updateDir(&mut self, forward: bool, reverse: bool) {
+        self.thrusting = if forward && !reverse {
+            1
+        } else if reverse && !forward && self.reverseThrust > 0.0 {
+            -1
+        } else {
+            0
+        };
+        if self.thrusting == 0 {
+            self.flameCount = 0;
+            return;
+        }
+        self.flameCount += 1;
+
         // 加速度ベクトル計算
-        let k = 0.5;    // 加速係数
+        let k = if self.thrusting > 0 { self.thrust } else { -self.thrust * self.reverseThrust };
         let ax = k * self.rotation.cos();
         let ay = k * self.rotation.sin();
 
         // 速度ベクトル更新
-        let vx1 = self.vx + ax;
-        let vy1 = self.vy + ay;
-        
-        // 速さを計算
-        let speed = (vx1*vx1 + vy1*vy1).sqrt();
+        self.vx += ax;
+        self.vy += ay;
 
-        if (speed >= 0.0) && (speed < self.maxSpeed) {
-            // 速度範囲内なら現在値を更新
-            self.vx = vx1;
-            self.vy = vy1;
+        // 速さを計算
+        let speed = (self.vx*self.vx + self.vy*self.vy).sqrt();
+        if speed > self.maxSpeed {
+            self.vx *= self.maxSpeed / speed;
+            self.vy *= self.maxSpeed / speed;
         }
     }
 
-    pub fn incrRotation(&mut self, d: f64) {
-        self.rotation += d;
+    // 回転（dir ... -1:左、1:右、0:離した）
+    // 押している間は角加速度で速くなり、離すと同じ割合で止まる
+    pub fn updateRotation(&mut self, dir: i32) {
+        let a = self.rotationAccel;
+        if dir != 0 {
+            let target = (dir as f64) * self.maxRotationSpeed;
+            self.rotationSpeed = if self.rotationSpeed < target {
+                (self.rotationSpeed + a).min(target)
+            } else {
+                (self.rotationSpeed - a).max(target)
+            };
+        } else if self.rotationSpeed > 0.0 {
+            self.rotationSpeed = (self.rotationSpeed - a).max(0.0);
+        } else {
+            self.rotationSpeed = (self.rotationSpeed + a).min(0.0);
+        }
+        self.rotation += self.rotationSpeed;
     }
 
-    pub fn draw(&self, canvas: &mut Canvas<Window>) {
-        canvas.set_draw_color(Color::RGB(255, 255, 0));
-
+    // 宇宙船の形（閉じた折れ線）
+    pub fn getOutline(&self) -> [Point; 4] {
         // ３角形の中央
         let x = self.radius * self.rotation.cos() + self.x;
         let y = self.radius * self.rotation.sin() + self.y;
@@ -122,28 +248,176 @@ impl MyShip {
         theta = self.rotation + Util::deg2rad(210.0);
         let p3: Point = Point::new((x+tr*theta.cos()) as i32, (y+tr*theta.sin()) as i32);
 
+        return [p1, p2, p3, p1];
+    }
+
+    // 噴射の炎（閉じた折れ線、噴射していなければNone）
+    // 前進は後ろから、逆噴射は先端から短く出す。長さはフレームごとに揺らす
+    // steady ... trueならちらつかせない（点滅を抑える設定）
+    fn getFlame(&self, steady: bool) -> Option<[Point; 4]> {
+        if self.thrusting == 0 {
+            return None;
+        }
+        let (cx, cy) = self.getCenter();
+        let r = self.radius;
+        let flicker = if steady { 1.0 } else { [0.6, 1.0, 0.8, 1.2][(self.flameCount / 2 % 4) as usize] };
+
+        // 炎の根元（3角形の辺の上の2点）と先端
+        let (baseDist, baseHalf, len, dir) = if self.thrusting > 0 {
+            (r * 0.87, r * 0.3, r * 1.2 * flicker, self.rotation + Util::deg2rad(180.0))
+        } else {
+            (r * 0.6, r * 0.15, r * 0.6 * flicker, self.rotation)
+        };
+        let (ux, uy) = (dir.cos(), dir.sin());
+        let (nx, ny) = (-uy, ux);
+        let bx = cx + ux * baseDist;
+        let by = cy + uy * baseDist;
+        let p1 = Point::new((bx + nx * baseHalf) as i32, (by + ny * baseHalf) as i32);
+        let p2 = Point::new((bx + ux * len) as i32, (by + uy * len) as i32);
+        let p3 = Point::new((bx - nx * baseHalf) as i32, (by - ny * baseHalf) as i32);
+        return Some([p1, p2, p3, p1]);
+    }
+
+    pub fn save(&self, w: &mut SaveWriter) {
+        w.line("ship");
+        w.f64(self.x);
+        w.f64(self.y);
+        w.f64(self.vx);
+        w.f64(self.vy);
+        w.f64(self.radius);
+        w.f64(self.rotation);
+        w.f64(self.maxSpeed);
+        w.f64(self.thrust);
+        w.f64(self.bulletSpeed);
+        w.f64(self.shieldDrain);
+        w.f64(self.shieldRecharge);
+        w.f64(self.energy);
+        w.bool(self.shielded);
+        w.f64(self.rotationSpeed);
+        w.f64(self.maxRotationSpeed);
+        w.f64(self.rotationAccel);
+        w.f64(self.reverseThrust);
+        w.f64(self.drag);
+        w.i32(self.explosionAnimCount);
+        w.color(self.color);
+    }
+
+    pub fn load(r: &mut SaveReader) -> Result<Self, String> {
+        r.expect("ship")?;
+        let x = r.f64()?;
+        let y = r.f64()?;
+        let vx = r.f64()?;
+        let vy = r.f64()?;
+        let radius = r.f64()?;
+        let rotation = r.f64()?;
+        let maxSpeed = r.f64()?;
+        let thrust = r.f64()?;
+        let bulletSpeed = r.f64()?;
+        let shieldDrain = r.f64()?;
+        let shieldRecharge = r.f64()?;
+        let energy = r.f64()?;
+        let shielded = r.bool()?;
+        let rotationSpeed = r.f64()?;
+        let maxRotationSpeed = r.f64()?;
+        let rotationAccel = r.f64()?;
+        let reverseThrust = r.f64()?;
+        let drag = r.f64()?;
+        return Ok(MyShip {
+            x: x,
+            y: y,
+            vx: vx,
+            vy: vy,
+            radius: radius,
+            rotation: rotation,
+            rotationSpeed: rotationSpeed,
+            maxRotationSpeed: maxRotationSpeed,
+            rotationAccel: rotationAccel,
+            maxSpeed: maxSpeed,
+            thrust: thrust,
+            reverseThrust: reverseThrust,
+            drag: drag,
+            thrusting: 0,
+            flameCount: 0,
+            bulletSpeed: bulletSpeed,
+            shieldDrain: shieldDrain,
+            shieldRecharge: shieldRecharge,
+            energy: energy.max(0.0).min(MAX_ENERGY),
+            shielded: shielded,
+            explosionAnimCount: r.i32()?,
+            color: r.color()?
+        });
+    }
+
+    // 画面の端にかかっていたら反対側にも描く
+    pub fn draw(&self, canvas: &mut dyn Draw, view: &View, theme: &Theme) {
+        canvas.setDrawColor(self.color);
+
         // draw_lines()で3角形を描く
-        let ps = [p1, p2, p3, p1];
-        let _ = canvas.draw_lines(ps.as_ref()); // [Point]から&[Point]を生成する
+        // （3角形は中心から最大で半径の2倍まで広がる）
+        let ps = self.getOutline();
+        let flame = self.getFlame(theme.reducedFlashing);
+        for (ox, oy) in view.offsets(self.x, self.y, self.radius * 3.0) {
+            let shifted: Vec<Point> = ps.iter().map(|p| p.offset(ox as i32, oy as i32)).collect();
+            canvas.drawLines(shifted.as_ref()); // [Point]から&[Point]を生成する
+
+            if let Some(ref flame) = flame {
+                let shifted: Vec<Point> = flame.iter().map(|p| p.offset(ox as i32, oy as i32)).collect();
+                canvas.setDrawColor(theme.flame);
+                canvas.drawLines(shifted.as_ref());
+                canvas.setDrawColor(self.color);
+            }
+        }
+
+        if self.shielded {
+            self.drawShield(canvas, view, theme);
+        }
     }
 
-    pub fn drawExplosion(&self, canvas: &mut Canvas<Window>) {
-        canvas.set_draw_color(Color::RGB(255, 255, 0));
+    // シールドの泡（エネルギーが減ると暗くなる）
+    fn drawShield(&self, canvas: &mut dyn Draw, view: &View, theme: &Theme) {
+        let (cx, cy) = self.getCenter();
+        let r = self.getShieldRadius();
+        let k = 0.4 + 0.6 * self.energy / MAX_ENERGY;
+        let c = theme.shield;
+        canvas.setDrawColor(Color::RGB(((c.r as f64) * k) as u8, ((c.g as f64) * k) as u8, ((c.b as f64) * k) as u8));
 
-        // ３角形の中央
-        let x = self.radius * self.rotation.cos() + self.x;
-        let y = self.radius * self.rotation.sin() + self.y;
+        let N = 24;
+        for (ox, oy) in view.offsets(cx, cy, r) {
+            let ps: Vec<Point> = (0..N+1).map(|i| {
+                let theta = Util::deg2rad(360.0 * (i as f64) / (N as f64));
+                return Point::new((cx + r * theta.cos() + ox) as i32, (cy + r * theta.sin() + oy) as i32);
+            }).collect();
+            canvas.drawLines(ps.as_ref());
+        }
+    }
 
+    // 点滅を抑える設定では、暗めの色で回転させずに広げるだけにする
+    pub fn drawExplosion(&self, canvas: &mut dyn Draw, view: &View, theme: &Theme) {
         // 爆発半径
         let er = (self.explosionAnimCount as f64) * 5.0;
 
+        let extent = self.radius + er + 10.0;
+        for (ox, oy) in view.offsets(self.x, self.y, extent) {
+            self.drawExplosionAt(canvas, ox, oy, er, theme.reducedFlashing);
+        }
+    }
+
+    fn drawExplosionAt(&self, canvas: &mut dyn Draw, ox: f64, oy: f64, er: f64, calm: bool) {
+        let c = self.color;
+        canvas.setDrawColor(if calm { Color::RGB(c.r / 2, c.g / 2, c.b / 2) } else { c });
+        let spin = if calm { 0.0 } else { self.explosionAnimCount as f64 };
+
+        // ３角形の中央
+        let x = self.radius * self.rotation.cos() + self.x + ox;
+        let y = self.radius * self.rotation.sin() + self.y + oy;
+
         let N = 12;
         for i in 0..N {
-            let theta: f64 = Util::deg2rad(360.0 * (i as f64) / (N as f64) + (self.explosionAnimCount as f64) * 2.0);
+            let theta: f64 = Util::deg2rad(360.0 * (i as f64) / (N as f64) + spin * 2.0);
             let cp: Point = Point::new((x+er*theta.cos()) as i32, (y+er*theta.sin()) as i32);
 
-            let ro1 = Util::deg2rad((self.explosionAnimCount as f64) * 5.0);
-            let ro2 = Util::deg2rad((self.explosionAnimCount as f64) * 5.0 + 180.0);
+            let ro1 = Util::deg2rad(spin * 5.0);
+            let ro2 = Util::deg2rad(spin * 5.0 + 180.0);
             
             let er2 = 10.0; // 爆発半径2(固定値)
             let p1: Point = Point::new((er2*ro1.cos()) as i32 + cp.x, (er2*ro1.sin()) as i32 + cp.y);
@@ -151,18 +425,28 @@ impl MyShip {
 
             // draw_lines()で3角形を描く
             let ps = [p1, p2];
-            let _ = canvas.draw_lines(ps.as_ref()); // [Point]から&[Point]を生成する
+            canvas.drawLines(ps.as_ref()); // [Point]から&[Point]を生成する
         }
     }
 
-    pub fn fire(&self) -> Bullet::Bullet {
+    // 衝突判定（同士討ち用、画面の端をまたいでいても当たる）
+    // (tx, ty) ... 衝突判定対象座標
+    pub fn hitTest(&self, tx: f64, ty: f64, width: u32, height: u32) -> bool {
+        let tdx = Util::wrapDelta(tx - self.x, width as f64);
+        let tdy = Util::wrapDelta(ty - self.y, height as f64);
+        let tr = (tdx*tdx + tdy*tdy).sqrt();
+
+        return tr < self.radius;
+    }
+
+    // 弾を撃つ（宇宙船の速度を弾の速度に足す）
+    // life ... 弾が消えるまでのフレーム数
+    pub fn fire(&self, life: i32) -> Bullet::Bullet {
         let x = self.x;
         let y = self.y;
-        let vx = self.rotation.cos() * 5.0;
-        let vy = self.rotation.sin() * 5.0;
-
-        // println!("new bullet : p=({}, {}), v=({}, {})", x, y, vx, vy);
+        let vx = self.rotation.cos() * self.bulletSpeed + self.vx;
+        let vy = self.rotation.sin() * self.bulletSpeed + self.vy;
 
-        return Bullet::Bullet::new(x, y, vx, vy);
+        return Bullet::Bullet::new(x, y, vx, vy, life);
     }
 }
@@ -0,0 +1,723 @@
+#![allow(non_snake_case)]
+
+use std::io;
+use std::net::{UdpSocket, SocketAddr, ToSocketAddrs};
+use std::time::{Duration, Instant};
+use std::collections::HashMap;
+
+use rand::Rng;
+
+use Input::PlayerInput;
+use Random::Random;
+use Scene::Settings;
+
+// ---------------------------------
+//  ネットワーク対戦（UDP、ロックステップ方式）
+//
+//  ホストが各クライアントの操作を集めて全員に配り、
+//  全員分の操作がそろったtickだけゲームを進める．
+//  ゲームは決定的（同じシード・同じ操作なら同じ結果）なので、
+//  送るのは操作だけでよい．
+//
+//  パケットが消えても困らないように、相手から受信確認(ack)が
+//  来るまで同じ操作を何度でも送り直す．
+// ---------------------------------
+
+pub const DEFAULT_PORT: u16 = 7777;
+pub const MAX_PLAYERS: usize = 4;
+pub const INPUT_DELAY: u32 = 3;     // ローカルの操作を何tick先に予約するか（遅延を隠す）
+pub const MAX_INPUT_DELAY: u32 = 30;    // 回線が遅い時に伸ばす上限
+pub const HASH_INTERVAL: u32 = 60;  // 同期ずれチェックの間隔(tick)
+
+const MAX_RESEND: u32 = 32;         // 1パケットで送る最大tick数
+const TIMEOUT_SECS: u64 = 10;       // これだけ受信がなければ切断とみなす
+const RESEND_MS: u64 = 200;         // HELLO, STARTの再送間隔
+
+// パケットの種類
+const MSG_HELLO: u8 = 1;    // client -> host : 参加したい
+const MSG_WELCOME: u8 = 2;  // host -> client : 参加OK
+const MSG_START: u8 = 3;    // host -> client : ゲーム開始（シードと設定）
+const MSG_INPUT: u8 = 4;    // 操作とack
+const MSG_BYE: u8 = 5;      // 抜ける
+
+// ---------------------------------
+//  疑似回線
+//  送信パケットに遅延とパケットロスを加える（1台でのテスト用）
+// ---------------------------------
+pub struct NetSim {
+    latencyMs: u32,     // 片道の遅延
+    jitterMs: u32,      // 遅延のゆらぎ
+    lossPercent: u32,   // パケットロス率(%)
+    rng: Random,
+    queue: Vec<(Instant, SocketAddr, Vec<u8>)>  // 送信待ち(送信時刻, 宛先, データ)
+}
+
+impl NetSim {
+    pub fn new(latencyMs: u32, lossPercent: u32) -> Self {
+        return NetSim {
+            latencyMs: latencyMs,
+            jitterMs: latencyMs / 4,
+            lossPercent: lossPercent,
+            rng: Random::new(Random::seedFromTime()),
+            queue: Vec::new()
+        };
+    }
+
+    // 遅延もロスもなし
+    pub fn none() -> Self {
+        return NetSim::new(0, 0);
+    }
+
+    fn send(&mut self, socket: &UdpSocket, addr: SocketAddr, data: Vec<u8>) {
+        if self.lossPercent > 0 && self.rng.gen_range(0, 100) < self.lossPercent {
+            // パケットロス
+            return;
+        }
+
+        if self.latencyMs == 0 {
+            let _ = socket.send_to(&data, addr);
+            return;
+        }
+
+        let jitter = if self.jitterMs > 0 { self.rng.gen_range(0, self.jitterMs * 2 + 1) } else { 0 };
+        let delay = (self.latencyMs + jitter).saturating_sub(self.jitterMs);
+        let due = Instant::now() + Duration::from_millis(delay as u64);
+        self.queue.push((due, addr, data));
+    }
+
+    // 時間が来たパケットを送信
+    fn flush(&mut self, socket: &UdpSocket) {
+        let now = Instant::now();
+        let mut i = 0;
+        while i < self.queue.len() {
+            if self.queue[i].0 <= now {
+                let (_, addr, data) = self.queue.swap_remove(i);
+                let _ = socket.send_to(&data, addr);
+            } else {
+                i += 1;
+            }
+        }
+    }
+}
+
+// ---------------------------------
+//  パケットの読み書き
+// ---------------------------------
+fn putU32(buf: &mut Vec<u8>, v: u32) {
+    for i in 0..4 {
+        buf.push((v >> (i * 8)) as u8);
+    }
+}
+
+fn putU64(buf: &mut Vec<u8>, v: u64) {
+    for i in 0..8 {
+        buf.push((v >> (i * 8)) as u8);
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        return Reader { data: data, pos: 0 };
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        if self.pos >= self.data.len() {
+            return None;
+        }
+        self.pos += 1;
+        return Some(self.data[self.pos-1]);
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let mut v: u32 = 0;
+        for i in 0..4 {
+            v |= (self.u8()? as u32) << (i * 8);
+        }
+        return Some(v);
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        let mut v: u64 = 0;
+        for i in 0..8 {
+            v |= (self.u8()? as u64) << (i * 8);
+        }
+        return Some(v);
+    }
+}
+
+fn bindSocket(addr: &str) -> io::Result<UdpSocket> {
+    let socket = UdpSocket::bind(addr)?;
+    socket.set_nonblocking(true)?;
+    return Ok(socket);
+}
+
+// 受信できるだけ受信する
+fn recvAll(socket: &UdpSocket) -> Vec<(SocketAddr, Vec<u8>)> {
+    let mut packets = Vec::new();
+    let mut buf = [0u8; 2048];
+    loop {
+        match socket.recv_from(&mut buf) {
+            Ok((n, addr)) => packets.push((addr, buf[..n].to_vec())),
+            Err(_) => break     // WouldBlock（もう来ていない）またはエラー
+        }
+    }
+    return packets;
+}
+
+// ---------------------------------
+//  ホスト側のロビー（参加者を待つ）
+// ---------------------------------
+pub struct NetHost {
+    socket: UdpSocket,
+    sim: NetSim,
+    clients: Vec<SocketAddr>    // 参加したクライアント（プレイヤー1, 2, ...）
+}
+
+impl NetHost {
+    pub fn bind(port: u16, sim: NetSim) -> io::Result<Self> {
+        let socket = bindSocket(&format!("0.0.0.0:{}", port))?;
+        return Ok(NetHost {
+            socket: socket,
+            sim: sim,
+            clients: Vec::new()
+        });
+    }
+
+    pub fn getPort(&self) -> u16 {
+        return match self.socket.local_addr() {
+            Ok(addr) => addr.port(),
+            Err(_) => 0
+        };
+    }
+
+    // ホスト自身を含めた人数
+    pub fn getPlayerCount(&self) -> usize {
+        return self.clients.len() + 1;
+    }
+
+    pub fn poll(&mut self) {
+        for (addr, data) in recvAll(&self.socket) {
+            if data.is_empty() || data[0] != MSG_HELLO {
+                continue;
+            }
+
+            // 新しい参加者なら登録（WELCOMEが消えた時のために何度でも返事をする）
+            let index = match self.clients.iter().position(|a| *a == addr) {
+                Some(i) => i + 1,
+                None => {
+                    if self.clients.len() + 1 >= MAX_PLAYERS {
+                        continue;   // 満員
+                    }
+                    self.clients.push(addr);
+                    self.clients.len()
+                }
+            };
+            self.sim.send(&self.socket, addr, vec![MSG_WELCOME, index as u8]);
+        }
+        self.sim.flush(&self.socket);
+    }
+
+    // ゲーム開始．以降はSessionでやりとりする
+    pub fn start(self, settings: &Settings, seed: u64) -> Session {
+        let nPlayers = self.getPlayerCount();
+        let mut peers = Vec::new();
+        for (i, addr) in self.clients.iter().enumerate() {
+            peers.push(Peer::new(*addr, i + 1, nPlayers));
+        }
+        return Session::new(self.socket, self.sim, true, 0, nPlayers, seed, settings.clone(), peers);
+    }
+}
+
+// ---------------------------------
+//  クライアント側のロビー（ホストに参加してSTARTを待つ）
+// ---------------------------------
+pub struct NetClient {
+    socket: UdpSocket,
+    sim: NetSim,
+    host: SocketAddr,
+    index: Option<usize>,   // ホストから割り当てられたプレイヤー番号
+    lastHello: Option<Instant>,
+    created: Instant
+}
+
+impl NetClient {
+    pub fn connect(addr: &str, sim: NetSim) -> io::Result<Self> {
+        // ソケットはIPv4で開くのでIPv4のアドレスを使う
+        let host = match addr.to_socket_addrs()?.find(|a| a.is_ipv4()) {
+            Some(a) => a,
+            None => { return Err(io::Error::new(io::ErrorKind::InvalidInput, "bad address")); }
+        };
+        let socket = bindSocket("0.0.0.0:0")?;
+        return Ok(NetClient {
+            socket: socket,
+            sim: sim,
+            host: host,
+            index: None,
+            lastHello: None,
+            created: Instant::now()
+        });
+    }
+
+    // 参加できていればプレイヤー番号
+    pub fn getIndex(&self) -> Option<usize> {
+        return self.index;
+    }
+
+    // ホストから返事がないまま時間切れならtrue
+    pub fn isTimedOut(&self) -> bool {
+        return self.index.is_none() && self.created.elapsed() > Duration::from_secs(TIMEOUT_SECS);
+    }
+
+    // STARTを受け取ったらSessionを返す
+    pub fn poll(&mut self) -> Option<Session> {
+        let mut session = None;
+        for (addr, data) in recvAll(&self.socket) {
+            if addr != self.host || data.is_empty() {
+                continue;
+            }
+            let mut r = Reader::new(&data[1..]);
+            match data[0] {
+                MSG_WELCOME => {
+                    self.index = r.u8().map(|i| i as usize);
+                }
+                MSG_START => {
+                    session = self.readStart(&mut r);
+                }
+                _ => {}
+            }
+        }
+
+        if session.is_none() && self.index.is_none() {
+            // 返事が来るまでHELLOを送り続ける
+            let due = match self.lastHello {
+                Some(t) => t.elapsed() > Duration::from_millis(RESEND_MS),
+                None => true
+            };
+            if due {
+                self.sim.send(&self.socket, self.host, vec![MSG_HELLO]);
+                self.lastHello = Some(Instant::now());
+            }
+        }
+        self.sim.flush(&self.socket);
+        return session;
+    }
+
+    fn readStart(&mut self, r: &mut Reader) -> Option<Session> {
+        let nPlayers = r.u8()? as usize;
+        let index = r.u8()? as usize;
+        let seed = r.u64()?;
+        let mut settings = Settings::new();
+        settings.nShips = r.u8()? as i32;
+        settings.nAsteroids = r.u8()? as i32;
+        settings.friendlyFire = r.u8()? != 0;
+        settings.asteroidCollisions = r.u8()? != 0;
+        settings.restitution = r.u8()? as f64 / 100.0;
+        settings.cutFragments = r.u8()? != 0;
+        settings.openSpace = r.u8()? != 0;
+        settings.tuning.shipMaxSpeed = f64::from_bits(r.u64()?);
+        settings.tuning.shipRotationDeg = f64::from_bits(r.u64()?);
+        settings.tuning.shipThrust = f64::from_bits(r.u64()?);
+        settings.tuning.bulletSpeed = f64::from_bits(r.u64()?);
+        settings.tuning.splitMinSize = r.u8()? as i32;
+        settings.tuning.shieldDrain = f64::from_bits(r.u64()?);
+        settings.tuning.shieldRecharge = f64::from_bits(r.u64()?);
+        settings.tuning.bulletLifetime = r.u32()? as i32;
+        settings.tuning.maxBullets = r.u8()? as i32;
+        settings.tuning.fireCooldown = r.u8()? as i32;
+        settings.tuning.shipDrag = f64::from_bits(r.u64()?);
+        settings.tuning.reverseThrust = f64::from_bits(r.u64()?);
+        settings.tuning.shipRotationAccelDeg = f64::from_bits(r.u64()?);
+
+        let socket = self.socket.try_clone().ok()?;
+        let sim = NetSim::new(self.sim.latencyMs, self.sim.lossPercent);
+        let peers = vec![Peer::new(self.host, 0, nPlayers)];
+        return Some(Session::new(socket, sim, false, index, nPlayers, seed, settings, peers));
+    }
+}
+
+// ---------------------------------
+//  通信相手
+// ---------------------------------
+struct Peer {
+    addr: SocketAddr,
+    player: usize,      // 相手自身のプレイヤー番号
+    acked: Vec<u32>,    // プレイヤーごとに、相手が受信済みのtick数
+    lastRecv: Instant,
+    started: bool,      // 相手がゲームを開始したらtrue（ホスト用）
+    checkedTick: Option<u32>    // 相手のハッシュ値と比べ終わった最後のtick
+}
+
+impl Peer {
+    fn new(addr: SocketAddr, player: usize, nPlayers: usize) -> Self {
+        return Peer {
+            addr: addr,
+            player: player,
+            acked: vec![INPUT_DELAY; nPlayers],
+            lastRecv: Instant::now(),
+            started: false,
+            checkedTick: None
+        };
+    }
+}
+
+// ---------------------------------
+//  ゲーム中の通信（ロックステップ）
+// ---------------------------------
+pub struct Session {
+    socket: UdpSocket,
+    sim: NetSim,
+    isHost: bool,
+    localPlayer: usize,     // 自分のプレイヤー番号（ホストは0）
+    nPlayers: usize,
+    seed: u64,
+    settings: Settings,
+    peers: Vec<Peer>,       // ホスト : 全クライアント / クライアント : ホストのみ
+    inputs: Vec<Vec<u8>>,   // プレイヤーごと、tickごとの操作
+    created: Vec<Instant>,  // 自分の操作を作った時刻（往復時間の計測用）
+    rttMs: f64,             // 往復時間の推定値
+    localTick: u32,         // 次に自分の操作を入れるtick
+    simTick: u32,           // 次にゲームを進めるtick
+    hashes: HashMap<u32, u64>,          // 自分のゲーム状態のハッシュ値（全員と比べ終わったものは捨てる）
+    lastHash: Option<(u32, u64)>,       // 最後に記録したハッシュ値（送信用）
+    remoteHashes: HashMap<(usize, u32), u64>,   // 相手から届いた、自分がまだ進んでいないtickのハッシュ値（peers番号, tick）
+    desyncTick: Option<u32>,    // 同期ずれが見つかったtick
+    lastStart: Option<Instant>, // 最後にSTARTを送った時刻（ホスト用）
+    closed: bool            // 相手が抜けたらtrue
+}
+
+impl Session {
+    fn new(
+        socket: UdpSocket, sim: NetSim,
+        isHost: bool, localPlayer: usize, nPlayers: usize,
+        seed: u64, settings: Settings, peers: Vec<Peer>
+    ) -> Self {
+        // 最初のINPUT_DELAY tick分は全員「何もしない」で埋めておく
+        let inputs = vec![vec![0u8; INPUT_DELAY as usize]; nPlayers];
+        return Session {
+            socket: socket,
+            sim: sim,
+            isHost: isHost,
+            localPlayer: localPlayer,
+            nPlayers: nPlayers,
+            seed: seed,
+            settings: settings,
+            peers: peers,
+            inputs: inputs,
+            created: vec![Instant::now(); INPUT_DELAY as usize],
+            rttMs: 0.0,
+            localTick: INPUT_DELAY,
+            simTick: 0,
+            hashes: HashMap::new(),
+            lastHash: None,
+            remoteHashes: HashMap::new(),
+            desyncTick: None,
+            lastStart: None,
+            closed: false
+        };
+    }
+
+    pub fn getLocalPlayer(&self) -> usize {
+        return self.localPlayer;
+    }
+
+    pub fn getPlayerCount(&self) -> usize {
+        return self.nPlayers;
+    }
+
+    pub fn getSeed(&self) -> u64 {
+        return self.seed;
+    }
+
+    pub fn getSettings(&self) -> &Settings {
+        return &self.settings;
+    }
+
+    // 次に進めるtick
+    pub fn getTick(&self) -> u32 {
+        return self.simTick;
+    }
+
+    pub fn getDesyncTick(&self) -> Option<u32> {
+        return self.desyncTick;
+    }
+
+    // 相手が抜けたか、応答がなくなったらtrue
+    pub fn isDisconnected(&self) -> bool {
+        if self.closed {
+            return true;
+        }
+        let timeout = Duration::from_secs(TIMEOUT_SECS);
+        return self.peers.iter().any(|p| p.lastRecv.elapsed() > timeout);
+    }
+
+    pub fn getRttMs(&self) -> f64 {
+        return self.rttMs;
+    }
+
+    // 操作を何tick先に予約するか
+    // 往復時間の分だけ先に送っておけば、相手は待たずに済む
+    pub fn getInputDelay(&self) -> u32 {
+        let d = (self.rttMs / (1000.0 / 60.0)).ceil() as u32 + 2;
+        return if d < INPUT_DELAY { INPUT_DELAY } else if d > MAX_INPUT_DELAY { MAX_INPUT_DELAY } else { d };
+    }
+
+    // 自分の操作を入れてよければtrue
+    // （ゲームの進行よりgetInputDelay()以上先には進まない．
+    //   遅延が伸びた時は同じ操作を何回か入れて隙間を埋める）
+    pub fn canAddLocal(&self) -> bool {
+        return self.localTick < self.simTick + self.getInputDelay();
+    }
+
+    pub fn addLocalInput(&mut self, input: PlayerInput) {
+        let p = self.localPlayer;
+        self.inputs[p].push(input.toBits());
+        self.created.push(Instant::now());
+        self.localTick += 1;
+    }
+
+    // 全員の操作がそろっていれば(tick, 操作)を返してtickを進める
+    pub fn nextTick(&mut self) -> Option<(u32, Vec<PlayerInput>)> {
+        let t = self.simTick as usize;
+        if self.inputs.iter().any(|v| v.len() <= t) {
+            return None;
+        }
+        let inputs = self.inputs.iter().map(|v| PlayerInput::fromBits(v[t])).collect();
+        self.simTick += 1;
+        return Some((t as u32, inputs));
+    }
+
+    // ゲーム状態のハッシュ値を記録（HASH_INTERVALごと）
+    pub fn recordHash(&mut self, tick: u32, hash: u64) {
+        if tick % HASH_INTERVAL != 0 {
+            return;
+        }
+        self.hashes.insert(tick, hash);
+        self.lastHash = Some((tick, hash));
+        for pi in 0..self.peers.len() {
+            if let Some(remote) = self.remoteHashes.remove(&(pi, tick)) {
+                self.checkHash(pi, tick, remote);
+            }
+        }
+    }
+
+    // pi番目の相手から届いたハッシュ値を自分のものと比べる
+    // 相手は同じハッシュ値を次のものができるまで何度も送ってくるので、比べ終わったtickより前は無視する
+    fn checkHash(&mut self, pi: usize, tick: u32, remote: u64) {
+        if self.peers[pi].checkedTick.map_or(false, |t| tick <= t) {
+            return;
+        }
+        match self.hashes.get(&tick) {
+            Some(&local) => {
+                if local != remote && self.desyncTick.is_none() {
+                    eprintln!("desync detected at tick {}", tick);
+                    self.desyncTick = Some(tick);
+                }
+                self.peers[pi].checkedTick = Some(tick);
+
+                // 全員と比べ終わったtickまでの自分のハッシュ値はもういらない
+                if let Some(oldest) = self.peers.iter().map(|p| p.checkedTick).min().and_then(|t| t) {
+                    self.hashes.retain(|&t, _| t > oldest);
+                }
+            }
+            None => {
+                // まだ自分がそのtickまで進んでいない
+                self.remoteHashes.insert((pi, tick), remote);
+            }
+        }
+    }
+
+    // 受信処理
+    pub fn poll(&mut self) {
+        for (addr, data) in recvAll(&self.socket) {
+            let pi = match self.peers.iter().position(|p| p.addr == addr) {
+                Some(i) => i,
+                None => { continue; }
+            };
+            if data.is_empty() {
+                continue;
+            }
+            self.peers[pi].lastRecv = Instant::now();
+
+            match data[0] {
+                MSG_INPUT => {
+                    self.peers[pi].started = true;
+                    self.readInput(pi, &data[1..]);
+                }
+                MSG_BYE => {
+                    self.closed = true;
+                }
+                _ => {}     // 遅れて届いたHELLO, STARTなど
+            }
+        }
+
+        // ホストはまだ始めていないクライアントにSTARTを送り直す
+        if self.isHost {
+            let due = match self.lastStart {
+                Some(t) => t.elapsed() > Duration::from_millis(RESEND_MS),
+                None => true
+            };
+            if due {
+                for i in 0..self.peers.len() {
+                    if !self.peers[i].started {
+                        let packet = self.startPacket(self.peers[i].player);
+                        let addr = self.peers[i].addr;
+                        self.sim.send(&self.socket, addr, packet);
+                    }
+                }
+                self.lastStart = Some(Instant::now());
+            }
+        }
+
+        self.sim.flush(&self.socket);
+    }
+
+    fn startPacket(&self, index: usize) -> Vec<u8> {
+        let mut buf = vec![MSG_START, self.nPlayers as u8, index as u8];
+        putU64(&mut buf, self.seed);
+        buf.push(self.settings.nShips as u8);
+        buf.push(self.settings.nAsteroids as u8);
+        buf.push(if self.settings.friendlyFire { 1 } else { 0 });
+        buf.push(if self.settings.asteroidCollisions { 1 } else { 0 });
+        buf.push((self.settings.restitution * 100.0).round() as u8);
+        buf.push(if self.settings.cutFragments { 1 } else { 0 });
+        buf.push(if self.settings.openSpace { 1 } else { 0 });
+        let t = &self.settings.tuning;
+        for v in &[t.shipMaxSpeed, t.shipRotationDeg, t.shipThrust, t.bulletSpeed] {
+            putU64(&mut buf, v.to_bits());
+        }
+        buf.push(t.splitMinSize as u8);
+        putU64(&mut buf, t.shieldDrain.to_bits());
+        putU64(&mut buf, t.shieldRecharge.to_bits());
+        putU32(&mut buf, t.bulletLifetime as u32);
+        buf.push(t.maxBullets as u8);
+        buf.push(t.fireCooldown as u8);
+        for v in &[t.shipDrag, t.reverseThrust, t.shipRotationAccelDeg] {
+            putU64(&mut buf, v.to_bits());
+        }
+        return buf;
+    }
+
+    // このSessionがプレイヤーpの操作をpeerに送る役目ならtrue
+    fn relays(&self, p: usize, peer: &Peer) -> bool {
+        if self.isHost {
+            // ホストは相手自身以外の全員分を配る
+            return p != peer.player;
+        } else {
+            // クライアントは自分の分だけ
+            return p == self.localPlayer;
+        }
+    }
+
+    // INPUTパケット
+    //   u8 件数, (u8 プレイヤー, u32 開始tick, u8 tick数, 操作...)*件数
+    //   u8 ack数, (u8 プレイヤー, u32 受信済みtick数)*ack数
+    //   u32 ハッシュのtick, u64 ハッシュ値
+    pub fn send(&mut self) {
+        for i in 0..self.peers.len() {
+            let mut entries: Vec<u8> = Vec::new();
+            let mut nEntries = 0;
+            for p in 0..self.nPlayers {
+                if !self.relays(p, &self.peers[i]) {
+                    continue;
+                }
+                let start = self.peers[i].acked[p];
+                let end = self.inputs[p].len() as u32;
+                if start >= end {
+                    continue;
+                }
+                let count = if end - start > MAX_RESEND { MAX_RESEND } else { end - start };
+                entries.push(p as u8);
+                putU32(&mut entries, start);
+                entries.push(count as u8);
+                entries.extend_from_slice(&self.inputs[p][start as usize..(start + count) as usize]);
+                nEntries += 1;
+            }
+
+            let mut buf = vec![MSG_INPUT, nEntries];
+            buf.extend_from_slice(&entries);
+            buf.push(self.nPlayers as u8);
+            for p in 0..self.nPlayers {
+                buf.push(p as u8);
+                putU32(&mut buf, self.inputs[p].len() as u32);
+            }
+            let (ht, hv) = match self.lastHash {
+                Some(h) => h,
+                None => (u32::max_value(), 0)
+            };
+            putU32(&mut buf, ht);
+            putU64(&mut buf, hv);
+
+            let addr = self.peers[i].addr;
+            self.sim.send(&self.socket, addr, buf);
+        }
+        self.sim.flush(&self.socket);
+    }
+
+    fn readInput(&mut self, pi: usize, data: &[u8]) {
+        let mut r = Reader::new(data);
+        let _ = self.readInputBody(pi, &mut r);
+    }
+
+    fn readInputBody(&mut self, pi: usize, r: &mut Reader) -> Option<()> {
+        let nEntries = r.u8()?;
+        for _ in 0..nEntries {
+            let p = r.u8()? as usize;
+            let start = r.u32()?;
+            let count = r.u8()? as u32;
+            let mut bits = Vec::new();
+            for _ in 0..count {
+                bits.push(r.u8()?);
+            }
+
+            // ホストは相手自身の操作だけ、クライアントは自分以外の操作だけ受け付ける
+            let accept = p < self.nPlayers && if self.isHost { p == self.peers[pi].player } else { p != self.localPlayer };
+            if !accept {
+                continue;
+            }
+
+            // 続きの部分だけ追加（重複は捨てる）
+            let have = self.inputs[p].len() as u32;
+            if start <= have && have < start + count {
+                let from = (have - start) as usize;
+                self.inputs[p].extend_from_slice(&bits[from..]);
+            }
+        }
+
+        let nAcks = r.u8()?;
+        for _ in 0..nAcks {
+            let p = r.u8()? as usize;
+            let n = r.u32()?;
+            if p < self.nPlayers && n > self.peers[pi].acked[p] {
+                self.peers[pi].acked[p] = n;
+
+                // 自分の操作が届くまでの時間から往復時間を推定
+                if p == self.localPlayer && (n as usize) <= self.created.len() {
+                    let sample = self.created[(n-1) as usize].elapsed();
+                    let ms = (sample.as_secs() as f64) * 1000.0 + (sample.subsec_nanos() as f64) / 1.0e6;
+                    self.rttMs = if self.rttMs == 0.0 { ms } else { self.rttMs * 0.9 + ms * 0.1 };
+                }
+            }
+        }
+
+        let ht = r.u32()?;
+        let hv = r.u64()?;
+        if ht != u32::max_value() {
+            self.checkHash(pi, ht, hv);
+        }
+        return Some(());
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        // 抜けることを知らせる（届かなくても相手はタイムアウトで気づく）
+        for peer in &self.peers {
+            let _ = self.socket.send_to(&[MSG_BYE], peer.addr);
+        }
+    }
+}
@@ -0,0 +1,339 @@
+#![allow(non_snake_case)]
+
+use sdl2::pixels::Color;
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+
+use Scene::{Scene, SceneContext, Transition};
+use Draw::Draw;
+use Net::{NetHost, NetClient, NetSim, Session, DEFAULT_PORT};
+use NetPlayScene;
+use Random::Random;
+use Lang::tr;
+
+enum LobbyState {
+    MENU,       // ホストか参加かを選ぶ
+    ADDRESS,    // 参加先アドレスを入力中
+    HOSTING,    // 参加者を待っている
+    JOINING,    // ホストからの開始を待っている
+    STARTING,   // ループバックテストの相手が開始するのを待っている
+    ERROR       // 失敗（メッセージを表示）
+}
+
+const LOOPBACK_WAIT_FRAMES: i32 = 60 * 5;  // ループバックテストの相手が開始するまで待つフレーム数
+
+// ---------------------------------
+//  ネットワーク対戦のロビー画面
+//  H: ホストになる  J: 参加する  L: 1台でのループバックテスト
+// ---------------------------------
+pub struct NetLobbyScene {
+    state: LobbyState,
+    address: String,            // 参加先 "host:port"
+    host: Option<NetHost>,
+    client: Option<NetClient>,
+    loopback: Option<NetClient>,    // ループバックテスト用の相手（同じプロセス内）
+    session: Option<Session>,   // 開始したホスト側のSession（ループバックテストの相手を待つ間）
+    startCount: i32,    // 相手を待ち始めてからのフレーム数
+    skipText: bool,     // Jキーの文字入力イベントを無視するためのフラグ
+    message: String
+}
+
+impl NetLobbyScene {
+    pub fn new() -> Self {
+        return NetLobbyScene {
+            state: LobbyState::MENU,
+            address: format!("127.0.0.1:{}", DEFAULT_PORT),
+            host: None,
+            client: None,
+            loopback: None,
+            session: None,
+            startCount: 0,
+            skipText: false,
+            message: String::new()
+        };
+    }
+
+    // コマンドラインの --host で起動した時用
+    pub fn newHost(ctx: &SceneContext, port: u16) -> Self {
+        let mut scene = NetLobbyScene::new();
+        scene.startHost(ctx, port, false);
+        return scene;
+    }
+
+    // コマンドラインの --join で起動した時用
+    pub fn newJoin(ctx: &SceneContext, address: &str) -> Self {
+        let mut scene = NetLobbyScene::new();
+        scene.address = address.to_string();
+        scene.startJoin(ctx);
+        return scene;
+    }
+
+    fn sim(ctx: &SceneContext) -> NetSim {
+        return NetSim::new(ctx.settings.netLatencyMs, ctx.settings.netLossPercent);
+    }
+
+    fn startHost(&mut self, ctx: &SceneContext, port: u16, loopback: bool) {
+        match NetHost::bind(port, NetLobbyScene::sim(ctx)) {
+            Ok(host) => {
+                if loopback {
+                    // 同じプロセス内に相手を作って127.0.0.1経由で参加させる
+                    let address = format!("127.0.0.1:{}", host.getPort());
+                    match NetClient::connect(&address, NetLobbyScene::sim(ctx)) {
+                        Ok(client) => self.loopback = Some(client),
+                        Err(e) => {
+                            self.fail(format!("loopback failed: {}", e));
+                            return;
+                        }
+                    }
+                }
+                self.host = Some(host);
+                self.state = LobbyState::HOSTING;
+            }
+            Err(e) => {
+                self.fail(format!("cannot open port {}: {}", port, e));
+            }
+        }
+    }
+
+    fn startJoin(&mut self, ctx: &SceneContext) {
+        match NetClient::connect(&self.address, NetLobbyScene::sim(ctx)) {
+            Ok(client) => {
+                self.client = Some(client);
+                self.state = LobbyState::JOINING;
+            }
+            Err(e) => {
+                self.fail(format!("cannot connect to {}: {}", self.address, e));
+            }
+        }
+    }
+
+    fn fail(&mut self, message: String) {
+        eprintln!("{}", message);
+        self.message = message;
+        self.host = None;
+        self.client = None;
+        self.loopback = None;
+        self.session = None;
+        self.state = LobbyState::ERROR;
+    }
+
+    // ホストとしてゲーム開始
+    fn startGame(&mut self, ctx: &SceneContext) -> Transition {
+        let host = match self.host.take() {
+            Some(host) => host,
+            None => { return Transition::NONE; }
+        };
+        let session = host.start(&ctx.settings, Random::seedFromTime());
+
+        // ループバックテストなら相手側のSessionも要るので、開始するまでupdate()で待つ
+        if self.loopback.is_some() {
+            self.session = Some(session);
+            self.startCount = 0;
+            self.state = LobbyState::STARTING;
+            return Transition::NONE;
+        }
+        return Transition::REPLACE(Box::new(NetPlayScene::NetPlayScene::new(session, None)));
+    }
+
+    // ループバックテストの相手が開始したか（1フレームに1回調べる）
+    fn pollLoopbackStart(&mut self) -> Transition {
+        if let Some(ref mut session) = self.session {
+            session.poll();
+        }
+        let peer = match self.loopback {
+            Some(ref mut client) => client.poll(),
+            None => None
+        };
+        if let Some(peer) = peer {
+            if let Some(session) = self.session.take() {
+                self.loopback = None;
+                return Transition::REPLACE(Box::new(NetPlayScene::NetPlayScene::new(session, Some(peer))));
+            }
+        }
+
+        self.startCount += 1;
+        if self.startCount >= LOOPBACK_WAIT_FRAMES {
+            self.fail("loopback peer did not start".to_string());
+        }
+        return Transition::NONE;
+    }
+}
+
+impl Scene for NetLobbyScene {
+    fn handleInput(&mut self, ctx: &mut SceneContext, event: &Event) -> Transition {
+        match self.state {
+            LobbyState::ADDRESS => {
+                match *event {
+                    Event::TextInput { ref text, .. } => {
+                        if self.skipText {
+                            self.skipText = false;
+                        } else {
+                            self.address.push_str(text);
+                        }
+                    }
+                    Event::KeyDown { keycode: Some(Keycode::Backspace), .. } => {
+                        self.address.pop();
+                    }
+                    Event::KeyDown { keycode: Some(Keycode::Return), .. } => {
+                        self.startJoin(ctx);
+                    }
+                    Event::KeyDown { keycode: Some(Keycode::Escape), .. } => {
+                        self.state = LobbyState::MENU;
+                    }
+                    _ => {}
+                }
+                return Transition::NONE;
+            }
+            _ => {}
+        }
+
+        let keycode = match *event {
+            Event::KeyDown { keycode: Some(k), .. } => k,
+            _ => { return Transition::NONE; }
+        };
+
+        match self.state {
+            LobbyState::MENU => {
+                match keycode {
+                    Keycode::H => self.startHost(ctx, DEFAULT_PORT, false),
+                    Keycode::J => {
+                        // Jキー自身の文字入力が続けて届くので1回無視する
+                        self.skipText = true;
+                        self.state = LobbyState::ADDRESS;
+                    }
+                    Keycode::L => self.startHost(ctx, 0, true),     // 空いているポートを使う
+                    Keycode::Escape => {
+                        return Transition::POP;
+                    }
+                    _ => {}
+                }
+            }
+            LobbyState::HOSTING => {
+                match keycode {
+                    Keycode::Return => {
+                        let ready = match self.host {
+                            Some(ref host) => host.getPlayerCount() >= 2,
+                            None => false
+                        };
+                        if ready {
+                            return self.startGame(ctx);
+                        }
+                    }
+                    Keycode::Escape => {
+                        self.host = None;
+                        self.loopback = None;
+                        self.state = LobbyState::MENU;
+                    }
+                    _ => {}
+                }
+            }
+            LobbyState::JOINING | LobbyState::ERROR => {
+                if keycode == Keycode::Escape {
+                    self.client = None;
+                    self.state = LobbyState::MENU;
+                }
+            }
+            LobbyState::STARTING => {
+                if keycode == Keycode::Escape {
+                    self.session = None;
+                    self.loopback = None;
+                    self.state = LobbyState::MENU;
+                }
+            }
+            LobbyState::ADDRESS => {}
+        }
+        return Transition::NONE;
+    }
+
+    fn update(&mut self, _ctx: &mut SceneContext) -> Transition {
+        if let LobbyState::STARTING = self.state {
+            return self.pollLoopbackStart();
+        }
+        if let Some(ref mut host) = self.host {
+            host.poll();
+        }
+        if let Some(ref mut client) = self.loopback {
+            let _ = client.poll();
+        }
+
+        let mut session = None;
+        let mut timedOut = false;
+        if let Some(ref mut client) = self.client {
+            session = client.poll();
+            timedOut = client.isTimedOut();
+        }
+        if let Some(session) = session {
+            // ホストがゲームを開始した
+            return Transition::REPLACE(Box::new(NetPlayScene::NetPlayScene::new(session, None)));
+        }
+        if timedOut {
+            let message = format!("no answer from {}", self.address);
+            self.fail(message);
+        }
+        return Transition::NONE;
+    }
+
+    fn render(&self, canvas: &mut dyn Draw, ctx: &SceneContext) {
+        let theme = ctx.settings.getTheme();
+        let width = ctx.width as i32;
+        let height = ctx.height as i32;
+
+        // clear canvas
+        canvas.setDrawColor(Color::RGB(0, 0, 0)); // black
+        canvas.clear();
+
+        let lang = ctx.settings.language;
+        let title = tr(lang, "Network Game");
+        canvas.textOutColor(title, theme.text, width/2-(title.chars().count() as i32)*25, height/2-300, 50, 80);
+
+        let mut lines: Vec<String> = Vec::new();
+        match self.state {
+            LobbyState::MENU => {
+                lines.push(tr(lang, "H: Host a game").to_string());
+                lines.push(tr(lang, "J: Join a game").to_string());
+                lines.push(tr(lang, "L: Loopback test (127.0.0.1)").to_string());
+                lines.push(format!("{} {} ms, {} {} %", tr(lang, "Simulated latency"), ctx.settings.netLatencyMs, tr(lang, "loss"), ctx.settings.netLossPercent));
+                lines.push(tr(lang, "Escape: Back").to_string());
+            }
+            LobbyState::ADDRESS => {
+                lines.push(tr(lang, "Host address:").to_string());
+                lines.push(format!("{}_", self.address));
+                lines.push(tr(lang, "Enter: Connect  Escape: Back").to_string());
+            }
+            LobbyState::HOSTING => {
+                if let Some(ref host) = self.host {
+                    lines.push(format!("{} {}", tr(lang, "Hosting on port"), host.getPort()));
+                    lines.push(format!("{}: {}", tr(lang, "Players"), host.getPlayerCount()));
+                    if host.getPlayerCount() >= 2 {
+                        lines.push(tr(lang, "Enter: Start").to_string());
+                    } else {
+                        lines.push(tr(lang, "Waiting for players...").to_string());
+                    }
+                }
+                lines.push(tr(lang, "Escape: Cancel").to_string());
+            }
+            LobbyState::JOINING => {
+                lines.push(format!("{} {}", tr(lang, "Connecting to"), self.address));
+                if let Some(ref client) = self.client {
+                    match client.getIndex() {
+                        Some(i) => lines.push(format!("{} {}. {}", tr(lang, "Joined as Player"), i+1, tr(lang, "Waiting for host..."))),
+                        None => lines.push(tr(lang, "Waiting for answer...").to_string())
+                    }
+                }
+                lines.push(tr(lang, "Escape: Cancel").to_string());
+            }
+            LobbyState::STARTING => {
+                lines.push(tr(lang, "Starting loopback test...").to_string());
+                lines.push(tr(lang, "Escape: Cancel").to_string());
+            }
+            LobbyState::ERROR => {
+                lines.push(self.message.clone());
+                lines.push(tr(lang, "Escape: Back").to_string());
+            }
+        }
+
+        for (i, line) in lines.iter().enumerate() {
+            canvas.textOutColor(line, theme.text, width/2-(line.chars().count() as i32)*10, height/2-100+(i as i32)*50, 20, 30);
+        }
+    }
+}
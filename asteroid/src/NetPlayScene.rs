@@ -0,0 +1,166 @@
+#![allow(non_snake_case)]
+
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+
+use Scene::{Scene, SceneContext, Transition};
+use Draw::Draw;
+use Game;
+use Input::{KeyState, KeySet, PlayerInput};
+use Net::Session;
+use HighScoreScene;
+use TitleScene;
+use Lang::tr;
+
+// ---------------------------------
+//  ネットワーク対戦のプレイ画面
+// ---------------------------------
+pub struct NetPlayScene {
+    session: Session,
+    game: Game::Game,
+    keys: KeyState,
+    keySet: KeySet,
+    loopback: Option<(Session, Game::Game)>,    // ループバックテストの相手側
+    stallCount: i32,    // 相手の操作待ちで止まっているフレーム数
+    lostCount: i32      // 切断されてからのフレーム数
+}
+
+impl NetPlayScene {
+    pub fn new(session: Session, loopback: Option<Session>) -> Self {
+        let mut game = Game::Game::newNetwork(session.getSettings(), session.getPlayerCount(), session.getSeed());
+        game.setViewPlayer(session.getLocalPlayer());
+        let loopback = loopback.map(|s| {
+            let mut g = Game::Game::newNetwork(s.getSettings(), s.getPlayerCount(), s.getSeed());
+            g.setViewPlayer(s.getLocalPlayer());
+            (s, g)
+        });
+        return NetPlayScene {
+            session: session,
+            game: game,
+            keys: KeyState::new(),
+            keySet: KeySet::player1(),
+            loopback: loopback,
+            stallCount: 0,
+            lostCount: 0
+        };
+    }
+}
+
+// 1フレーム分通信してゲームを進める．進んだらtrue
+fn step(session: &mut Session, game: &mut Game::Game, input: PlayerInput, width: u32, height: u32) -> bool {
+    session.poll();
+    while session.canAddLocal() {
+        session.addLocalInput(input);
+    }
+    session.send();
+
+    match session.nextTick() {
+        Some((tick, inputs)) => {
+            for (i, input) in inputs.into_iter().enumerate() {
+                game.setInput(i, input);
+            }
+            game.update(width, height);
+            session.recordHash(tick, game.checksum());
+            return true;
+        }
+        None => {
+            return false;
+        }
+    }
+}
+
+impl Scene for NetPlayScene {
+    // 配色は通信で受け取らず、それぞれの画面の設定を使う
+    // （ゲームの速さは全員で揃える必要があるので使わない）
+    fn enter(&mut self, ctx: &mut SceneContext) {
+        self.game.setTheme(&ctx.settings.getTheme());
+        self.game.setLanguage(ctx.settings.language);
+    }
+
+    fn exit(&mut self, ctx: &mut SceneContext) {
+        ctx.stats.save();
+    }
+
+    fn handleInput(&mut self, _ctx: &mut SceneContext, event: &Event) -> Transition {
+        self.keys.handleEvent(event);
+
+        match *event {
+            Event::KeyDown { keycode: Some(Keycode::Escape), .. } => {
+                // ネットワーク対戦にはポーズがないので、抜けてタイトルへ
+                return Transition::RESET(Box::new(TitleScene::TitleScene::new()));
+            }
+            _ => {}
+        }
+        return Transition::NONE;
+    }
+
+    fn update(&mut self, ctx: &mut SceneContext) -> Transition {
+        if self.session.isDisconnected() {
+            // 相手がいなくなった．しばらく表示してからタイトルへ
+            self.lostCount += 1;
+            if self.lostCount >= 180 {
+                return Transition::RESET(Box::new(TitleScene::TitleScene::new()));
+            }
+            return Transition::NONE;
+        }
+
+        let input = self.keySet.read(&self.keys);
+        if step(&mut self.session, &mut self.game, input, ctx.width, ctx.height) {
+            self.stallCount = 0;
+            // 統計は自分の分だけ
+            let local = self.session.getLocalPlayer();
+            ctx.stats.record(self.game.getEvents(), |p| p == local);
+        } else {
+            self.stallCount += 1;
+        }
+
+        // ループバックテストの相手は何も操作しない
+        if let Some((ref mut session, ref mut game)) = self.loopback {
+            step(session, game, PlayerInput::default(), ctx.width, ctx.height);
+        }
+
+        if self.game.isOver() {
+            let local = self.session.getLocalPlayer();
+            let score = self.game.getScores()[local];
+            if ctx.highScores.qualifies(score) {
+                return Transition::RESET(Box::new(HighScoreScene::HighScoreScene::new(vec![(local, score)])));
+            } else {
+                return Transition::RESET(Box::new(TitleScene::TitleScene::new()));
+            }
+        }
+        return Transition::NONE;
+    }
+
+    fn getGame(&self) -> Option<&Game::Game> {
+        return Some(&self.game);
+    }
+
+    fn render(&self, canvas: &mut dyn Draw, ctx: &SceneContext) {
+        let theme = ctx.settings.getTheme();
+        self.game.draw(canvas, ctx.width, ctx.height);
+
+        // 通信状態
+        let lang = ctx.settings.language;
+        let mut status = format!("{} {}  {}: {}  RTT: {:.0} ms  {}: {}",
+                                 tr(lang, "You: Player"), self.session.getLocalPlayer()+1,
+                                 tr(lang, "Tick"), self.session.getTick(), self.session.getRttMs(),
+                                 tr(lang, "Delay"), self.session.getInputDelay());
+        if self.loopback.is_some() {
+            status.push_str(&format!("  {} {} ms / {} %", tr(lang, "Loopback"), ctx.settings.netLatencyMs, ctx.settings.netLossPercent));
+        }
+        if let Some(tick) = self.session.getDesyncTick() {
+            status.push_str(&format!("  {} {}", tr(lang, "DESYNC at tick"), tick));
+        }
+        canvas.textOutColor(&status, theme.text, 10, (ctx.height as i32) - 35, 12, 25);
+
+        let width = ctx.width as i32;
+        let height = ctx.height as i32;
+        if self.session.isDisconnected() {
+            let text = tr(lang, "Connection lost");
+            canvas.textOutColor(text, theme.warning, width/2-(text.chars().count() as i32)*15, height/2-40, 30, 60);
+        } else if self.stallCount >= 30 {
+            let text = tr(lang, "Waiting for other players...");
+            canvas.textOutColor(text, theme.highlight, width/2-(text.chars().count() as i32)*10, height/2-20, 20, 40);
+        }
+    }
+}
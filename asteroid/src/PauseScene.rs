@@ -0,0 +1,73 @@
+#![allow(non_snake_case)]
+
+use sdl2::render::BlendMode;
+use sdl2::pixels::Color;
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+
+use Scene::{Scene, SceneContext, Transition};
+use Draw::Draw;
+use TitleScene;
+use Lang::tr;
+
+// ---------------------------------
+//  ポーズ画面（プレイ画面の上に重ねる）
+// ---------------------------------
+pub struct PauseScene {
+}
+
+impl PauseScene {
+    pub fn new() -> Self {
+        return PauseScene {
+        };
+    }
+}
+
+impl Scene for PauseScene {
+    fn handleInput(&mut self, _ctx: &mut SceneContext, event: &Event) -> Transition {
+        match *event {
+            Event::KeyDown { keycode: Some(Keycode::Escape), .. }
+            | Event::KeyDown { keycode: Some(Keycode::P), .. }
+            | Event::KeyDown { keycode: Some(Keycode::Return), .. } => {
+                // ゲームに戻る
+                return Transition::POP;
+            }
+            Event::KeyDown { keycode: Some(Keycode::Q), .. } => {
+                // ゲームをやめてタイトルへ
+                return Transition::RESET(Box::new(TitleScene::TitleScene::new()));
+            }
+            _ => {
+                return Transition::NONE;
+            }
+        }
+    }
+
+    fn update(&mut self, _ctx: &mut SceneContext) -> Transition {
+        return Transition::NONE;
+    }
+
+    fn render(&self, canvas: &mut dyn Draw, ctx: &SceneContext) {
+        let theme = ctx.settings.getTheme();
+        let width = ctx.width as i32;
+        let height = ctx.height as i32;
+
+        // 下のプレイ画面を暗くする
+        canvas.setBlendMode(BlendMode::Blend);
+        canvas.setDrawColor(Color::RGBA(0, 0, 0, 160));
+        canvas.fillRect(None);
+        canvas.setBlendMode(BlendMode::None);
+
+        // 真ん中にそろえる（言語で長さが変わる）
+        let lang = ctx.settings.language;
+        let lines = [(tr(lang, "Paused"), height/2-100, 60, 90),
+                     (tr(lang, "Escape / P: Resume"), height/2+40, 20, 30),
+                     (tr(lang, "Q: Quit to Title"), height/2+80, 20, 30)];
+        for &(text, y, w, h) in lines.iter() {
+            canvas.textOutColor(text, theme.text, width/2-(text.chars().count() as i32)*(w as i32)/2, y, w, h);
+        }
+    }
+
+    fn isOverlay(&self) -> bool {
+        return true;
+    }
+}
@@ -0,0 +1,105 @@
+#![allow(non_snake_case)]
+
+use Asteroid::Asteroid;
+use Util;
+
+const MAX_ITERATIONS: usize = 200; // 重なりの解消を繰り返す上限（詰まりすぎて解けないときの安全装置）
+const SLOP: f64 = 0.01;         // 押し出した後に残すすき間(px)
+const SPIN_TRANSFER: f64 = 0.3; // 接触面のすべりを回転に移す割合（摩擦）
+const MAX_SPIN: f64 = 0.2;      // 回転速度の上限(rad/フレーム)
+
+// ---------------------------------
+//  小惑星どうしの衝突
+//  小惑星は半径getRadius()の円盤、質量は面積として扱う
+//  restitution ... 反発係数（1.0で完全弾性衝突）
+//  画面の端はつながっているので、距離は近い方の向きで測る
+//  重なりがなくなるまで押し出しを繰り返す（MAX_ITERATIONS回で解けなければ残りは次のフレームに回す）
+// ---------------------------------
+pub fn collideAsteroids(asteroids: &mut Vec<Asteroid>, restitution: f64, width: u32, height: u32) {
+    let wf = width as f64;
+    let hf = height as f64;
+    let n = asteroids.len();
+
+    for iter in 0..MAX_ITERATIONS {
+        let mut overlapped = false;
+        for i in 0..n {
+            if !asteroids[i].getValid() {
+                continue;
+            }
+            for j in (i+1)..n {
+                if !asteroids[j].getValid() {
+                    continue;
+                }
+                // 速度は最初の1回だけ変える（あとは重なりの解消のみ）
+                if resolve(asteroids, i, j, restitution, iter == 0, wf, hf) {
+                    overlapped = true;
+                }
+            }
+        }
+        if !overlapped {
+            break;
+        }
+    }
+}
+
+// 2個の小惑星が重なっていたら押し離して、近づいているなら跳ね返す
+// 重なっていたらtrue
+fn resolve(asteroids: &mut Vec<Asteroid>, i: usize, j: usize, restitution: f64, bounce: bool, wf: f64, hf: f64) -> bool {
+    let (xi, yi, ri, mi) = (asteroids[i].getX(), asteroids[i].getY(), asteroids[i].getRadius(), asteroids[i].getMass().max(1.0));
+    let (xj, yj, rj, mj) = (asteroids[j].getX(), asteroids[j].getY(), asteroids[j].getRadius(), asteroids[j].getMass().max(1.0));
+
+    let dx = Util::wrapDelta(xj - xi, wf);
+    let dy = Util::wrapDelta(yj - yi, hf);
+    let dist = (dx*dx + dy*dy).sqrt();
+    let overlap = ri + rj - dist;
+    if overlap <= 0.0 {
+        return false;
+    }
+
+    // 法線（iからjの向き）、ぴったり重なっていたら横に離す
+    let (nx, ny) = if dist > 1e-9 { (dx / dist, dy / dist) } else { (1.0, 0.0) };
+    let (tx, ty) = (-ny, nx);
+
+    // 重なりを質量の逆比で分けて押し出す
+    let push = overlap + SLOP;
+    let total = mi + mj;
+    asteroids[i].setPos(Util::wrap(xi - nx * push * mj / total, wf), Util::wrap(yi - ny * push * mj / total, hf));
+    asteroids[j].setPos(Util::wrap(xj + nx * push * mi / total, wf), Util::wrap(yj + ny * push * mi / total, hf));
+
+    if !bounce {
+        return true;
+    }
+
+    let (mut vxi, mut vyi) = (asteroids[i].getVx(), asteroids[i].getVy());
+    let (mut vxj, mut vyj) = (asteroids[j].getVx(), asteroids[j].getVy());
+    let mut wi = asteroids[i].getSpin();
+    let mut wj = asteroids[j].getSpin();
+
+    let vn = (vxj - vxi) * nx + (vyj - vyi) * ny;
+    if vn < 0.0 {
+        // 法線方向の撃力
+        let jn = -(1.0 + restitution) * vn / (1.0 / mi + 1.0 / mj);
+        vxi -= jn / mi * nx;
+        vyi -= jn / mi * ny;
+        vxj += jn / mj * nx;
+        vyj += jn / mj * ny;
+
+        // 接触点のすべり（回転を含む）を摩擦で減らし、回転をやり取りする
+        // 円盤の慣性モーメント I = m r^2 / 2
+        let slip = (vxj - vxi) * tx + (vyj - vyi) * ty - wi * ri - wj * rj;
+        let k = 3.0 * (1.0 / mi + 1.0 / mj);
+        let jt = -SPIN_TRANSFER * slip / k;
+        vxi -= jt / mi * tx;
+        vyi -= jt / mi * ty;
+        vxj += jt / mj * tx;
+        vyj += jt / mj * ty;
+        wi -= 2.0 * jt / (mi * ri.max(1.0));
+        wj -= 2.0 * jt / (mj * rj.max(1.0));
+
+        asteroids[i].setVelocity(vxi, vyi);
+        asteroids[j].setVelocity(vxj, vyj);
+        asteroids[i].setSpin(wi.max(-MAX_SPIN).min(MAX_SPIN));
+        asteroids[j].setSpin(wj.max(-MAX_SPIN).min(MAX_SPIN));
+    }
+    return true;
+}
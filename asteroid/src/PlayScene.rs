@@ -0,0 +1,129 @@
+#![allow(non_snake_case)]
+
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+
+use Scene::{Scene, SceneContext, Transition};
+use Draw::Draw;
+use Game;
+use Game::{PlayMode, GameState};
+use Input::{KeyState, KeySet};
+use Random::Random;
+use PauseScene;
+use HighScoreScene;
+use TitleScene;
+use SaveGame;
+
+// ---------------------------------
+//  ゲームプレイ画面
+// ---------------------------------
+pub struct PlayScene {
+    game: Game::Game,
+    keys: KeyState,
+    keySets: [KeySet; 2],   // プレイヤー1, 2のキー割り当て
+    speedCarry: u32     // ゲームの速さの端数（100溜まるごとに1フレーム進める）
+}
+
+impl PlayScene {
+    pub fn new(ctx: &SceneContext, mode: PlayMode) -> Self {
+        return PlayScene {
+            game: Game::Game::new(&ctx.settings, mode, Random::seedFromTime()),
+            keys: KeyState::new(),
+            keySets: keySets(ctx),
+            speedCarry: 0
+        };
+    }
+
+    // 保存してあったゲームを再開
+    pub fn fromGame(ctx: &SceneContext, game: Game::Game) -> Self {
+        return PlayScene {
+            game: game,
+            keys: KeyState::new(),
+            keySets: keySets(ctx),
+            speedCarry: 0
+        };
+    }
+}
+
+fn keySets(ctx: &SceneContext) -> [KeySet; 2] {
+    let swap = ctx.settings.swapControls;
+    return [KeySet::forPlayer(0, swap), KeySet::forPlayer(1, swap)];
+}
+
+impl Scene for PlayScene {
+    fn enter(&mut self, ctx: &mut SceneContext) {
+        // ポーズ中に離したキーが押しっぱなしにならないようにする
+        self.keys.clear();
+        self.game.setTheme(&ctx.settings.getTheme());
+        self.game.setLanguage(ctx.settings.language);
+    }
+
+    // 途中でやめたら（ポーズからタイトルへ、ウィンドウを閉じた）続きを保存する
+    fn exit(&mut self, ctx: &mut SceneContext) {
+        ctx.stats.save();
+
+        // ゲームオーバーになっていたら保存しない
+        if let GameState::GAMEOVER = *self.game.getState() {
+            return;
+        }
+        match SaveGame::saveGame(&self.game, SaveGame::SAVE_FILE) {
+            Ok(()) => eprintln!("saved game to {}", SaveGame::SAVE_FILE),
+            Err(e) => eprintln!("failed to save game: {}", e)
+        }
+    }
+
+    fn handleInput(&mut self, _ctx: &mut SceneContext, event: &Event) -> Transition {
+        self.keys.handleEvent(event);
+
+        match *event {
+            Event::KeyDown { keycode: Some(Keycode::Escape), .. }
+            | Event::KeyDown { keycode: Some(Keycode::P), .. } => {
+                // ポーズ
+                return Transition::PUSH(Box::new(PauseScene::PauseScene::new()));
+            }
+            _ => {}
+        }
+        return Transition::NONE;
+    }
+
+    fn update(&mut self, ctx: &mut SceneContext) -> Transition {
+        // ゲームの速さが100%未満なら、ときどき1フレーム休んでゆっくり進める
+        self.speedCarry += ctx.settings.gameSpeed;
+        if self.speedCarry < 100 {
+            return Transition::NONE;
+        }
+        self.speedCarry -= 100;
+
+        // キー入力をプレイヤーごとの操作にする
+        self.game.setInput(0, self.keySets[0].read(&self.keys));
+        self.game.setInput(1, self.keySets[1].read(&self.keys));
+
+        self.game.update(ctx.width, ctx.height);
+        // この画面のプレイヤーは全員数える
+        ctx.stats.record(self.game.getEvents(), |_| true);
+
+        if self.game.isOver() {
+            // ハイスコア表に載るプレイヤー
+            let scores: Vec<(usize, i32)> = self.game.getScores().into_iter()
+                .enumerate()
+                .filter(|&(_, score)| ctx.highScores.qualifies(score))
+                .collect();
+            if !scores.is_empty() {
+                // ハイスコア入力へ
+                return Transition::REPLACE(Box::new(HighScoreScene::HighScoreScene::new(scores)));
+            } else {
+                // タイトル画面に戻る
+                return Transition::REPLACE(Box::new(TitleScene::TitleScene::new()));
+            }
+        }
+        return Transition::NONE;
+    }
+
+    fn render(&self, canvas: &mut dyn Draw, ctx: &SceneContext) {
+        self.game.draw(canvas, ctx.width, ctx.height);
+    }
+
+    fn getGame(&self) -> Option<&Game::Game> {
+        return Some(&self.game);
+    }
+}
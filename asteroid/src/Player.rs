@@ -0,0 +1,71 @@
+#![allow(non_snake_case)]
+
+use sdl2::pixels::Color;
+
+use MyShip;
+use SaveGame::{SaveWriter, SaveReader};
+
+// ---------------------------------
+//  プレイヤー（スコアと残り宇宙船はプレイヤーごと）
+// ---------------------------------
+pub struct Player {
+    pub score: i32,
+    pub nShips: i32,    // 残り宇宙船
+    pub ship: Option<MyShip::MyShip>,
+    pub lifeFrames: i32,    // 今の宇宙船が出てからのフレーム数
+    pub exploding: bool,    // 爆発アニメーション中ならtrue（協力プレイ用）
+    pub color: Color,   // 宇宙船とスコアの色
+    pub prevFire: bool, // 前フレームで発射キーが押されていたらtrue
+    pub fireCooldown: i32   // 次に撃てるまでの残りフレーム数
+}
+
+impl Player {
+    pub fn new(nShips: i32, color: Color) -> Self {
+        return Player {
+            score: 0,
+            nShips: nShips,
+            ship: None,
+            lifeFrames: 0,
+            exploding: false,
+            color: color,
+            prevFire: false,
+            fireCooldown: 0
+        };
+    }
+
+    pub fn save(&self, w: &mut SaveWriter) {
+        w.line("player");
+        w.i32(self.score);
+        w.i32(self.nShips);
+        w.bool(self.exploding);
+        w.color(self.color);
+        w.bool(self.prevFire);
+        w.i32(self.lifeFrames);
+        w.i32(self.fireCooldown);
+        w.bool(self.ship.is_some());
+        if let Some(ref ship) = self.ship {
+            ship.save(w);
+        }
+    }
+
+    pub fn load(r: &mut SaveReader) -> Result<Self, String> {
+        r.expect("player")?;
+        let mut player = Player::new(0, Color::RGB(0, 0, 0));
+        player.score = r.i32()?;
+        player.nShips = r.i32()?;
+        player.exploding = r.bool()?;
+        player.color = r.color()?;
+        player.prevFire = r.bool()?;
+        player.lifeFrames = r.i32()?;
+        player.fireCooldown = r.i32()?;
+        if r.bool()? {
+            player.ship = Some(MyShip::MyShip::load(r)?);
+        }
+        return Ok(player);
+    }
+
+    // まだ宇宙船が残っていればtrue
+    pub fn isAlive(&self) -> bool {
+        return self.nShips > 0;
+    }
+}
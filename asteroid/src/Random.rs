@@ -0,0 +1,83 @@
+#![allow(non_snake_case)]
+
+use rand::{RngCore, Error};
+
+use SaveGame::{SaveWriter, SaveReader};
+
+// ---------------------------------
+//  乱数生成器 (xorshift64*)
+//  同じシードなら同じ乱数列になるので、
+//  ネットワーク対戦でも全員が同じ小惑星を作れる
+// ---------------------------------
+#[derive(Clone)]
+pub struct Random {
+    state: u64
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        // 状態が0だと0しか出ないので混ぜておく
+        let mut s = seed ^ 0x9E3779B97F4A7C15;
+        if s == 0 {
+            s = 0x9E3779B97F4A7C15;
+        }
+        return Random {
+            state: s
+        };
+    }
+
+    // 途中のゲームの保存用（状態をそのまま書いて読む）
+    pub fn save(&self, w: &mut SaveWriter) {
+        w.line("rng");
+        w.u64(self.state);
+    }
+
+    pub fn load(r: &mut SaveReader) -> Result<Self, String> {
+        r.expect("rng")?;
+        let state = r.u64()?;
+        if state == 0 {
+            return Err(r.error("random state must not be 0"));
+        }
+        return Ok(Random {
+            state: state
+        });
+    }
+
+    // 現在時刻からシードを作る
+    pub fn seedFromTime() -> u64 {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        return match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(d) => d.as_secs() ^ ((d.subsec_nanos() as u64) << 32),
+            Err(_) => 0
+        };
+    }
+}
+
+impl RngCore for Random {
+    fn next_u32(&mut self) -> u32 {
+        return (self.next_u64() >> 32) as u32;
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.state = x;
+        return x.wrapping_mul(0x2545F4914F6CDD1D);
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let v = self.next_u64();
+            for (i, b) in chunk.iter_mut().enumerate() {
+                *b = (v >> (i * 8)) as u8;
+            }
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        return Ok(());
+    }
+}
@@ -0,0 +1,231 @@
+#![allow(non_snake_case)]
+
+use std::fs;
+use std::path::Path;
+
+use sdl2::pixels::Color;
+
+use Game::Game;
+
+pub const SAVE_FILE: &str = "savegame.txt";
+const MAGIC: &str = "asteroid-save";
+pub const SAVE_VERSION: u32 = 1;    // 保存形式を変えたら上げる
+
+// ---------------------------------
+//  途中のゲームの保存と再開
+//
+//  1行目は "asteroid-save バージョン"、
+//  以降は1行に1つ、"タグ 値 値 ..." の形でゲームと中身を書く
+//  小数はRustの表示形式（読み戻すと同じ値になる）で書く
+// ---------------------------------
+pub fn saveGame(game: &Game, path: &str) -> Result<(), String> {
+    let mut w = SaveWriter::new();
+    w.line(MAGIC);
+    w.u64(SAVE_VERSION as u64);
+    game.save(&mut w);
+    return fs::write(path, w.finish()).map_err(|e| format!("{}: {}", path, e));
+}
+
+pub fn loadGame(path: &str) -> Result<Game, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let mut r = SaveReader::new(&text);
+    r.expect(MAGIC).map_err(|_| format!("{}: not a save file", path))?;
+    let version = r.u64().map_err(|e| format!("{}: {}", path, e))?;
+
+    // 形式を変えたら、古いバージョンを読むならここで分けてGame::load()に渡す
+    if version != SAVE_VERSION as u64 {
+        return Err(format!("{}: save format version {} is not supported (expected {})", path, version, SAVE_VERSION));
+    }
+    let game = Game::load(&mut r).map_err(|e| format!("{}: {}", path, e))?;
+    if !r.isEnd() {
+        return Err(format!("{}: {}", path, r.error("unexpected data after the game")));
+    }
+    return Ok(game);
+}
+
+pub fn exists(path: &str) -> bool {
+    return Path::new(path).is_file();
+}
+
+pub fn remove(path: &str) {
+    let _ = fs::remove_file(path);
+}
+
+// ---------------------------------
+//  保存データの書き出し
+// ---------------------------------
+pub struct SaveWriter {
+    text: String
+}
+
+impl SaveWriter {
+    pub fn new() -> Self {
+        return SaveWriter {
+            text: String::new()
+        };
+    }
+
+    // 新しい行を始める
+    pub fn line(&mut self, tag: &str) {
+        if !self.text.is_empty() {
+            self.text.push('\n');
+        }
+        self.text.push_str(tag);
+    }
+
+    pub fn f64(&mut self, v: f64) {
+        self.text.push_str(&format!(" {}", v));
+    }
+
+    pub fn i32(&mut self, v: i32) {
+        self.text.push_str(&format!(" {}", v));
+    }
+
+    pub fn u64(&mut self, v: u64) {
+        self.text.push_str(&format!(" {}", v));
+    }
+
+    pub fn bool(&mut self, v: bool) {
+        self.text.push_str(if v { " 1" } else { " 0" });
+    }
+
+    pub fn color(&mut self, c: Color) {
+        self.text.push_str(&format!(" {} {} {}", c.r, c.g, c.b));
+    }
+
+    // 空白を含まない単語
+    pub fn word(&mut self, v: &str) {
+        self.text.push(' ');
+        self.text.push_str(v);
+    }
+
+    pub fn finish(mut self) -> String {
+        self.text.push('\n');
+        return self.text;
+    }
+}
+
+// ---------------------------------
+//  保存データの読み込み
+//  エラーメッセージには行番号を付ける
+// ---------------------------------
+pub struct SaveReader<'a> {
+    tokens: Vec<(usize, &'a str)>,  // (行番号, 単語)
+    pos: usize
+}
+
+impl<'a> SaveReader<'a> {
+    pub fn new(text: &'a str) -> Self {
+        let mut tokens = Vec::new();
+        for (n, line) in text.lines().enumerate() {
+            for t in line.split_whitespace() {
+                tokens.push((n + 1, t));
+            }
+        }
+        return SaveReader {
+            tokens: tokens,
+            pos: 0
+        };
+    }
+
+    pub fn isEnd(&self) -> bool {
+        return self.pos >= self.tokens.len();
+    }
+
+    pub fn error(&self, message: &str) -> String {
+        return match self.tokens.get(self.pos) {
+            Some(&(line, _)) => format!("line {}: {}", line, message),
+            None => format!("end of file: {}", message)
+        };
+    }
+
+    pub fn word(&mut self) -> Result<&'a str, String> {
+        match self.tokens.get(self.pos) {
+            Some(&(_, t)) => {
+                self.pos += 1;
+                return Ok(t);
+            }
+            None => {
+                return Err(self.error("unexpected end of save data"));
+            }
+        }
+    }
+
+    // タグを読む（違っていたらエラー）
+    pub fn expect(&mut self, tag: &str) -> Result<(), String> {
+        let t = self.word()?;
+        if t != tag {
+            self.pos -= 1;
+            return Err(self.error(&format!("expected '{}' but found '{}'", tag, t)));
+        }
+        return Ok(());
+    }
+
+    pub fn f64(&mut self) -> Result<f64, String> {
+        let t = self.word()?;
+        match t.parse::<f64>() {
+            Ok(v) if v.is_finite() => { return Ok(v); }
+            _ => {
+                self.pos -= 1;
+                return Err(self.error(&format!("expected a number but found '{}'", t)));
+            }
+        }
+    }
+
+    pub fn i32(&mut self) -> Result<i32, String> {
+        let t = self.word()?;
+        match t.parse::<i32>() {
+            Ok(v) => { return Ok(v); }
+            Err(_) => {
+                self.pos -= 1;
+                return Err(self.error(&format!("expected an integer but found '{}'", t)));
+            }
+        }
+    }
+
+    pub fn u64(&mut self) -> Result<u64, String> {
+        let t = self.word()?;
+        match t.parse::<u64>() {
+            Ok(v) => { return Ok(v); }
+            Err(_) => {
+                self.pos -= 1;
+                return Err(self.error(&format!("expected an integer but found '{}'", t)));
+            }
+        }
+    }
+
+    pub fn color(&mut self) -> Result<Color, String> {
+        let mut rgb = [0u8; 3];
+        for c in rgb.iter_mut() {
+            let v = self.u64()?;
+            if v > 255 {
+                self.pos -= 1;
+                return Err(self.error(&format!("color component {} is out of range", v)));
+            }
+            *c = v as u8;
+        }
+        return Ok(Color::RGB(rgb[0], rgb[1], rgb[2]));
+    }
+
+    // 個数（大きすぎる値は壊れたデータとして扱う）
+    pub fn count(&mut self, max: usize) -> Result<usize, String> {
+        let v = self.u64()?;
+        if v > max as u64 {
+            self.pos -= 1;
+            return Err(self.error(&format!("count {} is too large (max {})", v, max)));
+        }
+        return Ok(v as usize);
+    }
+
+    pub fn bool(&mut self) -> Result<bool, String> {
+        let t = self.word()?;
+        match t {
+            "1" => { return Ok(true); }
+            "0" => { return Ok(false); }
+            _ => {
+                self.pos -= 1;
+                return Err(self.error(&format!("expected 0 or 1 but found '{}'", t)));
+            }
+        }
+    }
+}
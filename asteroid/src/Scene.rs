@@ -0,0 +1,356 @@
+#![allow(non_snake_case)]
+
+use sdl2::render::BlendMode;
+use sdl2::pixels::Color;
+use sdl2::event::Event;
+
+use HighScore;
+use Stats::{StatsTracker, STATS_FILE};
+use Draw::Draw;
+use Game::Game;
+use Tuning::{Tuning, TuningTable, Difficulty, TUNING_FILE};
+use Lang::Language;
+use Theme::{Theme, ThemeKind};
+use Config;
+
+// ---------------------------------
+//  シーン間で共有する設定値
+// ---------------------------------
+#[derive(Clone)]
+pub struct Settings {
+    pub difficulty: Difficulty, // 選んでいる難易度
+    pub tuning: Tuning,     // 難易度の調整値（速さ、分裂のしきい値など）
+    pub nShips: i32,        // 宇宙船の個数
+    pub nAsteroids: i32,    // 最初の小惑星の個数
+    pub friendlyFire: bool, // 協力プレイで味方の弾に当たるならtrue
+    pub asteroidCollisions: bool,   // trueなら小惑星どうしがぶつかって跳ね返る
+    pub restitution: f64,   // 小惑星どうしの反発係数（1.0で完全弾性衝突）
+    pub cutFragments: bool, // trueなら割れた小惑星の破片は元の輪郭を切り分けた形
+    pub openSpace: bool,    // trueなら画面より広い宇宙をカメラで追いかける
+    pub volume: u32,        // 音量(0〜100)（効果音を入れるまでは保存だけ）
+    pub fullscreen: bool,   // trueならフルスクリーン
+    pub vectorFx: bool,     // trueならベクタースキャンのモニター風に描く（にじみと残光）
+    pub swapControls: bool, // trueなら1Pと2Pのキー割り当てを入れ替える
+    pub language: Language, // 表示する言語
+    pub theme: ThemeKind,   // 配色
+    pub reducedFlashing: bool,  // trueなら炎のちらつきと爆発の点滅を抑える
+    pub largeText: bool,    // trueならスコアなどの文字を大きくする
+    pub gameSpeed: u32,     // 1人用のゲームの速さ(%)（50, 75, 100）
+    pub netLatencyMs: u32,  // ネットワーク対戦の疑似遅延(ms)（テスト用）
+    pub netLossPercent: u32 // ネットワーク対戦の疑似パケットロス率(%)（テスト用）
+}
+
+impl Settings {
+    pub fn new() -> Self {
+        let tuning = Tuning::preset(Difficulty::NORMAL);
+        return Settings {
+            difficulty: Difficulty::NORMAL,
+            nShips: tuning.ships,
+            nAsteroids: tuning.asteroids,
+            tuning: tuning,
+            friendlyFire: false,
+            asteroidCollisions: false,
+            restitution: 1.0,
+            cutFragments: false,
+            openSpace: false,
+            volume: 80,
+            fullscreen: false,
+            vectorFx: false,
+            swapControls: false,
+            language: Language::ENGLISH,
+            theme: ThemeKind::COLOR,
+            reducedFlashing: false,
+            largeText: false,
+            gameSpeed: 100,
+            netLatencyMs: 0,
+            netLossPercent: 0
+        };
+    }
+
+    // 難易度を選ぶ（宇宙船と小惑星の個数もプリセットの値に戻す）
+    pub fn setDifficulty(&mut self, difficulty: Difficulty, tunings: &TuningTable) {
+        self.difficulty = difficulty;
+        self.tuning = tunings.get(difficulty).clone();
+        self.nShips = self.tuning.ships;
+        self.nAsteroids = self.tuning.asteroids;
+    }
+
+    // 今の設定の配色
+    pub fn getTheme(&self) -> Theme {
+        return Theme::new(self.theme, self.reducedFlashing, self.largeText);
+    }
+}
+
+// ---------------------------------
+//  シーン間で共有するデータ
+// ---------------------------------
+pub struct SceneContext {
+    pub width: u32,
+    pub height: u32,
+    pub settings: Settings,
+    pub tunings: TuningTable,   // 難易度ごとの調整値（tuning.toml）
+    pub tuningError: Option<String>,    // tuning.tomlが読めなかった理由（組み込みのプリセットで遊ぶ）
+    pub highScores: HighScore::HighScoreTable,
+    pub stats: StatsTracker     // プレイの統計と実績
+}
+
+impl SceneContext {
+    pub fn new(width: u32, height: u32) -> Self {
+        // 間違いのあるtuning.tomlは組み込みのプリセットにして、タイトルと設定画面に理由を出す
+        let (tunings, tuningError) = match TuningTable::load(TUNING_FILE) {
+            Ok(tunings) => (tunings, None),
+            Err(e) => {
+                eprintln!("{}", e);
+                eprintln!("using built-in tuning");
+                (TuningTable::new(), Some(e))
+            }
+        };
+        let mut settings = Settings::new();
+        settings.setDifficulty(Difficulty::NORMAL, &tunings);
+        Config::load(&mut settings, &tunings);  // 前回の設定
+        return SceneContext {
+            width: width,
+            height: height,
+            settings: settings,
+            tunings: tunings,
+            tuningError: tuningError,
+            highScores: HighScore::HighScoreTable::load(HighScore::HIGHSCORE_FILE),
+            stats: StatsTracker::load(STATS_FILE)
+        };
+    }
+}
+
+// ---------------------------------
+//  シーン遷移の指示
+// ---------------------------------
+pub enum Transition {
+    NONE,                   // 遷移なし
+    PUSH(Box<dyn Scene>),   // 新しいシーンを上に積む
+    POP,                    // 現在のシーンを取り除く
+    REPLACE(Box<dyn Scene>),    // 現在のシーンを入れ替える
+    RESET(Box<dyn Scene>),  // スタックを空にしてから積む
+    QUIT                    // アプリ終了
+}
+
+// ---------------------------------
+//  シーン（タイトル、プレイ中、ポーズ...）
+// ---------------------------------
+pub trait Scene {
+    // スタックの一番上になった
+    fn enter(&mut self, _ctx: &mut SceneContext) {
+    }
+
+    // スタックから取り除かれる
+    fn exit(&mut self, _ctx: &mut SceneContext) {
+    }
+
+    fn handleInput(&mut self, ctx: &mut SceneContext, event: &Event) -> Transition;
+
+    fn update(&mut self, ctx: &mut SceneContext) -> Transition;
+
+    fn render(&self, canvas: &mut dyn Draw, ctx: &SceneContext);
+
+    // 表示中のゲームがあれば返す（デバッグ表示用）
+    fn getGame(&self) -> Option<&Game> {
+        return None;
+    }
+
+    // trueなら下のシーンの上に重ねて描く（ポーズ画面など）
+    // 重ねるシーンの出し入れにはフェードをかけない
+    fn isOverlay(&self) -> bool {
+        return false;
+    }
+}
+
+enum Fade {
+    NONE,
+    OUT,    // 暗くしている途中（完了したら遷移を実行）
+    IN      // 明るくしている途中
+}
+
+const FADE_FRAMES: i32 = 15;    // フェードにかけるフレーム数
+
+// ---------------------------------
+//  シーンスタック
+// ---------------------------------
+pub struct SceneStack {
+    scenes: Vec<Box<dyn Scene>>,
+    ctx: SceneContext,
+    pending: Option<Transition>,    // フェードアウト後に実行する遷移
+    fade: Fade,
+    fadeCount: i32,
+    quit: bool
+}
+
+impl SceneStack {
+    pub fn new(first: Box<dyn Scene>, ctx: SceneContext) -> Self {
+        let mut stack = SceneStack {
+            scenes: Vec::new(),
+            ctx: ctx,
+            pending: None,
+            fade: Fade::IN,
+            fadeCount: FADE_FRAMES,
+            quit: false
+        };
+        stack.apply(Transition::PUSH(first));
+        return stack;
+    }
+
+    pub fn getContext(&self) -> &SceneContext {
+        return &self.ctx;
+    }
+
+    // 上のシーンから順に探して、表示中のゲームを返す
+    pub fn getGame(&self) -> Option<&Game> {
+        for scene in self.scenes.iter().rev() {
+            if let Some(game) = scene.getGame() {
+                return Some(game);
+            }
+        }
+        return None;
+    }
+
+    // アプリを閉じる前に呼ぶ（各シーンのexit()で後片付けや保存をさせる）
+    pub fn shutdown(&mut self) {
+        self.apply(Transition::QUIT);
+    }
+
+    // 終了してよければtrue
+    pub fn isFinished(&self) -> bool {
+        return self.quit || self.scenes.is_empty();
+    }
+
+    pub fn handleInput(&mut self, event: &Event) {
+        match self.fade {
+            Fade::NONE => {}
+            _ => { return; }    // フェード中は入力を受け付けない
+        }
+
+        let t = match self.scenes.last_mut() {
+            Some(scene) => scene.handleInput(&mut self.ctx, event),
+            None => Transition::NONE
+        };
+        self.request(t);
+    }
+
+    pub fn update(&mut self) {
+        self.ctx.stats.tick();
+
+        match self.fade {
+            Fade::OUT => {
+                self.fadeCount += 1;
+                if self.fadeCount >= FADE_FRAMES {
+                    if let Some(t) = self.pending.take() {
+                        self.apply(t);
+                    }
+                    self.fade = Fade::IN;
+                }
+                return;
+            }
+            Fade::IN => {
+                self.fadeCount -= 1;
+                if self.fadeCount <= 0 {
+                    self.fade = Fade::NONE;
+                }
+            }
+            Fade::NONE => {}
+        }
+
+        let t = match self.scenes.last_mut() {
+            Some(scene) => scene.update(&mut self.ctx),
+            None => Transition::NONE
+        };
+        self.request(t);
+    }
+
+    pub fn render(&self, canvas: &mut dyn Draw) {
+        // 重ねるシーンが続く間は下から描く
+        let mut bottom = self.scenes.len();
+        while bottom > 0 {
+            bottom -= 1;
+            if !self.scenes[bottom].isOverlay() {
+                break;
+            }
+        }
+        for scene in &self.scenes[bottom..] {
+            scene.render(canvas, &self.ctx);
+        }
+
+        // 実績解除の表示はどのシーンの上にも出す
+        self.ctx.stats.drawNotices(canvas, self.ctx.width, &self.ctx.settings.getTheme(), self.ctx.settings.language);
+
+        // フェード用に画面全体を黒で覆う
+        if self.fadeCount > 0 {
+            let alpha = (255 * self.fadeCount / FADE_FRAMES) as u8;
+            canvas.setBlendMode(BlendMode::Blend);
+            canvas.setDrawColor(Color::RGBA(0, 0, 0, alpha));
+            canvas.fillRect(None);
+            canvas.setBlendMode(BlendMode::None);
+        }
+    }
+
+    // 遷移を受け付ける（必要ならフェードアウトを始める）
+    fn request(&mut self, t: Transition) {
+        let instant = match t {
+            Transition::NONE => { return; }
+            Transition::QUIT => true,
+            Transition::PUSH(ref scene) => scene.isOverlay(),
+            Transition::POP => match self.scenes.last() {
+                Some(scene) => scene.isOverlay(),
+                None => true
+            },
+            _ => false
+        };
+
+        if instant {
+            self.apply(t);
+        } else {
+            self.pending = Some(t);
+            self.fade = Fade::OUT;
+            self.fadeCount = 0;
+        }
+    }
+
+    // 遷移を実行
+    fn apply(&mut self, t: Transition) {
+        match t {
+            Transition::NONE => {}
+            Transition::PUSH(scene) => {
+                self.push(scene);
+            }
+            Transition::POP => {
+                self.pop();
+                if let Some(scene) = self.scenes.last_mut() {
+                    scene.enter(&mut self.ctx);
+                }
+            }
+            Transition::REPLACE(scene) => {
+                self.pop();
+                self.push(scene);
+            }
+            Transition::RESET(scene) => {
+                while !self.scenes.is_empty() {
+                    self.pop();
+                }
+                self.push(scene);
+            }
+            Transition::QUIT => {
+                while !self.scenes.is_empty() {
+                    self.pop();
+                }
+                self.quit = true;
+            }
+        }
+    }
+
+    fn push(&mut self, mut scene: Box<dyn Scene>) {
+        scene.enter(&mut self.ctx);
+        self.scenes.push(scene);
+    }
+
+    fn pop(&mut self) {
+        if let Some(mut scene) = self.scenes.pop() {
+            scene.exit(&mut self.ctx);
+        }
+    }
+}
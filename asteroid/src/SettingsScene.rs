@@ -0,0 +1,187 @@
+#![allow(non_snake_case)]
+
+use sdl2::pixels::Color;
+use sdl2::event::Event;
+
+use Scene::{Scene, SceneContext, Transition};
+use Draw::Draw;
+use Tuning::DIFFICULTIES;
+use Lang::{tr, LANGUAGES};
+use Menu::{Menu, MenuAction};
+use Config;
+use Theme::THEMES;
+
+const N_ITEMS: usize = 20;   // 設定項目の数（最後は「戻る」）
+const BACK: usize = N_ITEMS - 1;
+
+// ---------------------------------
+//  設定画面
+//  上下キー（マウス）で項目を選び、左右キー（クリック）で値を変更
+//  変えた値はすぐに設定ファイルに保存する
+// ---------------------------------
+pub struct SettingsScene {
+    menu: Menu
+}
+
+impl SettingsScene {
+    pub fn new(ctx: &SceneContext) -> Self {
+        return SettingsScene {
+            menu: Menu::new((ctx.width as i32)/2, 130, 900, 34, 14, 26)
+        };
+    }
+
+    fn change(&self, ctx: &mut SceneContext, item: usize, d: i32) {
+        let settings = &mut ctx.settings;
+        match item {
+            0 => {
+                // 端まで行ったら反対側に回る（クリックだけでも一周できるように）
+                let n = DIFFICULTIES.len() as i32;
+                let i = DIFFICULTIES.iter().position(|x| *x == settings.difficulty).unwrap_or(1) as i32;
+                settings.setDifficulty(DIFFICULTIES[((i + d + n) % n) as usize], &ctx.tunings);
+            }
+            1 => {
+                settings.nShips = clamp(settings.nShips + d, 1, 9);
+            }
+            2 => {
+                settings.nAsteroids = clamp(settings.nAsteroids + d, 1, 30);
+            }
+            3 => {
+                settings.friendlyFire = !settings.friendlyFire;
+            }
+            4 => {
+                settings.asteroidCollisions = !settings.asteroidCollisions;
+            }
+            5 => {
+                let percent = clamp((settings.restitution * 100.0).round() as i32 + d * 10, 0, 100);
+                settings.restitution = percent as f64 / 100.0;
+            }
+            6 => {
+                settings.cutFragments = !settings.cutFragments;
+            }
+            7 => {
+                settings.openSpace = !settings.openSpace;
+            }
+            8 => {
+                settings.volume = clamp(settings.volume as i32 + d * 10, 0, 100) as u32;
+            }
+            9 => {
+                settings.fullscreen = !settings.fullscreen;
+            }
+            10 => {
+                settings.vectorFx = !settings.vectorFx;
+            }
+            11 => {
+                settings.swapControls = !settings.swapControls;
+            }
+            12 => {
+                let n = LANGUAGES.len() as i32;
+                let i = LANGUAGES.iter().position(|x| *x == settings.language).unwrap_or(0) as i32;
+                settings.language = LANGUAGES[((i + d + n) % n) as usize];
+            }
+            13 => {
+                let n = THEMES.len() as i32;
+                let i = THEMES.iter().position(|x| *x == settings.theme).unwrap_or(0) as i32;
+                settings.theme = THEMES[((i + d + n) % n) as usize];
+            }
+            14 => {
+                settings.reducedFlashing = !settings.reducedFlashing;
+            }
+            15 => {
+                settings.largeText = !settings.largeText;
+            }
+            16 => {
+                // 50 → 75 → 100 → 50 ...
+                let speeds = [50, 75, 100];
+                let n = speeds.len() as i32;
+                let i = speeds.iter().position(|x| *x == settings.gameSpeed).unwrap_or(2) as i32;
+                settings.gameSpeed = speeds[((i + d + n) % n) as usize];
+            }
+            17 => {
+                settings.netLatencyMs = clamp(settings.netLatencyMs as i32 + d * 20, 0, 500) as u32;
+            }
+            18 => {
+                settings.netLossPercent = clamp(settings.netLossPercent as i32 + d * 5, 0, 50) as u32;
+            }
+            _ => {
+                return;
+            }
+        }
+        Config::save(settings);
+    }
+}
+
+fn clamp(v: i32, min: i32, max: i32) -> i32 {
+    return if v < min { min } else if v > max { max } else { v };
+}
+
+impl Scene for SettingsScene {
+    fn handleInput(&mut self, ctx: &mut SceneContext, event: &Event) -> Transition {
+        match self.menu.handleEvent(event, N_ITEMS) {
+            MenuAction::CHOOSE(BACK) | MenuAction::BACK => {
+                // タイトルに戻る
+                return Transition::POP;
+            }
+            MenuAction::CHOOSE(i) => {
+                self.change(ctx, i, 1);
+            }
+            MenuAction::CHANGE(i, d) => {
+                self.change(ctx, i, d);
+            }
+            MenuAction::NONE => {}
+        }
+        return Transition::NONE;
+    }
+
+    fn update(&mut self, _ctx: &mut SceneContext) -> Transition {
+        return Transition::NONE;
+    }
+
+    fn render(&self, canvas: &mut dyn Draw, ctx: &SceneContext) {
+        let theme = ctx.settings.getTheme();
+        let width = ctx.width as i32;
+        let height = ctx.height as i32;
+        let s = &ctx.settings;
+        let lang = s.language;
+        let onOff = |b: bool| tr(lang, if b { "On" } else { "Off" });
+
+        // clear canvas
+        canvas.setDrawColor(Color::RGB(0, 0, 0)); // black
+        canvas.clear();
+
+        let title = tr(lang, "Settings");
+        canvas.textOutColor(title, theme.text, width/2-(title.chars().count() as i32)*20, 40, 40, 70);
+
+        let items = [
+            format!("{}: {}", tr(lang, "Difficulty"), tr(lang, s.difficulty.getLabel())),
+            format!("{}: {}", tr(lang, "Ships"), s.nShips),
+            format!("{}: {}", tr(lang, "Asteroids"), s.nAsteroids),
+            format!("{}: {}", tr(lang, "Friendly Fire (Co-op)"), onOff(s.friendlyFire)),
+            format!("{}: {}", tr(lang, "Asteroid Collisions"), onOff(s.asteroidCollisions)),
+            format!("{}: {} %", tr(lang, "Bounciness"), (s.restitution * 100.0).round()),
+            format!("{}: {}", tr(lang, "Fragments"), tr(lang, if s.cutFragments { "Cut from rock" } else { "New rocks" })),
+            format!("{}: {}", tr(lang, "Open Space"), onOff(s.openSpace)),
+            format!("{}: {} %", tr(lang, "Volume"), s.volume),
+            format!("{}: {}", tr(lang, "Window"), tr(lang, if s.fullscreen { "Fullscreen" } else { "Windowed" })),
+            format!("{}: {}", tr(lang, "Vector Glow"), onOff(s.vectorFx)),
+            format!("{}: P1 {} / P2 {}", tr(lang, "Controls"),
+                    if s.swapControls { "W/A/D" } else { "Arrows" },
+                    if s.swapControls { "Arrows" } else { "W/A/D" }),
+            format!("{}: {}", tr(lang, "Language"), s.language.getName()),
+            format!("{}: {}", tr(lang, "Theme"), tr(lang, s.theme.getLabel())),
+            format!("{}: {}", tr(lang, "Reduce Flashing"), onOff(s.reducedFlashing)),
+            format!("{}: {}", tr(lang, "Large HUD Text"), onOff(s.largeText)),
+            format!("{}: {} %", tr(lang, "Game Speed"), s.gameSpeed),
+            format!("{}: {} ms", tr(lang, "Net Test Latency"), s.netLatencyMs),
+            format!("{}: {} %", tr(lang, "Net Test Packet Loss"), s.netLossPercent),
+            tr(lang, "Back").to_string()
+        ];
+        self.menu.draw(canvas, &items, &theme);
+
+        let hint = tr(lang, "Up/Down: Select  Left/Right/Click: Change  Escape: Back");
+        canvas.textOutColor(hint, theme.text, width/2-(hint.chars().count() as i32)*8, height-80, 16, 26);
+        if let Some(ref e) = ctx.tuningError {
+            canvas.textOutColor(tr(lang, "Tuning file error, using built-in values"), theme.warning, 10, height-50, 10, 20);
+            canvas.textOutColor(e, theme.warning, 10, height-26, 9, 20);
+        }
+    }
+}
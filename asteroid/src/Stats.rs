@@ -0,0 +1,257 @@
+#![allow(non_snake_case)]
+
+use std::fs;
+
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::BlendMode;
+
+use Game::{GameEvent, DeathCause};
+use Draw::Draw;
+use Theme::Theme;
+use Lang::{Language, tr};
+
+pub const STATS_FILE: &str = "stats.txt";
+const LARGE_SIZE: i32 = 20;     // このサイズ以上を大きい小惑星とする
+const MEDIUM_SIZE: i32 = 10;    // このサイズ以上を中くらいの小惑星とする
+const NOTICE_FRAMES: i32 = 60 * 4;  // 実績解除の表示時間
+
+// ---------------------------------
+//  プレイの統計
+// ---------------------------------
+#[derive(Clone, Default)]
+pub struct Stats {
+    pub gamesPlayed: u64,
+    pub shotsFired: u64,
+    pub shotsHit: u64,      // 小惑星に当たった弾
+    pub largeDestroyed: u64,
+    pub mediumDestroyed: u64,
+    pub smallDestroyed: u64,
+    pub longestLife: u64,   // 1機で生き延びた最長フレーム数
+    pub deathsByAsteroid: u64,
+    pub deathsByBullet: u64,    // 味方の弾で失った宇宙船
+    pub bestWave: u64
+}
+
+// ファイルに書く名前
+const KEYS: [&str; 10] = [
+    "games_played", "shots_fired", "shots_hit",
+    "large_destroyed", "medium_destroyed", "small_destroyed",
+    "longest_life_frames", "deaths_by_asteroid", "deaths_by_bullet", "best_wave"
+];
+
+impl Stats {
+    pub fn new() -> Self {
+        return Stats::default();
+    }
+
+    // 命中率(%)、まだ撃っていなければ0
+    pub fn getAccuracy(&self) -> f64 {
+        if self.shotsFired == 0 {
+            return 0.0;
+        }
+        return 100.0 * (self.shotsHit as f64) / (self.shotsFired as f64);
+    }
+
+    pub fn getDestroyed(&self) -> u64 {
+        return self.largeDestroyed + self.mediumDestroyed + self.smallDestroyed;
+    }
+
+    fn field(&mut self, key: &str) -> Option<&mut u64> {
+        return match key {
+            "games_played" => Some(&mut self.gamesPlayed),
+            "shots_fired" => Some(&mut self.shotsFired),
+            "shots_hit" => Some(&mut self.shotsHit),
+            "large_destroyed" => Some(&mut self.largeDestroyed),
+            "medium_destroyed" => Some(&mut self.mediumDestroyed),
+            "small_destroyed" => Some(&mut self.smallDestroyed),
+            "longest_life_frames" => Some(&mut self.longestLife),
+            "deaths_by_asteroid" => Some(&mut self.deathsByAsteroid),
+            "deaths_by_bullet" => Some(&mut self.deathsByBullet),
+            "best_wave" => Some(&mut self.bestWave),
+            _ => None
+        };
+    }
+
+    fn record(&mut self, event: &GameEvent) {
+        match *event {
+            GameEvent::SHOT(_) => {
+                self.shotsFired += 1;
+            }
+            GameEvent::DESTROYED(_, size) => {
+                self.shotsHit += 1;
+                if size >= LARGE_SIZE {
+                    self.largeDestroyed += 1;
+                } else if size >= MEDIUM_SIZE {
+                    self.mediumDestroyed += 1;
+                } else {
+                    self.smallDestroyed += 1;
+                }
+            }
+            GameEvent::LOST(_, cause, frames) => {
+                match cause {
+                    DeathCause::ASTEROID => self.deathsByAsteroid += 1,
+                    DeathCause::BULLET => self.deathsByBullet += 1
+                }
+                self.longestLife = self.longestLife.max(frames.max(0) as u64);
+            }
+            GameEvent::WAVE(wave) => {
+                self.bestWave = self.bestWave.max(wave.max(0) as u64);
+            }
+            GameEvent::GAMEOVER => {
+                self.gamesPlayed += 1;
+            }
+        }
+    }
+}
+
+// ---------------------------------
+//  実績
+// ---------------------------------
+pub struct Achievement {
+    pub id: &'static str,   // ファイルに書く名前
+    pub name: &'static str,
+    pub description: &'static str,
+    check: fn(&Stats) -> bool   // 通算の統計で判定する
+}
+
+pub const ACHIEVEMENTS: [Achievement; 8] = [
+    Achievement { id: "first_rock", name: "First Rock", description: "Destroy an asteroid", check: firstRock },
+    Achievement { id: "big_game", name: "Big Game Hunter", description: "Destroy 50 large asteroids", check: bigGame },
+    Achievement { id: "demolition", name: "Demolition", description: "Destroy 500 asteroids", check: demolition },
+    Achievement { id: "sharpshooter", name: "Sharpshooter", description: "50% accuracy over 200 shots", check: sharpshooter },
+    Achievement { id: "survivor", name: "Survivor", description: "Keep one ship alive for 2 minutes", check: survivor },
+    Achievement { id: "wave_rider", name: "Wave Rider", description: "Reach wave 5", check: waveRider },
+    Achievement { id: "oops", name: "Oops", description: "Lose a ship to a teammate's bullet", check: oops },
+    Achievement { id: "veteran", name: "Veteran", description: "Play 10 games", check: veteran }
+];
+
+fn firstRock(s: &Stats) -> bool { return s.getDestroyed() >= 1; }
+fn bigGame(s: &Stats) -> bool { return s.largeDestroyed >= 50; }
+fn demolition(s: &Stats) -> bool { return s.getDestroyed() >= 500; }
+fn sharpshooter(s: &Stats) -> bool { return s.shotsFired >= 200 && s.getAccuracy() >= 50.0; }
+fn survivor(s: &Stats) -> bool { return s.longestLife >= 60 * 120; }
+fn waveRider(s: &Stats) -> bool { return s.bestWave >= 5; }
+fn oops(s: &Stats) -> bool { return s.deathsByBullet >= 1; }
+fn veteran(s: &Stats) -> bool { return s.gamesPlayed >= 10; }
+
+// ---------------------------------
+//  統計と実績の記録
+//  通算の統計と解除した実績はファイルに "名前 値" / "achievement 名前" の形で保存する
+// ---------------------------------
+pub struct StatsTracker {
+    path: String,
+    lifetime: Stats,    // 通算
+    session: Stats,     // 起動してから
+    unlocked: Vec<&'static str>,    // 解除した実績のid
+    notices: Vec<(&'static str, i32)>   // 実績解除の表示（実績の名前（英語）, 残りフレーム数）
+}
+
+impl StatsTracker {
+    pub fn load(path: &str) -> Self {
+        let mut lifetime = Stats::new();
+        let mut unlocked = Vec::new();
+
+        // ファイルがなければ0から（知らない行は読み飛ばす）
+        if let Ok(text) = fs::read_to_string(path) {
+            for line in text.lines() {
+                let mut it = line.split_whitespace();
+                if let (Some(key), Some(value)) = (it.next(), it.next()) {
+                    if key == "achievement" {
+                        if let Some(a) = ACHIEVEMENTS.iter().find(|a| a.id == value) {
+                            unlocked.push(a.id);
+                        }
+                    } else if let (Some(field), Ok(v)) = (lifetime.field(key), value.parse::<u64>()) {
+                        *field = v;
+                    }
+                }
+            }
+        }
+
+        return StatsTracker {
+            path: path.to_string(),
+            lifetime: lifetime,
+            session: Stats::new(),
+            unlocked: unlocked,
+            notices: Vec::new()
+        };
+    }
+
+    pub fn save(&self) {
+        let mut text = String::new();
+        let mut lifetime = self.lifetime.clone();
+        for key in KEYS.iter() {
+            if let Some(v) = lifetime.field(key) {
+                text.push_str(&format!("{} {}\n", key, v));
+            }
+        }
+        for id in &self.unlocked {
+            text.push_str(&format!("achievement {}\n", id));
+        }
+        if let Err(e) = fs::write(&self.path, text) {
+            println!("failed to save stats: {}", e);
+        }
+    }
+
+    pub fn getLifetime(&self) -> &Stats {
+        return &self.lifetime;
+    }
+
+    pub fn getSession(&self) -> &Stats {
+        return &self.session;
+    }
+
+    pub fn isUnlocked(&self, id: &str) -> bool {
+        return self.unlocked.iter().any(|u| *u == id);
+    }
+
+    // ゲームで起きたことを記録する
+    // counts ... 数えるプレイヤーならtrue（ネットワーク対戦では自分だけ）
+    pub fn record<F: Fn(usize) -> bool>(&mut self, events: &[GameEvent], counts: F) {
+        if events.is_empty() {
+            return;
+        }
+        for event in events {
+            let player = match *event {
+                GameEvent::SHOT(p) | GameEvent::DESTROYED(p, _) | GameEvent::LOST(p, _, _) => Some(p),
+                _ => None
+            };
+            if player.map_or(true, |p| counts(p)) {
+                self.lifetime.record(event);
+                self.session.record(event);
+            }
+        }
+
+        // 新しく解除した実績
+        for a in ACHIEVEMENTS.iter() {
+            if !self.isUnlocked(a.id) && (a.check)(&self.lifetime) {
+                self.unlocked.push(a.id);
+                self.notices.push((a.name, NOTICE_FRAMES));
+            }
+        }
+    }
+
+    // 1フレーム進める（実績解除の表示を消していく）
+    pub fn tick(&mut self) {
+        for notice in &mut self.notices {
+            notice.1 -= 1;
+        }
+        self.notices.retain(|n| n.1 > 0);
+    }
+
+    // 実績解除の表示（画面上部の中央に積む）
+    // 表示中に言語を変えても追従するように、描くときに訳す
+    pub fn drawNotices(&self, canvas: &mut dyn Draw, width: u32, theme: &Theme, lang: Language) {
+        for (i, &(name, _)) in self.notices.iter().enumerate() {
+            let text = format!("{}: {}", tr(lang, "Achievement Unlocked"), tr(lang, name));
+            let w = (text.chars().count() as i32) * 14 + 20;
+            let x = (width as i32) / 2 - w / 2;
+            let y = 90 + (i as i32) * 44;
+            canvas.setBlendMode(BlendMode::Blend);
+            canvas.setDrawColor(Color::RGBA(0, 0, 0, 200));
+            canvas.fillRect(Some(Rect::new(x, y, w as u32, 38)));
+            canvas.setBlendMode(BlendMode::None);
+            canvas.textOutColor(&text, theme.highlight, x + 10, y + 4, 14, 30);
+        }
+    }
+}
@@ -0,0 +1,96 @@
+#![allow(non_snake_case)]
+
+use sdl2::pixels::Color;
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+
+use Scene::{Scene, SceneContext, Transition};
+use Draw::Draw;
+use Stats::{Stats, ACHIEVEMENTS};
+use Lang::tr;
+
+// ---------------------------------
+//  統計と実績の画面
+//  左が起動してから、右が通算
+// ---------------------------------
+pub struct StatsScene {
+}
+
+impl StatsScene {
+    pub fn new() -> Self {
+        return StatsScene {
+        };
+    }
+}
+
+// 表の1行分（項目名（英語）, 値）
+fn rows(s: &Stats) -> Vec<(&'static str, String)> {
+    let life = s.longestLife / 60;
+    return vec![
+        ("Games Played", format!("{}", s.gamesPlayed)),
+        ("Shots Fired", format!("{}", s.shotsFired)),
+        ("Accuracy", format!("{:.1} %", s.getAccuracy())),
+        ("Asteroids L/M/S", format!("{}/{}/{}", s.largeDestroyed, s.mediumDestroyed, s.smallDestroyed)),
+        ("Longest Survival", format!("{}:{:02}", life / 60, life % 60)),
+        ("Lost to Asteroids", format!("{}", s.deathsByAsteroid)),
+        ("Lost to Teammates", format!("{}", s.deathsByBullet)),
+        ("Best Wave", format!("{}", s.bestWave))
+    ];
+}
+
+impl Scene for StatsScene {
+    fn handleInput(&mut self, _ctx: &mut SceneContext, event: &Event) -> Transition {
+        match *event {
+            Event::KeyDown { keycode: Some(Keycode::Escape), .. }
+            | Event::KeyDown { keycode: Some(Keycode::Return), .. } => {
+                // タイトルに戻る
+                return Transition::POP;
+            }
+            _ => {
+                return Transition::NONE;
+            }
+        }
+    }
+
+    fn update(&mut self, _ctx: &mut SceneContext) -> Transition {
+        return Transition::NONE;
+    }
+
+    fn render(&self, canvas: &mut dyn Draw, ctx: &SceneContext) {
+        let theme = ctx.settings.getTheme();
+        let width = ctx.width as i32;
+        let height = ctx.height as i32;
+
+        // clear canvas
+        canvas.setDrawColor(Color::RGB(0, 0, 0)); // black
+        canvas.clear();
+
+        let lang = ctx.settings.language;
+        let title = tr(lang, "Stats");
+        canvas.textOutColor(title, theme.text, width/2-(title.chars().count() as i32)*20, 30, 40, 70);
+
+        let x = width/2-420;
+        canvas.textOutColor(tr(lang, "This Session"), theme.text, x+360, 120, 12, 26);
+        canvas.textOutColor(tr(lang, "All Time"), theme.text, x+600, 120, 12, 26);
+        let session = rows(ctx.stats.getSession());
+        let lifetime = rows(ctx.stats.getLifetime());
+        for (i, (s, l)) in session.iter().zip(lifetime.iter()).enumerate() {
+            let y = 160 + (i as i32) * 34;
+            canvas.textOutColor(tr(lang, s.0), theme.text, x, y, 12, 26);
+            canvas.textOutColor(&s.1, theme.highlight, x+360, y, 12, 26);
+            canvas.textOutColor(&l.1, theme.highlight, x+600, y, 12, 26);
+        }
+
+        let top = 160 + (session.len() as i32) * 34 + 30;
+        let unlocked = ACHIEVEMENTS.iter().filter(|a| ctx.stats.isUnlocked(a.id)).count();
+        canvas.textOutColor(&format!("{} {}/{}", tr(lang, "Achievements"), unlocked, ACHIEVEMENTS.len()), theme.text, x, top, 14, 30);
+        for (i, a) in ACHIEVEMENTS.iter().enumerate() {
+            let y = top + 40 + (i as i32) * 30;
+            let (mark, c) = if ctx.stats.isUnlocked(a.id) { ("*", theme.highlight) } else { (" ", theme.dim) };
+            canvas.textOutColor(&format!("{} {} - {}", mark, tr(lang, a.name), tr(lang, a.description)), c, x, y, 11, 24);
+        }
+
+        let back = tr(lang, "Enter / Escape: Back");
+        canvas.textOutColor(back, theme.text, width/2-(back.chars().count() as i32)*10, height-60, 20, 30);
+    }
+}
@@ -0,0 +1,130 @@
+#![allow(non_snake_case)]
+
+use sdl2::pixels::Color;
+
+// ---------------------------------
+//  配色の種類
+// ---------------------------------
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ThemeKind {
+    COLOR,      // 色付き（宇宙船は黄色、文字は緑）
+    CLASSIC,    // 緑1色のモノクロモニター風
+    CONTRAST,   // 白と原色のハイコントラスト
+    COLORBLIND  // 色覚の違いがあっても見分けやすい色（Okabe-Itoの配色）
+}
+
+pub const THEMES: [ThemeKind; 4] = [ThemeKind::COLOR, ThemeKind::CLASSIC, ThemeKind::CONTRAST, ThemeKind::COLORBLIND];
+
+impl ThemeKind {
+    // 設定ファイルに書く名前
+    pub fn getCode(&self) -> &'static str {
+        return match *self {
+            ThemeKind::COLOR => "color",
+            ThemeKind::CLASSIC => "classic",
+            ThemeKind::CONTRAST => "contrast",
+            ThemeKind::COLORBLIND => "colorblind"
+        };
+    }
+
+    pub fn getLabel(&self) -> &'static str {
+        return match *self {
+            ThemeKind::COLOR => "Color",
+            ThemeKind::CLASSIC => "Classic Mono",
+            ThemeKind::CONTRAST => "High Contrast",
+            ThemeKind::COLORBLIND => "Colorblind Friendly"
+        };
+    }
+
+    pub fn fromCode(code: &str) -> Option<ThemeKind> {
+        return THEMES.iter().cloned().find(|t| t.getCode() == code);
+    }
+}
+
+// ---------------------------------
+//  描画に使う色と見やすさの設定
+// ---------------------------------
+#[derive(Clone, Copy, Debug)]
+pub struct Theme {
+    pub text: Color,        // 普通の文字、枠
+    pub dim: Color,         // 目立たせない線（レーダーの表示範囲など）
+    pub highlight: Color,   // 選んでいる項目
+    pub highlightBar: Color,    // 選んでいる項目の後ろの帯
+    pub warning: Color,     // エラーメッセージ
+    pub ships: [Color; 4],  // プレイヤーごとの宇宙船の色
+    pub asteroid: Color,
+    pub bullet: Color,
+    pub flame: Color,       // 噴射の炎
+    pub shield: Color,      // シールドとエネルギー計
+    pub reducedFlashing: bool,  // trueなら炎のちらつきと爆発の点滅を抑える
+    pub hudScale: f64       // スコアなどの文字の大きさの倍率
+}
+
+const GREEN: Color = Color { r: 0, g: 255, b: 128, a: 255 };
+const WHITE: Color = Color { r: 255, g: 255, b: 255, a: 255 };
+const YELLOW: Color = Color { r: 255, g: 255, b: 0, a: 255 };
+const LARGE_HUD_SCALE: f64 = 1.4;
+
+impl Theme {
+    pub fn new(kind: ThemeKind, reducedFlashing: bool, largeText: bool) -> Self {
+        let color = Theme {
+            text: GREEN,
+            dim: Color::RGB(0, 120, 60),
+            highlight: YELLOW,
+            highlightBar: Color::RGB(0, 60, 30),
+            warning: Color::RGB(255, 80, 80),
+            ships: [YELLOW, Color::RGB(0, 200, 255), Color::RGB(255, 100, 200), Color::RGB(120, 255, 80)],
+            asteroid: WHITE,
+            bullet: WHITE,
+            flame: Color::RGB(255, 140, 0),
+            shield: Color::RGB(80, 180, 255),
+            reducedFlashing: reducedFlashing,
+            hudScale: if largeText { LARGE_HUD_SCALE } else { 1.0 }
+        };
+        return match kind {
+            ThemeKind::COLOR => color,
+            ThemeKind::CLASSIC => Theme {
+                // 明るさだけでプレイヤーを見分ける
+                highlight: Color::RGB(200, 255, 220),
+                warning: Color::RGB(200, 255, 220),
+                ships: [GREEN, Color::RGB(150, 255, 200), Color::RGB(0, 190, 95), Color::RGB(90, 230, 150)],
+                asteroid: GREEN,
+                bullet: Color::RGB(200, 255, 220),
+                flame: Color::RGB(0, 190, 95),
+                shield: Color::RGB(150, 255, 200),
+                ..color
+            },
+            ThemeKind::CONTRAST => Theme {
+                text: WHITE,
+                dim: Color::RGB(170, 170, 170),
+                highlightBar: Color::RGB(0, 0, 150),
+                warning: Color::RGB(255, 60, 60),
+                ships: [YELLOW, Color::RGB(0, 255, 255), Color::RGB(255, 0, 255), WHITE],
+                flame: Color::RGB(255, 128, 0),
+                shield: Color::RGB(0, 255, 255),
+                ..color
+            },
+            ThemeKind::COLORBLIND => Theme {
+                text: Color::RGB(86, 180, 233),
+                dim: Color::RGB(0, 114, 178),
+                highlight: Color::RGB(240, 228, 66),
+                highlightBar: Color::RGB(0, 60, 100),
+                warning: Color::RGB(213, 94, 0),
+                ships: [Color::RGB(230, 159, 0), Color::RGB(86, 180, 233), Color::RGB(240, 228, 66), Color::RGB(204, 121, 167)],
+                asteroid: Color::RGB(235, 235, 235),
+                flame: Color::RGB(213, 94, 0),
+                shield: Color::RGB(0, 158, 115),
+                ..color
+            }
+        };
+    }
+
+    // i番目のプレイヤーの色
+    pub fn shipColor(&self, i: usize) -> Color {
+        return self.ships[i % self.ships.len()];
+    }
+
+    // スコアなどの文字の大きさ
+    pub fn hud(&self, v: i32) -> i32 {
+        return ((v as f64) * self.hudScale).round() as i32;
+    }
+}
@@ -0,0 +1,323 @@
+#![allow(non_snake_case)]
+
+use sdl2::pixels::Color;
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+
+use Scene::{Scene, SceneContext, Transition};
+use Draw::Draw;
+use PlayScene;
+use Game;
+use Game::PlayMode;
+use AiPilot::AiPilot;
+use HighScoreScene;
+use Random::Random;
+use SettingsScene;
+use NetLobbyScene;
+use SaveGame;
+use StatsScene;
+use Menu::{Menu, MenuAction};
+use Lang::tr;
+
+// ---------------------------------
+//  タイトル画面
+// ---------------------------------
+const IDLE_FRAMES: i32 = 60 * 5;     // これだけ何もしなければデモを始める
+const DEMO_FRAMES: i32 = 60 * 30;    // デモプレイの最長時間
+const SCORES_FRAMES: i32 = 60 * 6;   // ハイスコア表の表示時間
+
+// アトラクトモード（放っておくとデモとハイスコアを交互に見せる）
+enum Attract {
+    MENU,   // 通常のタイトル画面
+    DEMO,   // 自動操縦のデモプレイ
+    SCORES  // ハイスコア表
+}
+
+// メニューの項目
+#[derive(Clone, Copy, PartialEq)]
+enum Item {
+    PLAY,
+    MODE,       // 左右キー（クリック）で遊び方を切り替える
+    RESUME,     // 保存したゲームがあるときだけ出す
+    NETWORK,
+    SCORES,
+    STATS,
+    SETTINGS,
+    QUIT
+}
+
+// 選べる遊び方と表示名
+const MODES: [(PlayMode, &str); 3] = [
+    (PlayMode::SINGLE, "Single Player"),
+    (PlayMode::ALTERNATE, "Two Players (Alternate)"),
+    (PlayMode::COOP, "Co-op")
+];
+
+pub struct TitleScene {
+    menu: Menu,
+    mode: usize,    // MODESのどれを選んでいるか
+    attract: Attract,
+    count: i32,     // 今の表示になってからのフレーム数
+    demo: Option<(Game::Game, AiPilot)>,
+    hasSave: bool,  // 途中で保存したゲームがあればtrue
+    message: Option<String>     // 再開できなかったときのメッセージ
+}
+
+impl TitleScene {
+    pub fn new() -> Self {
+        return TitleScene {
+            menu: Menu::new(0, 0, 0, 1, 20, 34),
+            mode: 0,
+            attract: Attract::MENU,
+            count: 0,
+            demo: None,
+            hasSave: false,
+            message: None
+        };
+    }
+
+    fn setAttract(&mut self, attract: Attract) {
+        self.attract = attract;
+        self.count = 0;
+    }
+
+    // 今出すメニューの項目
+    fn items(&self) -> Vec<Item> {
+        let mut items = vec![Item::PLAY, Item::MODE];
+        if self.hasSave {
+            items.push(Item::RESUME);
+        }
+        items.extend_from_slice(&[Item::NETWORK, Item::SCORES, Item::STATS, Item::SETTINGS, Item::QUIT]);
+        return items;
+    }
+
+    fn choose(&mut self, ctx: &mut SceneContext, item: Item) -> Transition {
+        match item {
+            Item::PLAY => {
+                return Transition::REPLACE(Box::new(PlayScene::PlayScene::new(ctx, MODES[self.mode].0)));
+            }
+            Item::MODE => {
+                self.mode = (self.mode + 1) % MODES.len();
+            }
+            Item::RESUME => {
+                return self.resume(ctx);
+            }
+            Item::NETWORK => {
+                return Transition::PUSH(Box::new(NetLobbyScene::NetLobbyScene::new()));
+            }
+            Item::SCORES => {
+                self.setAttract(Attract::SCORES);
+            }
+            Item::STATS => {
+                return Transition::PUSH(Box::new(StatsScene::StatsScene::new()));
+            }
+            Item::SETTINGS => {
+                return Transition::PUSH(Box::new(SettingsScene::SettingsScene::new(ctx)));
+            }
+            Item::QUIT => {
+                return Transition::QUIT;
+            }
+        }
+        return Transition::NONE;
+    }
+
+    // 保存したゲームを読み込んで再開する（読めない保存データは消す）
+    fn resume(&mut self, ctx: &SceneContext) -> Transition {
+        let result = SaveGame::loadGame(SaveGame::SAVE_FILE);
+        SaveGame::remove(SaveGame::SAVE_FILE);
+        self.hasSave = false;
+        match result {
+            Ok(game) => {
+                return Transition::REPLACE(Box::new(PlayScene::PlayScene::fromGame(ctx, game)));
+            }
+            Err(e) => {
+                eprintln!("cannot resume: {}", e);
+                self.message = Some(e);
+                return Transition::NONE;
+            }
+        }
+    }
+}
+
+impl Scene for TitleScene {
+    fn enter(&mut self, ctx: &mut SceneContext) {
+        // 設定画面などから戻ってきたらメニューから
+        let selected = self.menu.getSelected();
+        self.menu = Menu::new((ctx.width as i32)/2, (ctx.height as i32)/2-70, 700, 44, 20, 34);
+        self.menu.setSelected(selected);
+        self.demo = None;
+        self.setAttract(Attract::MENU);
+        self.hasSave = SaveGame::exists(SaveGame::SAVE_FILE);
+    }
+
+    fn handleInput(&mut self, ctx: &mut SceneContext, event: &Event) -> Transition {
+        match self.attract {
+            Attract::MENU => {}
+            _ => {
+                // デモ中は何かキーを押すかクリックしたらタイトル画面に戻るだけ
+                match *event {
+                    Event::KeyDown { .. } | Event::MouseButtonDown { .. } => {
+                        self.demo = None;
+                        self.setAttract(Attract::MENU);
+                    }
+                    _ => {}
+                }
+                return Transition::NONE;
+            }
+        }
+        match *event {
+            Event::KeyDown { .. } | Event::MouseMotion { .. } | Event::MouseButtonDown { .. } => {
+                self.count = 0;
+            }
+            _ => {}
+        }
+
+        // 数字キーなどのショートカット（ゲーム開始1-3、再開R、ネットワークN、設定S、統計A）
+        match *event {
+            Event::KeyDown { keycode: Some(Keycode::A), .. } => {
+                return self.choose(ctx, Item::STATS);
+            }
+            Event::KeyDown { keycode: Some(Keycode::R), .. } if self.hasSave => {
+                return self.choose(ctx, Item::RESUME);
+            }
+            Event::KeyDown { keycode: Some(Keycode::Num1), .. } => {
+                return Transition::REPLACE(Box::new(PlayScene::PlayScene::new(ctx, PlayMode::SINGLE)));
+            }
+            Event::KeyDown { keycode: Some(Keycode::Num2), .. } => {
+                return Transition::REPLACE(Box::new(PlayScene::PlayScene::new(ctx, PlayMode::ALTERNATE)));
+            }
+            Event::KeyDown { keycode: Some(Keycode::Num3), .. } => {
+                return Transition::REPLACE(Box::new(PlayScene::PlayScene::new(ctx, PlayMode::COOP)));
+            }
+            Event::KeyDown { keycode: Some(Keycode::N), .. } => {
+                return self.choose(ctx, Item::NETWORK);
+            }
+            Event::KeyDown { keycode: Some(Keycode::S), .. } => {
+                return self.choose(ctx, Item::SETTINGS);
+            }
+            _ => {}
+        }
+
+        // メニュー（Escapeで終了）
+        let items = self.items();
+        match self.menu.handleEvent(event, items.len()) {
+            MenuAction::CHOOSE(i) => {
+                return self.choose(ctx, items[i]);
+            }
+            MenuAction::CHANGE(i, d) if items[i] == Item::MODE => {
+                let n = MODES.len() as i32;
+                self.mode = ((self.mode as i32 + d + n) % n) as usize;
+            }
+            MenuAction::BACK => {
+                return Transition::QUIT;
+            }
+            _ => {}
+        }
+        return Transition::NONE;
+    }
+
+    fn update(&mut self, ctx: &mut SceneContext) -> Transition {
+        self.count += 1;
+
+        match self.attract {
+            Attract::MENU => {
+                if self.count >= IDLE_FRAMES {
+                    // デモ開始
+                    let game = Game::Game::new(&ctx.settings, PlayMode::SINGLE, Random::seedFromTime());
+                    self.demo = Some((game, AiPilot::new()));
+                    self.setAttract(Attract::DEMO);
+                }
+            }
+            Attract::DEMO => {
+                let mut finished = self.count >= DEMO_FRAMES;
+                if let Some((ref mut game, ref mut pilot)) = self.demo {
+                    let input = pilot.think(game, 0, ctx.width, ctx.height);
+                    game.setInput(0, input);
+                    game.update(ctx.width, ctx.height);
+                    finished = finished || game.isOver();
+                }
+                if finished {
+                    self.demo = None;
+                    self.setAttract(Attract::SCORES);
+                }
+            }
+            Attract::SCORES => {
+                if self.count >= SCORES_FRAMES {
+                    self.setAttract(Attract::MENU);
+                }
+            }
+        }
+        return Transition::NONE;
+    }
+
+    fn getGame(&self) -> Option<&Game::Game> {
+        return match self.demo {
+            Some((ref game, _)) => Some(game),
+            None => None
+        };
+    }
+
+    fn render(&self, canvas: &mut dyn Draw, ctx: &SceneContext) {
+        let theme = ctx.settings.getTheme();
+        let width = ctx.width;
+        let height = ctx.height;
+
+        match self.attract {
+            Attract::MENU => {}
+            Attract::DEMO => {
+                if let Some((ref game, _)) = self.demo {
+                    game.draw(canvas, width, height);
+                }
+                canvas.textOutColor(tr(ctx.settings.language, "DEMO  -  Press any key"), theme.text, (width as i32)/2-220, (height as i32)-80, 20, 30);
+                return;
+            }
+            Attract::SCORES => {
+                canvas.setDrawColor(Color::RGB(0, 0, 0)); // black
+                canvas.clear();
+                HighScoreScene::drawTable(canvas, ctx);
+                return;
+            }
+        }
+
+        // clear canvas
+        canvas.setDrawColor(Color::RGB(0, 0, 0)); // black
+        canvas.clear();
+
+        let lang = ctx.settings.language;
+        let wl: u32 = 120;
+        let hl: u32 = 150;
+
+        canvas.textOutColor("Asteroid", theme.text, (width as i32)/2-(wl as i32)*4, (height as i32)/2-280, wl, hl);
+
+        let labels: Vec<String> = self.items().iter().map(|item| {
+            match *item {
+                Item::PLAY => tr(lang, "Play").to_string(),
+                Item::MODE => format!("{}: < {} >", tr(lang, "Mode"), tr(lang, MODES[self.mode].1)),
+                Item::RESUME => tr(lang, "Resume Saved Game").to_string(),
+                Item::NETWORK => tr(lang, "Network Game").to_string(),
+                Item::SCORES => tr(lang, "High Scores").to_string(),
+                Item::STATS => tr(lang, "Stats & Achievements").to_string(),
+                Item::SETTINGS => tr(lang, "Settings").to_string(),
+                Item::QUIT => tr(lang, "Quit").to_string()
+            }
+        }).collect();
+        self.menu.draw(canvas, &labels, &theme);
+
+        // 操作の説明（1Pのキー）
+        let controls = tr(lang, if ctx.settings.swapControls {
+            "W/A/D: Ship Control   Left Shift: Fire   Left Ctrl: Shield"
+        } else {
+            "Arrow Keys: Ship Control   Space Bar: Fire   Right Shift: Shield"
+        });
+        let hint = tr(lang, "Up/Down: Select  Enter/Click: Choose  Left/Right: Change");
+        canvas.textOutColor(controls, theme.text, (width as i32)/2-(controls.chars().count() as i32)*8, (height as i32)-110, 16, 28);
+        canvas.textOutColor(hint, theme.text, (width as i32)/2-(hint.chars().count() as i32)*8, (height as i32)-70, 16, 28);
+        if let Some(ref message) = self.message {
+            canvas.textOutColor(&format!("{}: {}", tr(lang, "Cannot resume"), message), theme.warning, 10, (height as i32)-30, 10, 20);
+        }
+        if let Some(ref e) = ctx.tuningError {
+            canvas.textOutColor(tr(lang, "Tuning file error, using built-in values"), theme.warning, 10, 10, 10, 20);
+            canvas.textOutColor(e, theme.warning, 10, 34, 9, 20);
+        }
+    }
+}
@@ -0,0 +1,338 @@
+#![allow(non_snake_case)]
+
+use std::fs;
+
+use SaveGame::{SaveWriter, SaveReader};
+
+pub const TUNING_FILE: &str = "tuning.toml";
+
+// ---------------------------------
+//  難易度
+// ---------------------------------
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Difficulty {
+    EASY,
+    NORMAL,
+    HARD
+}
+
+pub const DIFFICULTIES: [Difficulty; 3] = [Difficulty::EASY, Difficulty::NORMAL, Difficulty::HARD];
+
+impl Difficulty {
+    // tuning.tomlのセクション名
+    pub fn getName(&self) -> &'static str {
+        return match *self {
+            Difficulty::EASY => "easy",
+            Difficulty::NORMAL => "normal",
+            Difficulty::HARD => "hard"
+        };
+    }
+
+    pub fn getLabel(&self) -> &'static str {
+        return match *self {
+            Difficulty::EASY => "Easy",
+            Difficulty::NORMAL => "Normal",
+            Difficulty::HARD => "Hard"
+        };
+    }
+
+    pub fn fromName(name: &str) -> Option<Difficulty> {
+        return DIFFICULTIES.iter().cloned().find(|d| d.getName() == name);
+    }
+}
+
+// ---------------------------------
+//  ゲームの調整値
+// ---------------------------------
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tuning {
+    pub shipMaxSpeed: f64,      // 宇宙船の最大速さ(px/フレーム)
+    pub shipRotationDeg: f64,   // 1フレームの最大回転角(度)
+    pub shipThrust: f64,        // 加速度(px/フレーム^2)
+    pub bulletSpeed: f64,       // 弾の速さ(px/フレーム)
+    pub ships: i32,             // 宇宙船の個数
+    pub asteroids: i32,         // 最初の小惑星の個数
+    pub splitMinSize: i32,      // このサイズ以上の小惑星は撃つと分裂する
+    pub shieldDrain: f64,       // シールドを張っている間に減るエネルギー(/フレーム、最大100)
+    pub shieldRecharge: f64,    // シールドを張っていない間に溜まるエネルギー(/フレーム)
+    pub bulletLifetime: i32,    // 弾が消えるまでのフレーム数（射程）
+    pub maxBullets: i32,        // 1人が同時に撃っておける弾の数
+    pub fireCooldown: i32,      // 撃ってから次に撃てるまでのフレーム数
+    pub shipDrag: f64,          // 1フレームで失う速度の割合（0なら止まらない）
+    pub reverseThrust: f64,     // 逆噴射の強さ（前向きの加速度に対する割合、0なら逆噴射なし）
+    pub shipRotationAccelDeg: f64   // 回転の角加速度(度/フレーム^2)
+}
+
+// 項目の名前、最小値、最大値（保存データもこの順に書く）
+const KEYS: [(&str, f64, f64); 15] = [
+    ("ship_max_speed", 1.0, 50.0),
+    ("ship_rotation_deg", 0.5, 30.0),
+    ("ship_thrust", 0.05, 5.0),
+    ("bullet_speed", 1.0, 50.0),
+    ("ships", 1.0, 9.0),
+    ("asteroids", 1.0, 30.0),
+    ("split_min_size", 2.0, 64.0),
+    ("shield_drain", 0.05, 100.0),
+    ("shield_recharge", 0.0, 100.0),
+    ("bullet_lifetime", 10.0, 600.0),
+    ("max_bullets", 1.0, 20.0),
+    ("fire_cooldown", 0.0, 60.0),
+    ("ship_drag", 0.0, 0.5),
+    ("reverse_thrust", 0.0, 1.0),
+    ("ship_rotation_accel_deg", 0.1, 30.0)
+];
+
+// 整数でなければいけない項目
+const WHOLE_KEYS: [&str; 6] = ["ships", "asteroids", "split_min_size", "bullet_lifetime", "max_bullets", "fire_cooldown"];
+
+// 値がKEYSの範囲に入っているか（tuning.tomlと保存データの両方で使う）
+fn checkValue(key: &str, v: f64) -> Result<(), String> {
+    if let Some(&(_, min, max)) = KEYS.iter().find(|k| k.0 == key) {
+        if !(v >= min && v <= max) {
+            return Err(format!("{} must be between {} and {} (got {})", key, min, max, v));
+        }
+    }
+    if WHOLE_KEYS.contains(&key) && v.fract() != 0.0 {
+        return Err(format!("{} must be a whole number (got {})", key, v));
+    }
+    return Ok(());
+}
+
+impl Tuning {
+    // 組み込みのプリセット（tuning.tomlがなくても遊べるように）
+    pub fn preset(difficulty: Difficulty) -> Self {
+        let normal = Tuning {
+            shipMaxSpeed: 15.0,
+            shipRotationDeg: 5.0,
+            shipThrust: 0.5,
+            bulletSpeed: 5.0,
+            ships: 3,
+            asteroids: 10,
+            splitMinSize: 6,
+            shieldDrain: 1.0,
+            shieldRecharge: 0.25,
+            bulletLifetime: 110,
+            maxBullets: 4,
+            fireCooldown: 8,
+            shipDrag: 0.008,
+            reverseThrust: 0.5,
+            shipRotationAccelDeg: 1.0
+        };
+        return match difficulty {
+            Difficulty::EASY => Tuning { ships: 5, asteroids: 6, bulletSpeed: 7.0, shieldDrain: 0.6, shieldRecharge: 0.4,
+                                         bulletLifetime: 120, maxBullets: 6, fireCooldown: 6,
+                                         shipDrag: 0.015, shipRotationAccelDeg: 1.5, ..normal },
+            Difficulty::NORMAL => normal,
+            Difficulty::HARD => Tuning { shipMaxSpeed: 12.0, shipThrust: 0.4, ships: 2, asteroids: 16, splitMinSize: 4, shieldDrain: 1.5, shieldRecharge: 0.15,
+                                         bulletLifetime: 90, fireCooldown: 10,
+                                         shipDrag: 0.004, reverseThrust: 0.3, shipRotationAccelDeg: 0.8, ..normal }
+        };
+    }
+
+    pub fn save(&self, w: &mut SaveWriter) {
+        w.line("tuning");
+        w.f64(self.shipMaxSpeed);
+        w.f64(self.shipRotationDeg);
+        w.f64(self.shipThrust);
+        w.f64(self.bulletSpeed);
+        w.i32(self.ships);
+        w.i32(self.asteroids);
+        w.i32(self.splitMinSize);
+        w.f64(self.shieldDrain);
+        w.f64(self.shieldRecharge);
+        w.i32(self.bulletLifetime);
+        w.i32(self.maxBullets);
+        w.i32(self.fireCooldown);
+        w.f64(self.shipDrag);
+        w.f64(self.reverseThrust);
+        w.f64(self.shipRotationAccelDeg);
+    }
+
+    // 値はKEYSの順に並んでいて、tuning.tomlと同じ範囲を確かめる
+    pub fn load(r: &mut SaveReader) -> Result<Self, String> {
+        r.expect("tuning")?;
+        let mut tuning = Tuning::preset(Difficulty::NORMAL);
+        for &(key, _, _) in KEYS.iter() {
+            let v = if WHOLE_KEYS.contains(&key) { r.i32()? as f64 } else { r.f64()? };
+            if let Err(e) = checkValue(key, v) {
+                return Err(r.error(&e));
+            }
+            tuning.set(key, v).map_err(|e| r.error(&e))?;
+        }
+        return Ok(tuning);
+    }
+
+    // KEYSに書いてあってもここで扱っていなければエラー（書き漏れを見つけるため）
+    fn set(&mut self, key: &str, v: f64) -> Result<(), String> {
+        match key {
+            "ship_max_speed" => self.shipMaxSpeed = v,
+            "ship_rotation_deg" => self.shipRotationDeg = v,
+            "ship_thrust" => self.shipThrust = v,
+            "bullet_speed" => self.bulletSpeed = v,
+            "ships" => self.ships = v as i32,
+            "asteroids" => self.asteroids = v as i32,
+            "shield_drain" => self.shieldDrain = v,
+            "shield_recharge" => self.shieldRecharge = v,
+            "bullet_lifetime" => self.bulletLifetime = v as i32,
+            "max_bullets" => self.maxBullets = v as i32,
+            "fire_cooldown" => self.fireCooldown = v as i32,
+            "ship_drag" => self.shipDrag = v,
+            "reverse_thrust" => self.reverseThrust = v,
+            "ship_rotation_accel_deg" => self.shipRotationAccelDeg = v,
+            "split_min_size" => self.splitMinSize = v as i32,
+            _ => { return Err(format!("tuning key '{}' is not handled", key)); }
+        }
+        return Ok(());
+    }
+}
+
+// ---------------------------------
+//  難易度ごとの調整値（tuning.tomlから読む）
+//
+//    [normal]
+//    ship_max_speed = 15.0
+//    ships = 3
+//    ...
+//
+//  書いていない項目は組み込みのプリセットの値になる
+// ---------------------------------
+#[derive(Clone)]
+pub struct TuningTable {
+    presets: Vec<Tuning>    // DIFFICULTIESの順
+}
+
+impl TuningTable {
+    pub fn new() -> Self {
+        return TuningTable {
+            presets: DIFFICULTIES.iter().map(|d| Tuning::preset(*d)).collect()
+        };
+    }
+
+    pub fn get(&self, difficulty: Difficulty) -> &Tuning {
+        let i = DIFFICULTIES.iter().position(|d| *d == difficulty).unwrap_or(1);
+        return &self.presets[i];
+    }
+
+    // ファイルがなければ組み込みのプリセット
+    // 間違いがあれば "ファイル名: line N: 理由" を返す（どうするかは呼ぶ側で決める）
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(_) => { return Ok(TuningTable::new()); }
+        };
+        return TuningTable::parse(&text).map_err(|e| format!("{}: {}", path, e));
+    }
+
+    // TOMLのうち [section] と key = 数値 とコメントだけを読む
+    pub fn parse(text: &str) -> Result<TuningTable, String> {
+        let mut table = TuningTable::new();
+        let mut section: Option<usize> = None;
+
+        for (n, raw) in text.lines().enumerate() {
+            let lineNo = n + 1;
+            let line = match raw.find('#') {
+                Some(i) => &raw[..i],
+                None => raw
+            }.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if line.starts_with('[') {
+                if !line.ends_with(']') {
+                    return Err(format!("line {}: missing ']' in section header", lineNo));
+                }
+                let name = line[1..line.len()-1].trim();
+                match Difficulty::fromName(name) {
+                    Some(d) => section = DIFFICULTIES.iter().position(|x| *x == d),
+                    None => {
+                        return Err(format!("line {}: unknown section [{}] (expected [easy], [normal] or [hard])", lineNo, name));
+                    }
+                }
+                continue;
+            }
+
+            let eq = match line.find('=') {
+                Some(i) => i,
+                None => { return Err(format!("line {}: expected 'key = value'", lineNo)); }
+            };
+            let key = line[..eq].trim();
+            let value = line[eq+1..].trim();
+
+            let i = match section {
+                Some(i) => i,
+                None => { return Err(format!("line {}: '{}' must be inside a section like [normal]", lineNo, key)); }
+            };
+            if !KEYS.iter().any(|k| k.0 == key) {
+                let names: Vec<&str> = KEYS.iter().map(|k| k.0).collect();
+                return Err(format!("line {}: unknown key '{}' (expected one of {})", lineNo, key, names.join(", ")));
+            }
+            let v = match value.parse::<f64>() {
+                Ok(v) => v,
+                Err(_) => { return Err(format!("line {}: {} must be a number (got '{}')", lineNo, key, value)); }
+            };
+            if let Err(e) = checkValue(key, v) {
+                return Err(format!("line {}: {}", lineNo, e));
+            }
+            table.presets[i].set(key, v).map_err(|e| format!("line {}: {}", lineNo, e))?;
+        }
+        return Ok(table);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parseError(text: &str) -> String {
+        return match TuningTable::parse(text) {
+            Ok(_) => panic!("'{}' was accepted", text),
+            Err(e) => e
+        };
+    }
+
+    #[test]
+    fn every_key_can_be_set() {
+        // 最小値と最大値を入れたら違う調整値になる（どこかの項目に書き込まれている）
+        for &(key, min, max) in KEYS.iter() {
+            let mut low = Tuning::preset(Difficulty::NORMAL);
+            let mut high = low.clone();
+            assert_eq!(low.set(key, min), Ok(()), "{}", key);
+            assert_eq!(high.set(key, max), Ok(()), "{}", key);
+            assert!(low != high, "{} does not change the tuning", key);
+        }
+        assert!(Tuning::preset(Difficulty::NORMAL).set("no_such_key", 1.0).is_err());
+    }
+
+    #[test]
+    fn values_go_to_their_section() {
+        let table = TuningTable::parse("# comment\n[hard]\nships = 1   # one ship\nship_drag = 0.1\n\n[easy]\nasteroids = 2\n").unwrap();
+        assert_eq!(table.get(Difficulty::HARD).ships, 1);
+        assert_eq!(table.get(Difficulty::HARD).shipDrag, 0.1);
+        assert_eq!(table.get(Difficulty::EASY).asteroids, 2);
+        assert!(*table.get(Difficulty::NORMAL) == Tuning::preset(Difficulty::NORMAL));
+    }
+
+    #[test]
+    fn out_of_range_values_are_rejected() {
+        assert_eq!(parseError("[normal]\nships = 0"), "line 2: ships must be between 1 and 9 (got 0)");
+        assert_eq!(parseError("[normal]\nship_drag = 0.6"), "line 2: ship_drag must be between 0 and 0.5 (got 0.6)");
+        assert_eq!(parseError("[normal]\nships = 2.5"), "line 2: ships must be a whole number (got 2.5)");
+        assert!(parseError("[normal]\nship_thrust = NaN").contains("must be between"));
+    }
+
+    #[test]
+    fn unknown_keys_and_sections_are_rejected() {
+        assert!(parseError("[normal]\nship_speed = 3").starts_with("line 2: unknown key 'ship_speed' (expected one of ship_max_speed, "));
+        assert_eq!(parseError("[insane]\nships = 3"), "line 1: unknown section [insane] (expected [easy], [normal] or [hard])");
+        assert_eq!(parseError("[normal\nships = 3"), "line 1: missing ']' in section header");
+        assert_eq!(parseError("ships = 3"), "line 1: 'ships' must be inside a section like [normal]");
+    }
+
+    #[test]
+    fn bad_numbers_are_rejected() {
+        assert_eq!(parseError("[normal]\nships = three"), "line 2: ships must be a number (got 'three')");
+        assert_eq!(parseError("[normal]\nships ="), "line 2: ships must be a number (got '')");
+        assert_eq!(parseError("[normal]\nships 3"), "line 2: expected 'key = value'");
+    }
+}
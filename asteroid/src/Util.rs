@@ -1,7 +1,6 @@
 #![allow(non_snake_case)]
 
-use sdl2::video::{Window, WindowContext};
-use sdl2::render::{Canvas, TextureCreator};
+use sdl2::render::{Canvas, RenderTarget, TextureCreator};
 use sdl2::rect::Rect;
 use sdl2::pixels::Color;
 use sdl2::ttf::Font;
@@ -11,11 +10,33 @@ pub fn deg2rad(x: f64) -> f64 {
     return x * 3.14159265 / 180.0;
 }
 
+// 画面の端でつながっていることを考えた差分
+// (-size/2 .. size/2 の範囲にする)
+pub fn wrapDelta(d: f64, size: f64) -> f64 {
+    if d > size / 2.0 {
+        return d - size;
+    } else if d < -size / 2.0 {
+        return d + size;
+    }
+    return d;
+}
+
+// 座標を 0 .. size の範囲に戻す
+pub fn wrap(v: f64, size: f64) -> f64 {
+    if v < 0.0 {
+        return v + size;
+    } else if v >= size {
+        return v - size;
+    }
+    return v;
+}
+
 // 画面にテキストを描画
-pub fn textOut<'a>(
-    canvas: &mut Canvas<Window>,
+// texture_creatorはcanvasから作ったものを渡すこと
+pub fn textOut<'a, T: RenderTarget, C>(
+    canvas: &mut Canvas<T>,
     font: &Font,
-    texture_creator: &'a TextureCreator<WindowContext>,
+    texture_creator: &'a TextureCreator<C>,
     text: &str,
     r: u8, g: u8, b: u8,
     x: i32, y: i32,
@@ -23,6 +44,6 @@ pub fn textOut<'a>(
 ) {
     let surface = font.render(text).blended(Color::RGB(r, g, b)).expect("failed to get texture surface");
     let rendered_text = texture_creator.create_texture_from_surface(&surface).ok().expect("failed to render text");
-    let l = text.len();
+    let l = text.chars().count();   // アクセント付きの文字も1文字
     canvas.copy(&rendered_text, None, Some(Rect::new(x, y, w*(l as u32), h))).expect("failed to copy text");
 }
\ No newline at end of file
@@ -0,0 +1,169 @@
+#![allow(non_snake_case)]
+
+use sdl2::render::{Canvas, RenderTarget, Texture, TextureCreator, BlendMode};
+use sdl2::pixels::{Color, PixelFormatEnum};
+use sdl2::rect::{Point, Rect};
+use sdl2::ttf::Font;
+use sdl2::hint;
+
+use Draw::{Draw, CanvasDraw};
+
+const FADE_ALPHA: u8 = 90;      // 1フレームで残光を暗くする強さ（255で残光なし）
+const EDGE_ALPHA: u32 = 90;     // 太線の縁の濃さ（0〜255）
+const GLOW_SCALE: u32 = 4;      // にじみ用テクスチャの縮小率
+const GLOW_ALPHA: u8 = 150;     // にじみを重ねる強さ
+
+// ---------------------------------
+//  ベクタースキャンのモニター風の描画
+//    1. 線を太くして縁をぼかす（VectorDraw）
+//    2. 前のフレームを消さずに暗くするだけにして残光を出す
+//    3. 縮小したコピーを拡大して加算合成し、にじませる
+//  テクスチャへの描画で行うので、ソフトウェアレンダラーでも動く
+// ---------------------------------
+pub struct VectorFx<'t> {
+    persist: Texture<'t>,   // 残光を溜めるテクスチャ（画面と同じ大きさ）
+    glow: Texture<'t>,      // にじみ用の縮小テクスチャ
+    width: u32,
+    height: u32,
+    fresh: bool     // まだ1度も描いていなければtrue（最初に真っ黒にする）
+}
+
+impl<'t> VectorFx<'t> {
+    // texture_creatorは描画先のCanvasから作ったものを渡すこと
+    pub fn new<C>(texture_creator: &'t TextureCreator<C>, width: u32, height: u32) -> Result<Self, String> {
+        let persist = texture_creator.create_texture_target(PixelFormatEnum::ARGB8888, width, height)
+            .map_err(|e| e.to_string())?;
+
+        // 縮小テクスチャは拡大したときにぼやけるように線形補間にする
+        hint::set("SDL_RENDER_SCALE_QUALITY", "1");
+        let glow = texture_creator.create_texture_target(PixelFormatEnum::ARGB8888, width / GLOW_SCALE, height / GLOW_SCALE)
+            .map_err(|e| e.to_string());
+        hint::set("SDL_RENDER_SCALE_QUALITY", "0");
+        let mut glow = glow?;
+        glow.set_blend_mode(BlendMode::Add);
+        glow.set_alpha_mod(GLOW_ALPHA);
+
+        return Ok(VectorFx {
+            persist: persist,
+            glow: glow,
+            width: width,
+            height: height,
+            fresh: true
+        });
+    }
+
+    // drawに描いたものを効果をかけてcanvasに出す
+    pub fn render<T, C, F>(&mut self, canvas: &mut Canvas<T>, texture_creator: &TextureCreator<C>, font: Option<&Font>, draw: F) -> Result<(), String>
+        where T: RenderTarget, F: FnOnce(&mut dyn Draw)
+    {
+        let fresh = self.fresh;
+        self.fresh = false;
+
+        // 1, 2. 残光のテクスチャに重ねて描く
+        canvas.with_texture_canvas(&mut self.persist, |c| {
+            if fresh {
+                c.set_draw_color(Color::RGB(0, 0, 0));
+                c.clear();
+            }
+            let mut target = CanvasDraw::new(c, texture_creator, font);
+            let mut vector = VectorDraw::new(&mut target);
+            draw(&mut vector);
+        }).map_err(|e| e.to_string())?;
+
+        // 3. 縮小コピー
+        let persist = &self.persist;
+        canvas.with_texture_canvas(&mut self.glow, |c| {
+            c.set_draw_color(Color::RGB(0, 0, 0));
+            c.clear();
+            let _ = c.copy(persist, None, None);
+        }).map_err(|e| e.to_string())?;
+
+        // 画面に出す（にじみは少しずつずらして何回か足す）
+        canvas.set_blend_mode(BlendMode::None);
+        canvas.set_draw_color(Color::RGB(0, 0, 0));
+        canvas.clear();
+        canvas.copy(&self.persist, None, None)?;
+        let (w, h) = (self.width, self.height);
+        for &(dx, dy) in &[(-3, 0), (3, 0), (0, -3), (0, 3)] {
+            canvas.copy(&self.glow, None, Some(Rect::new(dx, dy, w, h)))?;
+        }
+        return Ok(());
+    }
+}
+
+// ---------------------------------
+//  太くて縁のぼけた線を描くDraw
+//  clear()は画面を消さずに暗くするだけ（残光）
+// ---------------------------------
+pub struct VectorDraw<'a> {
+    inner: &'a mut dyn Draw,
+    color: Color,       // 今の描画色
+    blend: BlendMode    // 今のブレンドモード
+}
+
+impl<'a> VectorDraw<'a> {
+    pub fn new(inner: &'a mut dyn Draw) -> Self {
+        return VectorDraw {
+            inner: inner,
+            color: Color::RGB(255, 255, 255),
+            blend: BlendMode::None
+        };
+    }
+
+    // 上下左右に1pxずらした縁を薄く描いてから、本体を描く
+    fn thick<F: Fn(&mut dyn Draw, i32, i32)>(&mut self, f: F) {
+        let c = self.color;
+        self.inner.setBlendMode(BlendMode::Blend);
+        self.inner.setDrawColor(Color::RGBA(c.r, c.g, c.b, ((c.a as u32) * EDGE_ALPHA / 255) as u8));
+        for &(dx, dy) in &[(-1, 0), (1, 0), (0, -1), (0, 1)] {
+            f(self.inner, dx, dy);
+        }
+        self.inner.setBlendMode(self.blend);
+        self.inner.setDrawColor(c);
+        f(self.inner, 0, 0);
+    }
+}
+
+impl<'a> Draw for VectorDraw<'a> {
+    fn size(&self) -> (u32, u32) {
+        return self.inner.size();
+    }
+
+    fn setDrawColor(&mut self, c: Color) {
+        self.color = c;
+        self.inner.setDrawColor(c);
+    }
+
+    fn setBlendMode(&mut self, mode: BlendMode) {
+        self.blend = mode;
+        self.inner.setBlendMode(mode);
+    }
+
+    // 消さずに暗くする
+    fn clear(&mut self) {
+        self.inner.setBlendMode(BlendMode::Blend);
+        self.inner.setDrawColor(Color::RGBA(0, 0, 0, FADE_ALPHA));
+        self.inner.fillRect(None);
+        self.inner.setBlendMode(self.blend);
+        self.inner.setDrawColor(self.color);
+    }
+
+    fn drawPoint(&mut self, p: Point) {
+        self.thick(|d, dx, dy| d.drawPoint(p.offset(dx, dy)));
+    }
+
+    fn drawLines(&mut self, ps: &[Point]) {
+        self.thick(|d, dx, dy| {
+            let shifted: Vec<Point> = ps.iter().map(|p| p.offset(dx, dy)).collect();
+            d.drawLines(&shifted);
+        });
+    }
+
+    fn fillRect(&mut self, rect: Option<Rect>) {
+        self.inner.fillRect(rect);
+    }
+
+    fn textOut(&mut self, text: &str, r: u8, g: u8, b: u8, x: i32, y: i32, w: u32, h: u32) {
+        self.inner.textOut(text, r, g, b, x, y, w, h);
+    }
+}
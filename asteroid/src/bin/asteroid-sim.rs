@@ -0,0 +1,338 @@
+// *****************************************************************
+//  Asteroid バランス調整用シミュレーター
+//  ウィンドウを出さずに、乱数の種を変えながら自動操縦で何千回も遊び、
+//  生き延びた時間、スコアの分布、到達した波、宇宙船を失った原因を集計する
+//
+//  例: cargo run --release --bin asteroid-sim -- --games 5000 --difficulty hard --format json --out hard.json
+// *****************************************************************
+#![allow(non_snake_case)]
+extern crate asteroid;
+
+use std::env;
+use std::fs;
+use std::collections::BTreeMap;
+
+use asteroid::Game::{Game, GameState, GameEvent, DeathCause, PlayMode};
+use asteroid::AiPilot::AiPilot;
+use asteroid::Input::PlayerInput;
+use asteroid::Scene::Settings;
+use asteroid::Tuning::{TuningTable, Difficulty, TUNING_FILE};
+
+const WIDTH: u32 = 1280;    // ゲーム本体と同じ画面の大きさで動かす
+const HEIGHT: u32 = 900;
+const FPS: f64 = 60.0;
+
+// ---------------------------------
+//  宇宙船の操縦のしかた
+// ---------------------------------
+#[derive(Clone, Copy, PartialEq)]
+enum PilotKind {
+    AI,     // デモプレイと同じ自動操縦
+    SPIN,   // 左に回りながら撃ち続ける（比較用の単純な操縦）
+    IDLE    // 何もしない（小惑星だけで何秒生き延びるか）
+}
+
+impl PilotKind {
+    fn fromName(name: &str) -> Option<PilotKind> {
+        return match name {
+            "ai" => Some(PilotKind::AI),
+            "spin" => Some(PilotKind::SPIN),
+            "idle" => Some(PilotKind::IDLE),
+            _ => None
+        };
+    }
+
+    fn getName(&self) -> &'static str {
+        return match *self {
+            PilotKind::AI => "ai",
+            PilotKind::SPIN => "spin",
+            PilotKind::IDLE => "idle"
+        };
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum OutputFormat {
+    CSV,    // 1ゲーム1行
+    JSON    // 集計と全ゲームの結果
+}
+
+// ---------------------------------
+//  1ゲーム分の結果
+// ---------------------------------
+struct GameResult {
+    seed: u64,
+    frames: u32,            // ゲームオーバーまでのフレーム数
+    score: i32,
+    wave: i32,              // 最後に到達した波
+    shots: u32,
+    destroyed: u32,         // 壊した小惑星の数
+    asteroidDeaths: u32,    // 小惑星にぶつかって失った宇宙船
+    bulletDeaths: u32,      // 味方の弾で失った宇宙船
+    shipFrames: Vec<i32>,   // 失った宇宙船ごとの生き延びたフレーム数
+    timedOut: bool          // --max-framesまでにゲームオーバーにならなかった
+}
+
+// 1ゲームを最後まで（またはmaxFramesまで）進める
+fn runGame(settings: &Settings, pilotKind: PilotKind, seed: u64, maxFrames: u32) -> GameResult {
+    let mut game = Game::new(settings, PlayMode::SINGLE, seed);
+    let mut pilot = AiPilot::new();
+    let mut result = GameResult {
+        seed: seed,
+        frames: 0,
+        score: 0,
+        wave: 1,
+        shots: 0,
+        destroyed: 0,
+        asteroidDeaths: 0,
+        bulletDeaths: 0,
+        shipFrames: Vec::new(),
+        timedOut: true
+    };
+
+    while result.frames < maxFrames {
+        let input = match pilotKind {
+            PilotKind::AI => pilot.think(&game, 0, WIDTH, HEIGHT),
+            PilotKind::SPIN => PlayerInput { left: true, fire: result.frames % 2 == 0, ..PlayerInput::default() },
+            PilotKind::IDLE => PlayerInput::default()
+        };
+        game.setInput(0, input);
+        game.update(WIDTH, HEIGHT);
+        result.frames += 1;
+
+        for event in game.getEvents() {
+            match *event {
+                GameEvent::SHOT(_) => result.shots += 1,
+                GameEvent::DESTROYED(_, _) => result.destroyed += 1,
+                GameEvent::LOST(_, cause, frames) => {
+                    match cause {
+                        DeathCause::ASTEROID => result.asteroidDeaths += 1,
+                        DeathCause::BULLET => result.bulletDeaths += 1
+                    }
+                    result.shipFrames.push(frames);
+                }
+                GameEvent::WAVE(wave) => result.wave = wave,
+                GameEvent::GAMEOVER => {}
+            }
+        }
+
+        // ゲームオーバー画面の表示待ちはしない
+        if let GameState::GAMEOVER = *game.getState() {
+            result.timedOut = false;
+            break;
+        }
+    }
+    result.score = game.getScore(0);
+    return result;
+}
+
+// ---------------------------------
+//  集計
+// ---------------------------------
+struct Summary {
+    games: usize,
+    meanSeconds: f64,       // ゲームオーバーまでの平均秒数
+    meanShipSeconds: Option<f64>,   // 失った宇宙船1機あたりの平均寿命(秒)（1機も失っていなければNone）
+    scores: Vec<(&'static str, f64)>,   // 最小、パーセンタイル、最大、平均
+    waves: BTreeMap<i32, usize>,        // 到達した波 → ゲーム数
+    asteroidDeaths: u32,
+    bulletDeaths: u32,
+    timedOut: usize
+}
+
+// ソート済みの値のpパーセンタイル（最も近い順位の値）
+fn percentile(sorted: &[i32], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let i = ((p / 100.0) * ((sorted.len() - 1) as f64)).round() as usize;
+    return sorted[i] as f64;
+}
+
+fn summarize(results: &[GameResult]) -> Summary {
+    let n = results.len().max(1) as f64;
+    let mut scores: Vec<i32> = results.iter().map(|r| r.score).collect();
+    scores.sort();
+    let meanScore = scores.iter().map(|&s| s as f64).sum::<f64>() / n;
+
+    let shipFrames: Vec<i32> = results.iter().flat_map(|r| r.shipFrames.iter().cloned()).collect();
+    let meanShipFrames = if shipFrames.is_empty() { None } else { Some(shipFrames.iter().map(|&f| f as f64).sum::<f64>() / (shipFrames.len() as f64)) };
+
+    let mut waves = BTreeMap::new();
+    for r in results {
+        *waves.entry(r.wave).or_insert(0) += 1;
+    }
+
+    return Summary {
+        games: results.len(),
+        meanSeconds: results.iter().map(|r| r.frames as f64).sum::<f64>() / n / FPS,
+        meanShipSeconds: meanShipFrames.map(|f| f / FPS),
+        scores: vec![
+            ("min", percentile(&scores, 0.0)),
+            ("p10", percentile(&scores, 10.0)),
+            ("p25", percentile(&scores, 25.0)),
+            ("median", percentile(&scores, 50.0)),
+            ("p75", percentile(&scores, 75.0)),
+            ("p90", percentile(&scores, 90.0)),
+            ("max", percentile(&scores, 100.0)),
+            ("mean", meanScore)
+        ],
+        waves: waves,
+        asteroidDeaths: results.iter().map(|r| r.asteroidDeaths).sum(),
+        bulletDeaths: results.iter().map(|r| r.bulletDeaths).sum(),
+        timedOut: results.iter().filter(|r| r.timedOut).count()
+    };
+}
+
+// ---------------------------------
+//  出力
+// ---------------------------------
+fn toCsv(results: &[GameResult]) -> String {
+    let mut text = String::from("seed,frames,seconds,score,wave,shots,destroyed,asteroid_deaths,bullet_deaths,timed_out\n");
+    for r in results {
+        text.push_str(&format!("{},{},{:.2},{},{},{},{},{},{},{}\n",
+                               r.seed, r.frames, (r.frames as f64) / FPS, r.score, r.wave, r.shots, r.destroyed,
+                               r.asteroidDeaths, r.bulletDeaths, if r.timedOut { 1 } else { 0 }));
+    }
+    return text;
+}
+
+fn toJson(difficulty: Difficulty, pilotKind: PilotKind, summary: &Summary, results: &[GameResult]) -> String {
+    let scores: Vec<String> = summary.scores.iter().map(|&(k, v)| format!("\"{}\": {:.1}", k, v)).collect();
+    let waves: Vec<String> = summary.waves.iter().map(|(w, n)| format!("\"{}\": {}", w, n)).collect();
+    let games: Vec<String> = results.iter().map(|r| {
+        format!("    {{\"seed\": {}, \"frames\": {}, \"score\": {}, \"wave\": {}, \"shots\": {}, \"destroyed\": {}, \"asteroid_deaths\": {}, \"bullet_deaths\": {}, \"timed_out\": {}}}",
+                r.seed, r.frames, r.score, r.wave, r.shots, r.destroyed, r.asteroidDeaths, r.bulletDeaths, r.timedOut)
+    }).collect();
+
+    let mut text = String::from("{\n");
+    text.push_str(&format!("  \"difficulty\": \"{}\",\n", difficulty.getName()));
+    text.push_str(&format!("  \"pilot\": \"{}\",\n", pilotKind.getName()));
+    text.push_str("  \"summary\": {\n");
+    text.push_str(&format!("    \"games\": {},\n", summary.games));
+    text.push_str(&format!("    \"mean_survival_seconds\": {:.2},\n", summary.meanSeconds));
+    text.push_str(&format!("    \"mean_ship_lifetime_seconds\": {},\n", summary.meanShipSeconds.map_or("null".to_string(), |s| format!("{:.2}", s))));
+    text.push_str(&format!("    \"score\": {{{}}},\n", scores.join(", ")));
+    text.push_str(&format!("    \"waves_reached\": {{{}}},\n", waves.join(", ")));
+    text.push_str(&format!("    \"death_causes\": {{\"asteroid\": {}, \"bullet\": {}}},\n", summary.asteroidDeaths, summary.bulletDeaths));
+    text.push_str(&format!("    \"timed_out\": {}\n", summary.timedOut));
+    text.push_str("  },\n");
+    text.push_str(&format!("  \"games\": [\n{}\n  ]\n", games.join(",\n")));
+    text.push_str("}\n");
+    return text;
+}
+
+// 集計を読みやすい形で標準エラーに出す（CSVを標準出力に出していても見えるように）
+fn printSummary(summary: &Summary) {
+    eprintln!("games: {}", summary.games);
+    match summary.meanShipSeconds {
+        Some(s) => eprintln!("mean survival: {:.1} s  (mean ship lifetime {:.1} s)", summary.meanSeconds, s),
+        None => eprintln!("mean survival: {:.1} s  (no ships lost)", summary.meanSeconds)
+    }
+    let scores: Vec<String> = summary.scores.iter().map(|&(k, v)| format!("{} {:.0}", k, v)).collect();
+    eprintln!("score: {}", scores.join("  "));
+    let waves: Vec<String> = summary.waves.iter().map(|(w, n)| format!("{}: {}", w, n)).collect();
+    eprintln!("waves reached: {}", waves.join("  "));
+    eprintln!("ships lost: asteroid {}  bullet {}", summary.asteroidDeaths, summary.bulletDeaths);
+    if summary.timedOut > 0 {
+        eprintln!("{} games hit --max-frames before game over", summary.timedOut);
+    }
+}
+
+fn usage() {
+    eprintln!("usage: asteroid-sim [options]");
+    eprintln!("  --games N           number of games (default 1000)");
+    eprintln!("  --seed S            seed of the first game, game i uses S+i (default 1)");
+    eprintln!("  --difficulty D      easy / normal / hard (default normal)");
+    eprintln!("  --tuning FILE       tuning file (default {})", TUNING_FILE);
+    eprintln!("  --pilot P           ai / spin / idle (default ai)");
+    eprintln!("  --max-frames N      stop a game after N frames (default 36000 = 10 minutes)");
+    eprintln!("  --format F          csv / json (default csv)");
+    eprintln!("  --out FILE          write to FILE instead of standard output");
+    eprintln!("");
+    eprintln!("The ai pilot is the title screen demo pilot, not a model of a human player.");
+    eprintln!("It sees every asteroid, reacts in the same frame and raises the shield just");
+    eprintln!("before each hit, so on easy and normal it rarely loses a ship and most games");
+    eprintln!("run until --max-frames. Compare score and waves reached between tunings");
+    eprintln!("rather than survival time. Every game is single player.");
+}
+
+// ---------------------------------
+//  メインルーチン
+// ---------------------------------
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let mut nGames: u64 = 1000;
+    let mut firstSeed: u64 = 1;
+    let mut difficulty = Difficulty::NORMAL;
+    let mut tuningFile = TUNING_FILE.to_string();
+    let mut pilotKind = PilotKind::AI;
+    let mut maxFrames: u32 = 60 * 60 * 10;
+    let mut format = OutputFormat::CSV;
+    let mut outFile: Option<String> = None;
+
+    let mut i = 1;
+    while i < args.len() {
+        if args[i] == "--help" {
+            usage();
+            return;
+        }
+        let next = args.get(i+1).cloned();
+        let ok = match args[i].as_str() {
+            "--games" => next.and_then(|s| s.parse().ok()).map(|n| nGames = n).is_some(),
+            "--seed" => next.and_then(|s| s.parse().ok()).map(|s| firstSeed = s).is_some(),
+            "--difficulty" => next.and_then(|s| Difficulty::fromName(&s)).map(|d| difficulty = d).is_some(),
+            "--tuning" => next.map(|s| tuningFile = s).is_some(),
+            "--pilot" => next.and_then(|s| PilotKind::fromName(&s)).map(|p| pilotKind = p).is_some(),
+            "--max-frames" => next.and_then(|s| s.parse().ok()).map(|n| maxFrames = n).is_some(),
+            "--format" => match next.as_ref().map(|s| s.as_str()) {
+                Some("csv") => { format = OutputFormat::CSV; true }
+                Some("json") => { format = OutputFormat::JSON; true }
+                _ => false
+            },
+            "--out" => next.map(|s| outFile = Some(s)).is_some(),
+            _ => false
+        };
+        if !ok {
+            eprintln!("bad option: {} {}", args[i], args.get(i+1).map_or("", |s| s.as_str()));
+            usage();
+            std::process::exit(2);
+        }
+        i += 2;
+    }
+
+    // ゲーム本体と同じく、難易度のプリセット（宇宙船と小惑星の個数も）をtuningファイルから読む
+    // 間違いのあるtuningファイルで集計しても意味がないので、組み込みのプリセットにはせずに止める
+    let tunings = match TuningTable::load(&tuningFile) {
+        Ok(tunings) => tunings,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    let mut settings = Settings::new();
+    settings.setDifficulty(difficulty, &tunings);
+
+    let mut results = Vec::new();
+    for n in 0..nGames {
+        results.push(runGame(&settings, pilotKind, firstSeed + n, maxFrames));
+    }
+
+    let summary = summarize(&results);
+    printSummary(&summary);
+
+    let text = match format {
+        OutputFormat::CSV => toCsv(&results),
+        OutputFormat::JSON => toJson(difficulty, pilotKind, &summary, &results)
+    };
+    match outFile {
+        Some(path) => {
+            if let Err(e) = fs::write(&path, text) {
+                eprintln!("failed to write {}: {}", path, e);
+                std::process::exit(1);
+            }
+            eprintln!("wrote {}", path);
+        }
+        None => print!("{}", text)
+    }
+}
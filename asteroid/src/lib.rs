@@ -2,9 +2,41 @@
 // （各modのソース内には書かない）
 extern crate sdl2;
 extern crate rand;
+extern crate capture;
 
 pub mod Game;
 pub mod MyShip;
 pub mod Bullet;
 pub mod Asteroid;
+pub mod AsteroidShape;
+pub mod Physics;
+pub mod Fragment;
+pub mod Tuning;
 pub mod Util;
+pub mod Camera;
+pub mod Draw;
+pub mod VectorFx;
+pub use capture::{Screenshot, Gif, Recorder};  // 画面の保存と録画は他のサンプルと共通
+pub mod DebugOverlay;
+pub mod Input;
+pub mod Player;
+pub mod Random;
+pub mod Net;
+pub mod AiPilot;
+pub mod Env;
+pub mod HighScore;
+pub mod SaveGame;
+pub mod Stats;
+pub mod Lang;
+pub mod Theme;
+pub mod Config;
+pub mod Menu;
+pub mod Scene;
+pub mod TitleScene;
+pub mod PlayScene;
+pub mod PauseScene;
+pub mod HighScoreScene;
+pub mod SettingsScene;
+pub mod StatsScene;
+pub mod NetLobbyScene;
+pub mod NetPlayScene;
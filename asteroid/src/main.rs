@@ -9,25 +9,152 @@ extern crate sdl2;
 extern crate rand;
 
 use sdl2::render::TextureCreator;
+use sdl2::ttf::Font;
 use sdl2::event::Event;
 use sdl2::keyboard::Keycode;
+use sdl2::video::FullscreenType;
 use std::time::Duration;
+use std::env;
 
 // このアプリ自身のクレート(lib.rsで公開しているもの)を使う．
 // クレート名はCargo.tomlの[package]のnameで指定したものになる．
 extern crate asteroid;
 
+use asteroid::Scene;
+use asteroid::TitleScene;
+use asteroid::NetLobbyScene;
+use asteroid::Net;
+use asteroid::Draw;
+use asteroid::Draw::CanvasDraw;
+use asteroid::Screenshot;
+use asteroid::Recorder::{Recorder, RecordFormat, startRecording, stopRecording};
 use asteroid::Game;
+use asteroid::Game::PlayMode;
+use asteroid::AiPilot::AiPilot;
+use asteroid::Random::Random;
+use asteroid::DebugOverlay::DebugOverlay;
+use asteroid::VectorFx::VectorFx;
+
+// ウィンドウを出さずに自動操縦のデモプレイを録画する
+fn recordHeadless(ctx: &Scene::SceneContext, font: &Font, seconds: u32, format: RecordFormat, fps: u32) {
+    let width = ctx.width;
+    let height = ctx.height;
+    let mut canvas = Draw::newSoftwareCanvas(width, height).expect("failed to create software canvas");
+    let texture_creator = canvas.texture_creator();
+
+    let mut game = Game::Game::new(&ctx.settings, PlayMode::SINGLE, Random::seedFromTime());
+    let mut pilot = AiPilot::new();
+    let mut recorder = match startRecording(format, fps) {
+        Some(recorder) => recorder,
+        None => { return; }
+    };
+
+    // 設定でベクター風の描画を選んでいれば録画にもかける
+    let mut fx = if ctx.settings.vectorFx {
+        VectorFx::new(&texture_creator, width, height).map_err(|e| println!("vector effects unavailable: {}", e)).ok()
+    } else {
+        None
+    };
+
+    for _ in 0..seconds*60 {
+        let input = pilot.think(&game, 0, width, height);
+        game.setInput(0, input);
+        game.update(width, height);
+        let drawn = match fx {
+            Some(ref mut fx) => fx.render(&mut canvas, &texture_creator, Some(font), |draw| game.draw(draw, width, height)).is_ok(),
+            None => false
+        };
+        if !drawn {
+            let mut draw = CanvasDraw::new(&mut canvas, &texture_creator, Some(font));
+            game.draw(&mut draw, width, height);
+        }
+        if let Err(e) = recorder.capture(&canvas) {
+            println!("recording failed: {}", e);
+            break;
+        }
+        if game.isOver() {
+            break;
+        }
+    }
+    stopRecording(recorder);
+}
 
 // ---------------------------------
 //  メインルーチン
 // ---------------------------------
 fn main() {
     let sdl2_context = sdl2::init().unwrap();
-    let video_subsystem = sdl2_context.video().unwrap();
 
     let width: u32 = 1280;
     let height: u32 = 900;
+
+    // init font stuff
+    let ttf_context = sdl2::ttf::init().expect("failed to init SDL TTF");
+    let font = ttf_context.load_font("assets/arial.ttf", 128).expect("failed to load font");
+
+    // コマンドライン引数
+    //   --host [PORT]   ネットワーク対戦のホストとして起動
+    //   --join ADDR     ネットワーク対戦に参加 (例: 127.0.0.1:7777)
+    //   --latency MS    疑似遅延（1台でのテスト用）
+    //   --loss PERCENT  疑似パケットロス率（1台でのテスト用）
+    //   --record-fps N      録画のフレームレート（デフォルト15）
+    //   --record-format F   録画の形式 gif / png（デフォルトgif）
+    //   --headless SECONDS  ウィンドウを出さずにデモプレイを録画して終了
+    let args: Vec<String> = env::args().collect();
+    let mut ctx = Scene::SceneContext::new(width, height);
+    let mut hostPort: Option<u16> = None;
+    let mut joinAddr: Option<String> = None;
+    let mut recordFps: u32 = 15;
+    let mut recordFormat = RecordFormat::GIF;
+    let mut headless: Option<u32> = None;
+    let mut i = 1;
+    while i < args.len() {
+        let next = args.get(i+1).cloned();
+        match args[i].as_str() {
+            "--host" => {
+                hostPort = Some(Net::DEFAULT_PORT);
+                if let Some(port) = next.and_then(|s| s.parse::<u16>().ok()) {
+                    hostPort = Some(port);
+                    i += 1;
+                }
+            }
+            "--join" => {
+                joinAddr = next;
+                i += 1;
+            }
+            "--latency" => {
+                ctx.settings.netLatencyMs = next.and_then(|s| s.parse().ok()).unwrap_or(0);
+                i += 1;
+            }
+            "--loss" => {
+                ctx.settings.netLossPercent = next.and_then(|s| s.parse().ok()).unwrap_or(0);
+                i += 1;
+            }
+            "--record-fps" => {
+                recordFps = next.and_then(|s| s.parse().ok()).unwrap_or(15);
+                i += 1;
+            }
+            "--record-format" => {
+                recordFormat = next.as_ref().and_then(|s| RecordFormat::fromName(s)).unwrap_or(RecordFormat::GIF);
+                i += 1;
+            }
+            "--headless" => {
+                headless = Some(next.and_then(|s| s.parse().ok()).unwrap_or(10));
+                i += 1;
+            }
+            arg => {
+                println!("unknown option: {}", arg);
+            }
+        }
+        i += 1;
+    }
+
+    if let Some(seconds) = headless {
+        recordHeadless(&ctx, &font, seconds, recordFormat, recordFps);
+        return;
+    }
+
+    let video_subsystem = sdl2_context.video().unwrap();
     let window = video_subsystem
         .window("Asteroid", width, height)
         .position_centered()
@@ -35,57 +162,136 @@ fn main() {
         .unwrap();
 
     let mut canvas = window.into_canvas().build().unwrap();
-
-    // init font stuff
-    let ttf_context = sdl2::ttf::init().expect("failed to init SDL TTF");
-    let font = ttf_context.load_font("assets/arial.ttf", 128).expect("failed to load font");
+    // フルスクリーンでも1280x900の画面として描く（マウス座標もこの大きさに合わせてくれる）
+    canvas.set_logical_size(width, height).expect("failed to set logical size");
     let texture_creator: TextureCreator<_> = canvas.texture_creator();
 
-    let mut game = Game::Game::new();
+    // 最初はタイトル画面（ネットワーク対戦の指定があればロビー）
+    let first: Box<dyn Scene::Scene> = if let Some(port) = hostPort {
+        Box::new(NetLobbyScene::NetLobbyScene::newHost(&ctx, port))
+    } else if let Some(addr) = joinAddr {
+        Box::new(NetLobbyScene::NetLobbyScene::newJoin(&ctx, &addr))
+    } else {
+        Box::new(TitleScene::TitleScene::new())
+    };
+    let mut scenes = Scene::SceneStack::new(first, ctx);
+
+    let mut screenshot = false;     // F12でスクリーンショット（描画し終わってから保存する）
+    let mut recorder: Option<Recorder> = None;  // F10で録画開始/終了
+    let mut overlay = DebugOverlay::new();      // F3でデバッグ表示
+    let mut fullscreen = false;     // ウィンドウに反映したフルスクリーン設定
+    let mut fx: Option<VectorFx> = None;    // ベクター風の描画（設定でオンのときだけ作る）
+    let mut fxFailed = false;       // 作れなかった、または描けなかったらtrue（以後は普通に描く）
 
     let mut event_pump = sdl2_context.event_pump().unwrap();
     'running: loop {
         for event in event_pump.poll_iter() {
             match event {
-                Event::Quit { .. }
-                | Event::KeyDown {
-                    keycode: Some(Keycode::Up), ..
-                } => {
-                    game.upPressed();
+                Event::Quit { .. } => {
+                    scenes.shutdown();
+                    break 'running;
                 }
-                | Event::KeyDown {
-                    keycode: Some(Keycode::Left), ..
-                } => {
-                    game.leftPressed();
+                Event::KeyDown { keycode: Some(Keycode::F12), .. } => {
+                    screenshot = true;
                 }
-                | Event::KeyDown {
-                    keycode: Some(Keycode::Right), ..
-                } => {
-                    game.rightPressed();
+                Event::KeyDown { keycode: Some(Keycode::F3), .. } => {
+                    overlay.toggle();
                 }
-                | Event::KeyDown {
-                    keycode: Some(Keycode::Space), ..
-                } => {
-                    game.spacePressed();
+                Event::KeyDown { keycode: Some(Keycode::F10), .. } => {
+                    recorder = match recorder.take() {
+                        Some(r) => {
+                            stopRecording(r);
+                            None
+                        }
+                        None => startRecording(recordFormat, recordFps)
+                    };
                 }
-                | Event::KeyDown {
-                    keycode: Some(Keycode::Return), ..
-                } => {
-                    game.enterPressed();
+                _ => {
+                    // キー入力は一番上のシーンが処理する
+                    scenes.handleInput(&event);
                 }
-                | Event::KeyDown {
-                    keycode: Some(Keycode::Escape), ..
-                } => break 'running,
-                _ => {}
             }
         }
 
+        overlay.frame();
+
+        // 設定画面でウィンドウモードが変わったら（起動時は前回の設定を）反映する
+        let wantFullscreen = scenes.getContext().settings.fullscreen;
+        if wantFullscreen != fullscreen {
+            let mode = if wantFullscreen { FullscreenType::Desktop } else { FullscreenType::Off };
+            if let Err(e) = canvas.window_mut().set_fullscreen(mode) {
+                println!("cannot change window mode: {}", e);
+            }
+            fullscreen = wantFullscreen;
+        }
+
         // update scene
-        game.update(&mut canvas, &font, &texture_creator, width, height);
+        scenes.update();
+        if scenes.isFinished() {
+            break 'running;
+        }
+        // ベクター風の描画を設定に合わせて用意する（オフにしたら捨てて、次にオンにしたときは残光なしから）
+        if scenes.getContext().settings.vectorFx && !fxFailed {
+            if fx.is_none() {
+                match VectorFx::new(&texture_creator, width, height) {
+                    Ok(f) => fx = Some(f),
+                    Err(e) => {
+                        println!("vector effects unavailable: {}", e);
+                        fxFailed = true;
+                    }
+                }
+            }
+        } else {
+            fx = None;
+        }
+
+        let mut drawn = false;
+        if let Some(ref mut f) = fx {
+            match f.render(&mut canvas, &texture_creator, Some(&font), |draw| scenes.render(draw)) {
+                Ok(()) => drawn = true,
+                Err(e) => {
+                    println!("vector effects failed: {}", e);
+                    fxFailed = true;
+                }
+            }
+        }
+        {
+            let mut draw = CanvasDraw::new(&mut canvas, &texture_creator, Some(&font));
+            if !drawn {
+                scenes.render(&mut draw);
+            }
+            // デバッグ表示には効果をかけない
+            overlay.draw(&mut draw, scenes.getGame(), width, height);
+        }
+
+        if screenshot {
+            match Screenshot::saveScreenshot(&canvas) {
+                Ok(path) => println!("saved {}", path),
+                Err(e) => println!("screenshot failed: {}", e)
+            }
+            screenshot = false;
+        }
+
+        // 失敗したら録画をやめる（それまでの分は閉じて保存する）
+        let mut failed = false;
+        if let Some(ref mut r) = recorder {
+            if let Err(e) = r.capture(&canvas) {
+                println!("recording failed: {}", e);
+                failed = true;
+            }
+        }
+        if failed {
+            if let Some(r) = recorder.take() {
+                stopRecording(r);
+            }
+        }
 
         // show backbuffer
         canvas.present();
 
         ::std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 60));
     }
+    if let Some(r) = recorder {
+        stopRecording(r);
+    }
 }
@@ -0,0 +1,84 @@
+// *****************************************************************
+//  小惑星の形のテスト
+//  どの種類でも、頂点が角度の順に並び、輪郭が自己交差しないことを確かめる
+// *****************************************************************
+#![allow(non_snake_case)]
+extern crate asteroid;
+
+use std::f64::consts::PI;
+
+use asteroid::AsteroidShape::{AsteroidShape, ARCHETYPES};
+use asteroid::Random::Random;
+
+const SHAPES: usize = 500;  // 種類ごとに作る形の数
+
+// 線分abと線分cdが交わるか（端を共有する隣どうしの辺は呼ばない）
+fn intersects(a: (f64, f64), b: (f64, f64), c: (f64, f64), d: (f64, f64)) -> bool {
+    let cross = |o: (f64, f64), p: (f64, f64), q: (f64, f64)| (p.0 - o.0) * (q.1 - o.1) - (p.1 - o.1) * (q.0 - o.0);
+    let d1 = cross(c, d, a);
+    let d2 = cross(c, d, b);
+    let d3 = cross(a, b, c);
+    let d4 = cross(a, b, d);
+    return d1 * d2 < 0.0 && d3 * d4 < 0.0;
+}
+
+// 頂点iから次の頂点までの、中心から見た角度の増え方（0から2πまで）
+fn turn(shape: &Vec<(f64, f64)>, i: usize) -> f64 {
+    let (x0, y0) = shape[i];
+    let (x1, y1) = shape[(i + 1) % shape.len()];
+    let d = y1.atan2(x1) - y0.atan2(x0);
+    return if d < 0.0 { d + PI * 2.0 } else { d };
+}
+
+#[test]
+fn vertices_go_around_in_angle_order() {
+    let mut rng = Random::new(1);
+    for kind in ARCHETYPES.iter() {
+        for _ in 0..SHAPES {
+            let shape = AsteroidShape::archetype(*kind, 30.0, &mut rng).generate(&mut rng);
+            // どの頂点も前の頂点より先にあり、ちょうど1周で最初に戻る
+            let mut total = 0.0;
+            for i in 0..shape.len() {
+                let d = turn(&shape, i);
+                assert!(d > 0.0 && d < PI, "{:?}: vertex {} goes back in {:?}", kind, i, shape);
+                total += d;
+            }
+            assert!((total - PI * 2.0).abs() < 1e-9, "{:?}: outline turns {} rad in {:?}", kind, total, shape);
+        }
+    }
+}
+
+#[test]
+fn outlines_do_not_intersect_themselves() {
+    let mut rng = Random::new(2);
+    for kind in ARCHETYPES.iter() {
+        for _ in 0..SHAPES {
+            let shape = AsteroidShape::archetype(*kind, 30.0, &mut rng).generate(&mut rng);
+            let n = shape.len();
+            for i in 0..n {
+                for j in (i+2)..n {
+                    if i == 0 && j == n - 1 {
+                        continue;   // 最後の辺と最初の辺は隣どうし
+                    }
+                    let (a, b) = (shape[i], shape[(i+1) % n]);
+                    let (c, d) = (shape[j], shape[(j+1) % n]);
+                    assert!(!intersects(a, b, c, d), "{:?}: edges {} and {} cross in {:?}", kind, i, j, shape);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn vertex_count_stays_in_the_archetype_range() {
+    let mut rng = Random::new(3);
+    for kind in ARCHETYPES.iter() {
+        let (min, max) = AsteroidShape::vertexRange(*kind);
+        for radius in &[4.0, 12.0, 30.0, 60.0] {
+            for _ in 0..SHAPES / 10 {
+                let n = AsteroidShape::archetype(*kind, *radius, &mut rng).generate(&mut rng).len();
+                assert!(n >= min && n <= max, "{:?} with radius {} has {} vertices", kind, radius, n);
+            }
+        }
+    }
+}
@@ -0,0 +1,75 @@
+// *****************************************************************
+//  小惑星どうしの衝突のテスト
+//  固まって置いた小惑星が、1回の衝突処理で重ならなくなることを確かめる
+// *****************************************************************
+#![allow(non_snake_case)]
+extern crate asteroid;
+
+use asteroid::Asteroid::Asteroid;
+use asteroid::Physics;
+use asteroid::Util::{deg2rad, wrapDelta};
+
+const WIDTH: u32 = 1280;    // ゲーム本体と同じ画面の大きさ
+const HEIGHT: u32 = 900;
+const VERTICES: usize = 12;
+
+// 回転しない正多角形の小惑星
+fn roundAsteroid(x: f64, y: f64, r: f64) -> Asteroid {
+    let shape = (0..VERTICES).map(|i| {
+        let theta = deg2rad(360.0 * (i as f64) / (VERTICES as f64));
+        return (r * theta.cos(), r * theta.sin());
+    }).collect();
+    return Asteroid::fromOutline(shape, (r / 2.0) as i32, x, y, 0.0, 0.0, 0.0);
+}
+
+// (cx, cy)のまわりに格子状に詰めて置く（隣どうしは半径より近いので全部重なる）
+fn cluster(cx: f64, cy: f64) -> Vec<Asteroid> {
+    let mut asteroids = Vec::new();
+    for i in 0..6 {
+        for j in 0..6 {
+            let r = 12.0 + ((i * 6 + j) % 5) as f64 * 4.0;
+            let x = cx + (i as f64 - 2.5) * 9.0;
+            let y = cy + (j as f64 - 2.5) * 9.0;
+            asteroids.push(roundAsteroid(x.rem_euclid(WIDTH as f64), y.rem_euclid(HEIGHT as f64), r));
+        }
+    }
+    return asteroids;
+}
+
+// 重なっている組（画面の端をまたぐ距離も近い方で測る）
+fn overlaps(asteroids: &Vec<Asteroid>) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    for i in 0..asteroids.len() {
+        for j in (i+1)..asteroids.len() {
+            let (a, b) = (&asteroids[i], &asteroids[j]);
+            let dx = wrapDelta(b.getX() - a.getX(), WIDTH as f64);
+            let dy = wrapDelta(b.getY() - a.getY(), HEIGHT as f64);
+            if (dx*dx + dy*dy).sqrt() < a.getRadius() + b.getRadius() {
+                pairs.push((i, j));
+            }
+        }
+    }
+    return pairs;
+}
+
+fn assertSeparated(cx: f64, cy: f64) {
+    let mut asteroids = cluster(cx, cy);
+    assert!(!overlaps(&asteroids).is_empty());
+    Physics::collideAsteroids(&mut asteroids, 0.8, WIDTH, HEIGHT);
+    assert_eq!(overlaps(&asteroids), vec![], "asteroids dropped at ({}, {}) still overlap", cx, cy);
+    for a in &asteroids {
+        assert!(a.getX() >= 0.0 && a.getX() < WIDTH as f64 && a.getY() >= 0.0 && a.getY() < HEIGHT as f64);
+    }
+}
+
+#[test]
+fn cluster_in_the_middle_is_separated() {
+    assertSeparated(640.0, 450.0);
+}
+
+#[test]
+fn cluster_across_the_wrap_seam_is_separated() {
+    assertSeparated(0.0, 450.0);
+    assertSeparated(640.0, 0.0);
+    assertSeparated(0.0, 0.0);
+}
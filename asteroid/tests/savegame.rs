@@ -0,0 +1,118 @@
+// *****************************************************************
+//  保存と再開のテスト
+//  途中で保存して読み直したゲームが元のゲームと同じように進むことと、範囲外の値を読まないことを確かめる
+// *****************************************************************
+#![allow(non_snake_case)]
+extern crate asteroid;
+
+use std::env;
+use std::fs;
+use std::process;
+
+use asteroid::Game::{Game, PlayMode};
+use asteroid::Asteroid::Asteroid;
+use asteroid::Input::PlayerInput;
+use asteroid::Scene::Settings;
+use asteroid::SaveGame;
+use asteroid::Util::deg2rad;
+
+const WIDTH: u32 = 1280;    // ゲーム本体と同じ画面の大きさ
+const HEIGHT: u32 = 900;
+
+// テストごとに別の一時ファイル
+fn tempPath(name: &str) -> String {
+    let path = env::temp_dir().join(format!("asteroid-{}-{}.txt", name, process::id()));
+    return path.to_string_lossy().into_owned();
+}
+
+// 保存して読み直す
+fn roundTrip(game: &Game, name: &str) -> Game {
+    let path = tempPath(name);
+    SaveGame::saveGame(game, &path).unwrap();
+    let loaded = SaveGame::loadGame(&path);
+    SaveGame::remove(&path);
+    return loaded.unwrap_or_else(|e| panic!("cannot load the saved game: {}", e));
+}
+
+// tick番目のフレームの操作（回りながら撃ち続ける）
+fn inputAt(tick: u32) -> PlayerInput {
+    let mut input = PlayerInput::default();
+    input.left = tick % 90 < 30;
+    input.up = tick % 50 < 10;
+    input.fire = tick % 6 == 0;
+    return input;
+}
+
+#[test]
+fn loaded_game_plays_the_same_as_the_original() {
+    // 弾が飛んでいるところで保存する
+    let mut game = Game::new(&Settings::new(), PlayMode::SINGLE, 7);
+    let mut tick = 0;
+    while tick < 300 || game.getBullets().is_empty() {
+        assert!(tick < 3600, "no bullets in flight to save");
+        game.setInput(0, inputAt(tick));
+        game.update(WIDTH, HEIGHT);
+        tick += 1;
+    }
+
+    let mut loaded = roundTrip(&game, "replay");
+    assert_eq!(loaded.checksum(), game.checksum());
+    for tick in tick..tick+600 {
+        game.setInput(0, inputAt(tick));
+        game.update(WIDTH, HEIGHT);
+        loaded.setInput(0, inputAt(tick));
+        loaded.update(WIDTH, HEIGHT);
+        assert_eq!(loaded.checksum(), game.checksum(), "games differ at tick {}", tick);
+    }
+    assert_eq!(loaded.getScores(), game.getScores());
+    assert_eq!(loaded.getWave(), game.getWave());
+}
+
+#[test]
+fn invalid_asteroids_are_not_saved() {
+    let mut game = Game::new(&Settings::new(), PlayMode::SINGLE, 1);
+    game.update(WIDTH, HEIGHT);
+
+    // 読み込みの上限（10000個）を超える数の壊れた小惑星と、1個の生きている小惑星
+    let square = || (0..4).map(|i| {
+        let theta = deg2rad(90.0 * (i as f64));
+        return (20.0 * theta.cos(), 20.0 * theta.sin());
+    }).collect();
+    let mut asteroids: Vec<Asteroid> = (0..10001).map(|_| {
+        let mut asteroid = Asteroid::fromOutline(square(), 10, 100.0, 100.0, 0.0, 0.0, 0.0);
+        asteroid.setValid(false);
+        return asteroid;
+    }).collect();
+    asteroids.push(Asteroid::fromOutline(square(), 10, 300.0, 300.0, 1.0, 0.0, 0.0));
+    game.setAsteroids(asteroids);
+
+    let loaded = roundTrip(&game, "invalid");
+    assert_eq!(loaded.getAsteroids().len(), 1);
+    assert!(loaded.getAsteroids()[0].getValid());
+    assert!(loaded.getBullets().iter().all(|b| b.getValid()));
+}
+
+#[test]
+fn out_of_range_tuning_is_rejected() {
+    let game = Game::new(&Settings::new(), PlayMode::SINGLE, 1);
+    let path = tempPath("tuning");
+    SaveGame::saveGame(&game, &path).unwrap();
+
+    // 宇宙船の数（5番目の値）を0にする（tuning.tomlでは1から9まで）
+    let text = fs::read_to_string(&path).unwrap();
+    let broken: Vec<String> = text.lines().map(|line| {
+        let mut words: Vec<&str> = line.split_whitespace().collect();
+        if words[0] == "tuning" {
+            words[5] = "0";
+        }
+        return words.join(" ");
+    }).collect();
+    fs::write(&path, broken.join("\n")).unwrap();
+
+    let loaded = SaveGame::loadGame(&path);
+    SaveGame::remove(&path);
+    match loaded {
+        Ok(_) => panic!("a save with 0 ships was loaded"),
+        Err(e) => assert!(e.contains("ships must be between 1 and 9"), "unexpected error: {}", e)
+    }
+}
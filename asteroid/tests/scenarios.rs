@@ -0,0 +1,267 @@
+// *****************************************************************
+//  シナリオテスト
+//  tests/scenarios/*.txt に書いた状態からゲームを始め、決めた操作で1フレームずつ進めて、
+//  決めたフレームのスコアや状態を確かめる（画面は使わない）
+//
+//  シナリオファイルの書き方（1行に1つ、#から後ろはコメント）
+//    seed 1                        ゲームの乱数の種（デフォルト1）
+//    difficulty normal             組み込みのプリセット（tuning.tomlは読まない）
+//    ships 3                       残りの宇宙船の数
+//    ship X Y VX VY ROT            宇宙船（ROTは度、0で右向き）。書かなければ画面の中央で右向き
+//    asteroid X Y VX VY SIZE       小惑星（半径SIZE*2の正12角形、回転しない）。何個でも書ける
+//    bullet X Y VX VY [LIFE]       プレイヤー1の弾（LIFEを省くとプリセットの射程）
+//    input FROM[-TO] KEY...        そのフレームの間押しているキー（up down left right fire shield）
+//    expect TICK WHAT VALUE        TICKフレーム進めた後の値（WHATは下のcheck()を参照）
+//
+//  小惑星を1個も書かなければ、最初のフレームで次の波が始まる
+// *****************************************************************
+#![allow(non_snake_case)]
+extern crate asteroid;
+
+use std::fs;
+use std::path::PathBuf;
+
+use asteroid::Game::{Game, PlayMode};
+use asteroid::Asteroid::Asteroid;
+use asteroid::Bullet::Bullet;
+use asteroid::MyShip::MyShip;
+use asteroid::Input::PlayerInput;
+use asteroid::Scene::Settings;
+use asteroid::Tuning::{TuningTable, Difficulty};
+use asteroid::Util::deg2rad;
+
+const WIDTH: u32 = 1280;    // ゲーム本体と同じ画面の大きさ
+const HEIGHT: u32 = 900;
+const SHIP_RADIUS: f64 = 10.0;
+const ASTEROID_VERTICES: usize = 12;
+
+struct Expect {
+    lineNo: usize,
+    tick: u32,
+    what: String,
+    value: String
+}
+
+struct Scenario {
+    seed: u64,
+    difficulty: Difficulty,
+    ships: Option<i32>,
+    ship: Option<[f64; 5]>,         // x, y, vx, vy, 向き(度)
+    asteroids: Vec<([f64; 4], i32)>,    // (x, y, vx, vy), サイズ
+    bullets: Vec<([f64; 4], Option<i32>)>,  // (x, y, vx, vy), 寿命
+    inputs: Vec<(u32, u32, PlayerInput)>,   // 最初と最後のフレーム、押しているキー
+    expects: Vec<Expect>
+}
+
+// 数をn個読む
+fn numbers(args: &[&str], n: usize, lineNo: usize) -> Result<Vec<f64>, String> {
+    if args.len() != n {
+        return Err(format!("line {}: expected {} numbers (got {})", lineNo, n, args.len()));
+    }
+    return args.iter()
+        .map(|a| a.parse::<f64>().map_err(|_| format!("line {}: '{}' is not a number", lineNo, a)))
+        .collect();
+}
+
+fn parse(text: &str) -> Result<Scenario, String> {
+    let mut scenario = Scenario {
+        seed: 1,
+        difficulty: Difficulty::NORMAL,
+        ships: None,
+        ship: None,
+        asteroids: Vec::new(),
+        bullets: Vec::new(),
+        inputs: Vec::new(),
+        expects: Vec::new()
+    };
+
+    for (n, raw) in text.lines().enumerate() {
+        let lineNo = n + 1;
+        let line = match raw.find('#') {
+            Some(i) => &raw[..i],
+            None => raw
+        };
+        let words: Vec<&str> = line.split_whitespace().collect();
+        if words.is_empty() {
+            continue;
+        }
+        let args = &words[1..];
+        match words[0] {
+            "seed" => {
+                scenario.seed = numbers(args, 1, lineNo)?[0] as u64;
+            }
+            "difficulty" => {
+                scenario.difficulty = match args.first().and_then(|d| Difficulty::fromName(d)) {
+                    Some(d) => d,
+                    None => { return Err(format!("line {}: expected easy, normal or hard", lineNo)); }
+                };
+            }
+            "ships" => {
+                scenario.ships = Some(numbers(args, 1, lineNo)?[0] as i32);
+            }
+            "ship" => {
+                let v = numbers(args, 5, lineNo)?;
+                scenario.ship = Some([v[0], v[1], v[2], v[3], v[4]]);
+            }
+            "asteroid" => {
+                let v = numbers(args, 5, lineNo)?;
+                scenario.asteroids.push(([v[0], v[1], v[2], v[3]], v[4] as i32));
+            }
+            "bullet" => {
+                let v = if args.len() == 5 { numbers(args, 5, lineNo)? } else { numbers(args, 4, lineNo)? };
+                scenario.bullets.push(([v[0], v[1], v[2], v[3]], v.get(4).map(|l| *l as i32)));
+            }
+            "input" => {
+                let range = args.first().ok_or(format!("line {}: expected a tick or range like 5-20", lineNo))?;
+                let (from, to) = match range.find('-') {
+                    Some(i) => (&range[..i], &range[i+1..]),
+                    None => (*range, *range)
+                };
+                let ticks = numbers(&[from, to], 2, lineNo)?;
+                let mut input = PlayerInput::default();
+                for key in &args[1..] {
+                    match *key {
+                        "up" => input.up = true,
+                        "down" => input.down = true,
+                        "left" => input.left = true,
+                        "right" => input.right = true,
+                        "fire" => input.fire = true,
+                        "shield" => input.shield = true,
+                        k => { return Err(format!("line {}: unknown key '{}'", lineNo, k)); }
+                    }
+                }
+                scenario.inputs.push((ticks[0] as u32, ticks[1] as u32, input));
+            }
+            "expect" => {
+                if args.len() != 3 {
+                    return Err(format!("line {}: expected 'expect TICK WHAT VALUE'", lineNo));
+                }
+                scenario.expects.push(Expect {
+                    lineNo: lineNo,
+                    tick: numbers(&args[..1], 1, lineNo)?[0] as u32,
+                    what: args[1].to_string(),
+                    value: args[2].to_string()
+                });
+            }
+            w => {
+                return Err(format!("line {}: unknown command '{}'", lineNo, w));
+            }
+        }
+    }
+    return Ok(scenario);
+}
+
+// 回転しない正多角形の小惑星（半径はAsteroid::new()と同じくサイズの2倍）
+fn roundAsteroid(p: [f64; 4], size: i32) -> Asteroid {
+    let r = (size * 2) as f64;
+    let shape = (0..ASTEROID_VERTICES).map(|i| {
+        let theta = deg2rad(360.0 * (i as f64) / (ASTEROID_VERTICES as f64));
+        return (r * theta.cos(), r * theta.sin());
+    }).collect();
+    return Asteroid::fromOutline(shape, size, p[0], p[1], p[2], p[3], 0.0);
+}
+
+// 今の値（WHATごと）
+fn check(game: &Game, what: &str) -> Option<String> {
+    return match what {
+        "state" => Some(format!("{:?}", game.getState())),
+        "score" => Some(game.getScore(0).to_string()),
+        "ships_left" => Some(game.getShipsLeft(0).to_string()),
+        "wave" => Some(game.getWave().to_string()),
+        "asteroids" => Some(game.getAsteroids().iter().filter(|a| a.getValid()).count().to_string()),
+        "bullets" => Some(game.getBullets().iter().filter(|b| b.getValid()).count().to_string()),
+        "ship" => Some((if game.getShip(0).is_some() { "yes" } else { "no" }).to_string()),
+        _ => None
+    };
+}
+
+// シナリオを最後のexpectまで進めて、合わなかったものを返す
+fn play(scenario: &Scenario) -> Vec<String> {
+    let mut settings = Settings::new();
+    settings.setDifficulty(scenario.difficulty, &TuningTable::new());
+    let mut game = Game::new(&settings, PlayMode::SINGLE, scenario.seed);
+
+    // 最初のupdate()で宇宙船と乱数の小惑星ができるので、それから置き換える
+    game.update(WIDTH, HEIGHT);
+    if let Some(n) = scenario.ships {
+        game.setShipsLeft(0, n);
+    }
+    if let Some(s) = scenario.ship {
+        let mut myShip = MyShip::new(s[0], s[1], SHIP_RADIUS, deg2rad(s[4]), game.getTuning());
+        myShip.setVelocity(s[2], s[3]);
+        game.setShip(0, myShip);
+    }
+    game.setAsteroids(scenario.asteroids.iter().map(|&(p, size)| roundAsteroid(p, size)).collect());
+    let life = game.getTuning().bulletLifetime;
+    game.setBullets(scenario.bullets.iter().map(|&(p, l)| Bullet::new(p[0], p[1], p[2], p[3], l.unwrap_or(life))).collect());
+
+    let mut failures = Vec::new();
+    let last = scenario.expects.iter().map(|e| e.tick).max().unwrap_or(0);
+    for tick in 0..last+1 {
+        if tick > 0 {
+            // 同じフレームに複数のinputがあれば全部のキーを押す
+            let mut input = PlayerInput::default();
+            for &(from, to, ref keys) in &scenario.inputs {
+                if from <= tick && tick <= to {
+                    input = PlayerInput::fromBits(input.toBits() | keys.toBits());
+                }
+            }
+            game.setInput(0, input);
+            game.update(WIDTH, HEIGHT);
+        }
+
+        for e in scenario.expects.iter().filter(|e| e.tick == tick) {
+            match check(&game, &e.what) {
+                Some(ref actual) if *actual == e.value => {}
+                Some(actual) => failures.push(format!("line {}: tick {}: expected {} {} but got {}", e.lineNo, tick, e.what, e.value, actual)),
+                None => failures.push(format!("line {}: unknown value '{}'", e.lineNo, e.what))
+            }
+        }
+    }
+    return failures;
+}
+
+fn run(name: &str) {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "scenarios", name].iter().collect();
+    let text = fs::read_to_string(&path).unwrap_or_else(|e| panic!("cannot read {}: {}", path.display(), e));
+    let scenario = parse(&text).unwrap_or_else(|e| panic!("{}: {}", name, e));
+    let failures = play(&scenario);
+    if !failures.is_empty() {
+        panic!("{}:\n  {}", name, failures.join("\n  "));
+    }
+}
+
+#[test]
+fn bulletDestroysAsteroid() {
+    run("bullet_destroys_asteroid.txt");
+}
+
+#[test]
+fn bigAsteroidSplits() {
+    run("big_asteroid_splits.txt");
+}
+
+#[test]
+fn bulletExpires() {
+    run("bullet_expires.txt");
+}
+
+#[test]
+fn fireCooldown() {
+    run("fire_cooldown.txt");
+}
+
+#[test]
+fn shipHitByAsteroid() {
+    run("ship_hit_by_asteroid.txt");
+}
+
+#[test]
+fn lastShipGameOver() {
+    run("last_ship_game_over.txt");
+}
+
+#[test]
+fn shieldBouncesSmallAsteroid() {
+    run("shield_bounces_small_asteroid.txt");
+}
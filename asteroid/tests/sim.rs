@@ -0,0 +1,64 @@
+// *****************************************************************
+//  asteroid-simのテスト
+//  同じ--seedなら同じ集計になることと、難易度を変えると集計が変わることを確かめる
+//  （tuningファイルは tests/sim/builtin.toml で、組み込みのプリセットを使う）
+// *****************************************************************
+#![allow(non_snake_case)]
+
+use std::process::Command;
+use std::path::PathBuf;
+
+const GAMES: &str = "10";
+const MAX_FRAMES: &str = "3600";   // 1分で打ち切る（自動操縦はなかなか死なないので）
+
+// シミュレーターをJSONで走らせて、"summary"の中身を返す
+fn summary(pilot: &str, difficulty: &str, seed: u64) -> String {
+    let tuning: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "sim", "builtin.toml"].iter().collect();
+    let output = Command::new(env!("CARGO_BIN_EXE_asteroid-sim"))
+        .args(&["--games", GAMES, "--max-frames", MAX_FRAMES, "--format", "json"])
+        .args(&["--seed", &seed.to_string(), "--pilot", pilot, "--difficulty", difficulty])
+        .arg("--tuning").arg(&tuning)
+        .output()
+        .expect("cannot run asteroid-sim");
+    assert!(output.status.success(), "asteroid-sim failed: {}", String::from_utf8_lossy(&output.stderr));
+
+    let text = String::from_utf8(output.stdout).unwrap();
+    let start = text.find("\"summary\": {").expect("no summary in the JSON output");
+    let end = start + text[start..].find("\n  }").expect("summary is not closed");
+    return text[start..end].to_string();
+}
+
+// 集計の中の数値（"name": 12.34）
+fn value(summary: &str, name: &str) -> f64 {
+    let key = format!("\"{}\": ", name);
+    let start = summary.find(&key).unwrap_or_else(|| panic!("no {} in {}", name, summary)) + key.len();
+    let rest = &summary[start..];
+    let end = rest.find(|c: char| c == ',' || c == '\n').unwrap_or(rest.len());
+    return rest[..end].parse().unwrap_or_else(|_| panic!("{} is not a number in {}", name, summary));
+}
+
+#[test]
+fn sameSeedSameSummary() {
+    for difficulty in &["easy", "normal", "hard"] {
+        assert_eq!(summary("ai", difficulty, 1), summary("ai", difficulty, 1), "difficulty {}", difficulty);
+    }
+}
+
+#[test]
+fn difficultiesChangeSummary() {
+    let easy = summary("ai", "easy", 1);
+    let normal = summary("ai", "normal", 1);
+    let hard = summary("ai", "hard", 1);
+    assert_ne!(easy, normal);
+    assert_ne!(normal, hard);
+    assert_ne!(easy, hard);
+}
+
+#[test]
+fn harderDifficultyShorterSurvival() {
+    // 何もしない宇宙船は小惑星が多く、宇宙船が少ないほど早くゲームオーバーになる
+    let easy = value(&summary("idle", "easy", 1), "mean_survival_seconds");
+    let normal = value(&summary("idle", "normal", 1), "mean_survival_seconds");
+    let hard = value(&summary("idle", "hard", 1), "mean_survival_seconds");
+    assert!(easy > normal && normal > hard, "easy {} normal {} hard {}", easy, normal, hard);
+}
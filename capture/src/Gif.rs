@@ -0,0 +1,204 @@
+#![allow(non_snake_case)]
+
+use std::fs::File;
+use std::io::{Write, BufWriter};
+use std::collections::HashMap;
+
+use Screenshot::Frame;
+
+const MAX_CODES: u16 = 4096;    // LZWの最大コード数（12ビット）
+
+// ---------------------------------
+//  アニメーションGIFの書き出し
+//  フレームごとに256色のパレットを作る
+//  ベクター画面は色数が少ないのでほとんどの場合は減色なしで収まる
+// ---------------------------------
+pub struct GifWriter {
+    out: BufWriter<File>,
+    width: u32,
+    height: u32
+}
+
+impl GifWriter {
+    pub fn create(path: &str, width: u32, height: u32) -> Result<GifWriter, String> {
+        let file = File::create(path).map_err(|e| e.to_string())?;
+        let mut gif = GifWriter {
+            out: BufWriter::new(file),
+            width: width,
+            height: height
+        };
+        gif.writeHeader().map_err(|e| e.to_string())?;
+        return Ok(gif);
+    }
+
+    // 1フレーム追加
+    // delay ... 表示時間(1/100秒)
+    pub fn addFrame(&mut self, frame: &Frame, delay: u16) -> Result<(), String> {
+        if frame.width != self.width || frame.height != self.height {
+            return Err(format!("frame size {}x{} does not match {}x{}", frame.width, frame.height, self.width, self.height));
+        }
+        let (palette, indices) = quantize(frame);
+        return self.writeFrame(&palette, &indices, delay).map_err(|e| e.to_string());
+    }
+
+    // 終端を書いて閉じる
+    pub fn finish(mut self) -> Result<(), String> {
+        self.out.write_all(&[0x3b]).map_err(|e| e.to_string())?;
+        return self.out.flush().map_err(|e| e.to_string());
+    }
+
+    fn writeHeader(&mut self) -> ::std::io::Result<()> {
+        self.out.write_all(b"GIF89a")?;
+        writeU16(&mut self.out, self.width as u16)?;
+        writeU16(&mut self.out, self.height as u16)?;
+        self.out.write_all(&[0x00, 0, 0])?;     // グローバルパレットなし
+        // 無限ループ (NETSCAPE2.0)
+        self.out.write_all(&[0x21, 0xff, 11])?;
+        self.out.write_all(b"NETSCAPE2.0")?;
+        self.out.write_all(&[3, 1, 0, 0, 0])?;
+        return Ok(());
+    }
+
+    fn writeFrame(&mut self, palette: &[[u8; 3]], indices: &[u8], delay: u16) -> ::std::io::Result<()> {
+        // Graphic Control Extension
+        self.out.write_all(&[0x21, 0xf9, 4, 0x00])?;
+        writeU16(&mut self.out, delay)?;
+        self.out.write_all(&[0, 0])?;
+
+        // Image Descriptor（256色のローカルパレット付き）
+        self.out.write_all(&[0x2c])?;
+        writeU16(&mut self.out, 0)?;
+        writeU16(&mut self.out, 0)?;
+        writeU16(&mut self.out, self.width as u16)?;
+        writeU16(&mut self.out, self.height as u16)?;
+        self.out.write_all(&[0x80 | 7])?;
+        for i in 0..256 {
+            let c = palette.get(i).cloned().unwrap_or([0, 0, 0]);
+            self.out.write_all(&c)?;
+        }
+
+        // 画像データ（LZW圧縮、255バイトずつのブロック）
+        self.out.write_all(&[8])?;
+        let data = lzw(indices);
+        for chunk in data.chunks(255) {
+            self.out.write_all(&[chunk.len() as u8])?;
+            self.out.write_all(chunk)?;
+        }
+        self.out.write_all(&[0])?;
+        return Ok(());
+    }
+}
+
+fn writeU16(out: &mut BufWriter<File>, v: u16) -> ::std::io::Result<()> {
+    return out.write_all(&[(v & 0xff) as u8, (v >> 8) as u8]);
+}
+
+// 256色以下に減色してパレットと色番号を返す
+// 色数が256以下ならそのまま、多ければRGB 3-3-2ビットに丸める
+fn quantize(frame: &Frame) -> (Vec<[u8; 3]>, Vec<u8>) {
+    let mut palette: Vec<[u8; 3]> = Vec::new();
+    let mut lookup: HashMap<[u8; 3], u8> = HashMap::new();
+    let mut indices: Vec<u8> = Vec::with_capacity(frame.pixels.len() / 3);
+
+    for p in frame.pixels.chunks(3) {
+        let c = [p[0], p[1], p[2]];
+        match lookup.get(&c) {
+            Some(&i) => indices.push(i),
+            None => {
+                if palette.len() >= 256 {
+                    return quantize332(frame);
+                }
+                let i = palette.len() as u8;
+                palette.push(c);
+                lookup.insert(c, i);
+                indices.push(i);
+            }
+        }
+    }
+    return (palette, indices);
+}
+
+fn quantize332(frame: &Frame) -> (Vec<[u8; 3]>, Vec<u8>) {
+    let mut palette: Vec<[u8; 3]> = Vec::new();
+    for i in 0..256u32 {
+        let r = (i >> 5) & 7;
+        let g = (i >> 2) & 7;
+        let b = i & 3;
+        palette.push([(r * 255 / 7) as u8, (g * 255 / 7) as u8, (b * 255 / 3) as u8]);
+    }
+    let indices = frame.pixels.chunks(3).map(|p| (p[0] & 0xe0) | ((p[1] >> 3) & 0x1c) | (p[2] >> 6)).collect();
+    return (palette, indices);
+}
+
+// ---------------------------------
+//  LZW圧縮（GIF用、最小コード長8ビット）
+// ---------------------------------
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u32,
+    nBits: u32
+}
+
+impl BitWriter {
+    fn put(&mut self, code: u16, size: u32) {
+        self.acc |= (code as u32) << self.nBits;
+        self.nBits += size;
+        while self.nBits >= 8 {
+            self.bytes.push((self.acc & 0xff) as u8);
+            self.acc >>= 8;
+            self.nBits -= 8;
+        }
+    }
+
+    fn flush(mut self) -> Vec<u8> {
+        if self.nBits > 0 {
+            self.bytes.push((self.acc & 0xff) as u8);
+        }
+        return self.bytes;
+    }
+}
+
+fn lzw(indices: &[u8]) -> Vec<u8> {
+    let clear: u16 = 256;
+    let end: u16 = 257;
+    let mut w = BitWriter { bytes: Vec::new(), acc: 0, nBits: 0 };
+    let mut dict: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next: u16 = 258;
+    let mut size: u32 = 9;
+
+    w.put(clear, size);
+    if indices.is_empty() {
+        w.put(end, size);
+        return w.flush();
+    }
+
+    let mut prefix = indices[0] as u16;
+    for &k in &indices[1..] {
+        if let Some(&code) = dict.get(&(prefix, k)) {
+            prefix = code;
+            continue;
+        }
+        w.put(prefix, size);
+        // 復号側は1コード遅れて辞書に登録するので、登録前の個数でコード長を決める
+        if (next as u32) >= (1 << size) && size < 12 {
+            size += 1;
+        }
+        if next < MAX_CODES {
+            dict.insert((prefix, k), next);
+            next += 1;
+        } else {
+            // 辞書がいっぱいになったら作り直す
+            w.put(clear, size);
+            dict.clear();
+            next = 258;
+            size = 9;
+        }
+        prefix = k as u16;
+    }
+    w.put(prefix, size);
+    if (next as u32) >= (1 << size) && size < 12 {
+        size += 1;
+    }
+    w.put(end, size);
+    return w.flush();
+}
@@ -0,0 +1,143 @@
+#![allow(non_snake_case)]
+
+use sdl2::render::{Canvas, RenderTarget};
+use std::fs;
+
+use Screenshot;
+use Screenshot::Frame;
+use Gif::GifWriter;
+
+const GAME_FPS: u32 = 60;   // ゲームのフレームレート
+const GIF_MAX_FPS: u32 = 50;    // GIFの待ち時間は1/100秒単位で、2未満だと再生ソフトが0.1秒にしてしまう
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum RecordFormat {
+    GIF,    // アニメーションGIF 1ファイル
+    PNG     // ディレクトリに連番のPNG
+}
+
+impl RecordFormat {
+    // コマンドラインでの名前（"gif", "png"）
+    pub fn fromName(name: &str) -> Option<RecordFormat> {
+        return match name {
+            "gif" => Some(RecordFormat::GIF),
+            "png" => Some(RecordFormat::PNG),
+            _ => None
+        };
+    }
+}
+
+// ---------------------------------
+//  プレイ画面の録画
+//  毎フレームcapture()を呼ぶと、指定したフレームレートになるように間引いて記録する
+//  （k枚目はゲームの経過時間がk/fps秒に達したフレーム。60で割り切れないfpsでも平均が合う）
+// ---------------------------------
+pub struct Recorder {
+    format: RecordFormat,
+    path: String,       // GIFならファイル名、PNGならディレクトリ名
+    fps: u32,           // 記録するフレームレート
+    count: u32,         // capture()が呼ばれた回数
+    nFrames: u32,       // 記録したフレーム数
+    gif: Option<GifWriter>
+}
+
+impl Recorder {
+    // 時刻付きの名前で録画を始める
+    // fps ... 記録するフレームレート(1-60、GIFは1-50)
+    pub fn start(format: RecordFormat, fps: u32) -> Result<Recorder, String> {
+        let path = match format {
+            RecordFormat::GIF => Screenshot::timestampedName("record", "gif"),
+            RecordFormat::PNG => {
+                let dir = format!("record-{}", Screenshot::timestamp());
+                fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+                dir
+            }
+        };
+        let maxFps = if format == RecordFormat::GIF { GIF_MAX_FPS } else { GAME_FPS };
+        let fps = if fps < 1 { 1 } else if fps > maxFps { maxFps } else { fps };
+        return Ok(Recorder {
+            format: format,
+            path: path,
+            fps: fps,
+            count: 0,
+            nFrames: 0,
+            gif: None
+        });
+    }
+
+    pub fn getPath(&self) -> &str {
+        return &self.path;
+    }
+
+    pub fn getFrameCount(&self) -> u32 {
+        return self.nFrames;
+    }
+
+    // 描画し終わったCanvasを渡す（ウィンドウならpresent()の前）
+    pub fn capture<T: RenderTarget>(&mut self, canvas: &Canvas<T>) -> Result<(), String> {
+        // 次の1枚の時刻 nFrames/fps が、今の時刻 count/GAME_FPS を過ぎていなければ記録しない
+        let due = (self.nFrames as u64) * (GAME_FPS as u64) <= (self.count as u64) * (self.fps as u64);
+        self.count += 1;
+        if !due {
+            return Ok(());
+        }
+        let frame = Frame::grab(canvas)?;
+        return self.addFrame(&frame);
+    }
+
+    pub fn addFrame(&mut self, frame: &Frame) -> Result<(), String> {
+        match self.format {
+            RecordFormat::GIF => {
+                if self.gif.is_none() {
+                    self.gif = Some(GifWriter::create(&self.path, frame.width, frame.height)?);
+                }
+                // k枚目の表示時間 = (k+1)/fps秒 - k/fps秒（1/100秒に丸めた時刻の差にして、端数を次の枚に回す）
+                let delay = (self.centiseconds(self.nFrames + 1) - self.centiseconds(self.nFrames)) as u16;
+                if let Some(ref mut gif) = self.gif {
+                    gif.addFrame(frame, delay)?;
+                }
+            }
+            RecordFormat::PNG => {
+                frame.savePng(&format!("{}/frame-{:05}.png", self.path, self.nFrames))?;
+            }
+        }
+        self.nFrames += 1;
+        return Ok(());
+    }
+
+    // k枚目の時刻(1/100秒、四捨五入)
+    fn centiseconds(&self, k: u32) -> u32 {
+        return (k * 100 + self.fps / 2) / self.fps;
+    }
+
+    // 録画を終えて保存先を返す
+    pub fn finish(mut self) -> Result<String, String> {
+        if let Some(gif) = self.gif.take() {
+            gif.finish()?;
+        }
+        return Ok(self.path);
+    }
+}
+
+// 録画を始める（失敗したらメッセージを出してNone）
+pub fn startRecording(format: RecordFormat, fps: u32) -> Option<Recorder> {
+    match Recorder::start(format, fps) {
+        Ok(recorder) => {
+            println!("recording to {}", recorder.getPath());
+            return Some(recorder);
+        }
+        Err(e) => {
+            println!("cannot start recording: {}", e);
+            return None;
+        }
+    }
+}
+
+// 録画を終えて、保存先とフレーム数を表示する
+pub fn stopRecording(recorder: Recorder) {
+    let n = recorder.getFrameCount();
+    match recorder.finish() {
+        Ok(path) => println!("saved {} ({} frames)", path, n),
+        Err(e) => println!("recording failed: {}", e)
+    }
+}
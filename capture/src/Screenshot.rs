@@ -0,0 +1,67 @@
+#![allow(non_snake_case)]
+
+use sdl2::render::{Canvas, RenderTarget};
+use sdl2::surface::Surface;
+use sdl2::pixels::{Color, PixelFormatEnum};
+use sdl2::image::SaveSurface;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// ---------------------------------
+//  画面1枚分の画像（メモリ上）
+//  1画素3バイト(R, G, B)で左上から順に並ぶ
+// ---------------------------------
+#[derive(Clone)]
+pub struct Frame {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>
+}
+
+impl Frame {
+    // Canvasの内容を読み出す
+    // ウィンドウのCanvasならpresent()する前に呼ぶこと
+    pub fn grab<T: RenderTarget>(canvas: &Canvas<T>) -> Result<Frame, String> {
+        let (w, h) = canvas.output_size()?;
+        let pixels = canvas.read_pixels(None, PixelFormatEnum::RGB24)?;
+        return Ok(Frame {
+            width: w,
+            height: h,
+            pixels: pixels
+        });
+    }
+
+    pub fn getPixel(&self, x: u32, y: u32) -> Color {
+        if x >= self.width || y >= self.height {
+            return Color::RGB(0, 0, 0);
+        }
+        let i = ((y * self.width + x) * 3) as usize;
+        return Color::RGB(self.pixels[i], self.pixels[i+1], self.pixels[i+2]);
+    }
+
+    // PNGファイルに保存
+    pub fn savePng(&self, path: &str) -> Result<(), String> {
+        let mut data = self.pixels.clone();
+        let surface = Surface::from_data(&mut data, self.width, self.height, self.width * 3, PixelFormatEnum::RGB24)?;
+        return surface.save(path);
+    }
+}
+
+// 現在時刻(UNIX時間、ms)
+pub fn timestamp() -> u64 {
+    return match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(d) => d.as_secs() * 1000 + (d.subsec_nanos() / 1_000_000) as u64,
+        Err(_) => 0
+    };
+}
+
+// "prefix-1234567890123.png" のような時刻(ms)付きのファイル名
+pub fn timestampedName(prefix: &str, ext: &str) -> String {
+    return format!("{}-{}.{}", prefix, timestamp(), ext);
+}
+
+// Canvasの内容をカレントディレクトリにPNGで保存して、ファイル名を返す
+pub fn saveScreenshot<T: RenderTarget>(canvas: &Canvas<T>) -> Result<String, String> {
+    let path = timestampedName("screenshot", "png");
+    Frame::grab(canvas)?.savePng(&path)?;
+    return Ok(path);
+}
@@ -0,0 +1,12 @@
+// *****************************************************************
+//  SDL2 on Rust
+//
+//  画面の保存と録画（各サンプルで共通）
+//  Cargo.tomlで capture = { path = "../capture" } として使う
+// *****************************************************************
+#![allow(non_snake_case)]
+extern crate sdl2;
+
+pub mod Screenshot;
+pub mod Gif;
+pub mod Recorder;
@@ -0,0 +1,182 @@
+// *****************************************************************
+//  GIFの書き出しのテスト
+//  書いたファイルをここで読み戻して、元の画素と表示時間に戻ることを確かめる
+// *****************************************************************
+#![allow(non_snake_case)]
+extern crate capture;
+
+use std::env;
+use std::fs;
+use std::process;
+
+use capture::Screenshot::Frame;
+use capture::Gif::GifWriter;
+
+// テストごとに別の一時ファイル
+fn tempPath(name: &str) -> String {
+    let path = env::temp_dir().join(format!("capture-{}-{}.gif", name, process::id()));
+    return path.to_string_lossy().into_owned();
+}
+
+fn u16At(data: &[u8], i: usize) -> u16 {
+    return data[i] as u16 | ((data[i+1] as u16) << 8);
+}
+
+// GIFのLZWを展開して色番号を返す（最小コード長8ビット）
+fn unlzw(data: &[u8]) -> Vec<u8> {
+    let clear = 256;
+    let end = 257;
+    let mut dict: Vec<Vec<u8>> = (0..258).map(|i| vec![i as u8]).collect();
+    let mut size = 9;
+    let mut prev: Option<usize> = None;
+    let mut out = Vec::new();
+    let mut acc: u32 = 0;
+    let mut nBits = 0;
+    let mut bytes = data.iter();
+    loop {
+        while nBits < size {
+            let b = *bytes.next().expect("image data ended without an end code");
+            acc |= (b as u32) << nBits;
+            nBits += 8;
+        }
+        let code = (acc & ((1 << size) - 1)) as usize;
+        acc >>= size;
+        nBits -= size;
+
+        if code == clear {
+            dict.truncate(258);
+            size = 9;
+            prev = None;
+            continue;
+        }
+        if code == end {
+            return out;
+        }
+        let entry = if code < dict.len() {
+            dict[code].clone()
+        } else {
+            // まだ登録されていないコード（直前の列 + その先頭）
+            assert_eq!(code, dict.len(), "code {} is not in the table", code);
+            let p = &dict[prev.expect("first code after clear is not a color")];
+            let mut e = p.clone();
+            e.push(p[0]);
+            e
+        };
+        out.extend_from_slice(&entry);
+        if let Some(p) = prev {
+            if dict.len() < 4096 {
+                let mut e = dict[p].clone();
+                e.push(entry[0]);
+                dict.push(e);
+            }
+        }
+        prev = Some(code);
+        if dict.len() == (1 << size) && size < 12 {
+            size += 1;
+        }
+    }
+}
+
+// GIFファイルを読んで、各フレームの(画素RGB, 表示時間)を返す
+fn decode(data: &[u8]) -> (u32, u32, Vec<(Vec<u8>, u16)>) {
+    assert_eq!(&data[0..6], b"GIF89a");
+    let width = u16At(data, 6) as u32;
+    let height = u16At(data, 8) as u32;
+    assert_eq!(data[10] & 0x80, 0, "unexpected global palette");
+    let mut i = 13;
+    let mut delay = 0;
+    let mut frames = Vec::new();
+    loop {
+        match data[i] {
+            0x21 => {
+                // 拡張ブロック（表示時間だけ読む）
+                if data[i+1] == 0xf9 {
+                    delay = u16At(data, i + 4);
+                }
+                i += 2;
+                while data[i] != 0 {
+                    i += data[i] as usize + 1;
+                }
+                i += 1;
+            }
+            0x2c => {
+                assert_eq!((u16At(data, i + 5) as u32, u16At(data, i + 7) as u32), (width, height));
+                let flags = data[i+9];
+                assert_eq!(flags & 0x80, 0x80, "no local palette");
+                let nColors = 2 << (flags & 7);
+                let palette = &data[i+10..i+10+nColors*3];
+                i += 10 + nColors * 3;
+                assert_eq!(data[i], 8, "minimum code size");
+                i += 1;
+                let mut lzw = Vec::new();
+                while data[i] != 0 {
+                    let n = data[i] as usize;
+                    lzw.extend_from_slice(&data[i+1..i+1+n]);
+                    i += n + 1;
+                }
+                i += 1;
+                let pixels = unlzw(&lzw).iter().flat_map(|&c| palette[c as usize*3..c as usize*3+3].to_vec()).collect();
+                frames.push((pixels, delay));
+            }
+            0x3b => {
+                assert_eq!(i, data.len() - 1, "data after the trailer");
+                return (width, height, frames);
+            }
+            b => panic!("unexpected block 0x{:02x} at {}", b, i)
+        }
+    }
+}
+
+// 書いて読み戻す
+fn roundTrip(name: &str, frames: &[(Frame, u16)]) -> (u32, u32, Vec<(Vec<u8>, u16)>) {
+    let path = tempPath(name);
+    let mut gif = GifWriter::create(&path, frames[0].0.width, frames[0].0.height).unwrap();
+    for &(ref frame, delay) in frames {
+        gif.addFrame(frame, delay).unwrap();
+    }
+    gif.finish().unwrap();
+    let data = fs::read(&path).unwrap();
+    let _ = fs::remove_file(&path);
+    return decode(&data);
+}
+
+fn frame(width: u32, height: u32, color: &dyn Fn(u32, u32) -> [u8; 3]) -> Frame {
+    let mut pixels = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            pixels.extend_from_slice(&color(x, y));
+        }
+    }
+    return Frame { width: width, height: height, pixels: pixels };
+}
+
+#[test]
+fn frames_come_back_with_their_pixels_and_delays() {
+    let a = frame(5, 3, &|x, y| if (x + y) % 2 == 0 { [255, 0, 0] } else { [0, 0, 0] });
+    let b = frame(5, 3, &|x, _| [0, (x * 50) as u8, 255]);
+    let (w, h, frames) = roundTrip("small", &[(a.clone(), 7), (b.clone(), 6)]);
+    assert_eq!((w, h), (5, 3));
+    assert_eq!(frames.len(), 2);
+    assert!(frames[0] == (a.pixels, 7));
+    assert!(frames[1] == (b.pixels, 6));
+}
+
+#[test]
+fn long_images_survive_a_full_code_table() {
+    // 色が多くても256色以下なら減色されず、辞書がいっぱいになって作り直しても同じに戻る
+    let noise = frame(200, 150, &|x, y| {
+        let v = (x.wrapping_mul(2654435761) ^ y.wrapping_mul(40503)).wrapping_mul(2246822519);
+        let c = ((v >> 16) % 200) as u8;
+        return [c, 255 - c, c / 2];
+    });
+    let (_, _, frames) = roundTrip("noise", &[(noise.clone(), 2)]);
+    assert!(frames[0].0 == noise.pixels);
+}
+
+#[test]
+fn frames_of_another_size_are_rejected() {
+    let path = tempPath("size");
+    let mut gif = GifWriter::create(&path, 4, 4).unwrap();
+    assert!(gif.addFrame(&frame(3, 4, &|_, _| [0, 0, 0]), 2).is_err());
+    let _ = fs::remove_file(&path);
+}
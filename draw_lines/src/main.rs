@@ -8,14 +8,19 @@
 #![allow(non_snake_case)]
 
 extern crate sdl2;
+extern crate capture;
 
-use sdl2::video::Window;
-use sdl2::render::Canvas;
+use sdl2::render::{Canvas, RenderTarget};
 use sdl2::pixels::Color;
 use sdl2::rect::Point;
 use sdl2::event::Event;
 use sdl2::keyboard::Keycode;
 use std::time::Duration;
+use sdl2::surface::Surface;
+use sdl2::pixels::PixelFormatEnum;
+use std::env;
+use capture::Screenshot;
+use capture::Recorder::{Recorder, RecordFormat, startRecording, stopRecording};
 
 // 1個 の 物体（ここでは3角形）
 struct Entity {
@@ -48,7 +53,7 @@ impl Entity {
         };
     }
 
-    fn draw(&self, canvas: &mut Canvas<Window>) {
+    fn draw<T: RenderTarget>(&self, canvas: &mut Canvas<T>) {
         canvas.set_draw_color(Color::RGB(0, (255.0 * &self.brightness) as u8, 0));
 
         // ３角形の中央
@@ -102,23 +107,50 @@ impl Entity {
     }
 }
 
+// 1フレーム分描いて、物体を動かす
+fn drawFrame<T: RenderTarget>(canvas: &mut Canvas<T>, entities: &mut Vec<Entity>) {
+    // clear canvas
+    canvas.set_draw_color(Color::RGB(0, 0, 0));
+    canvas.clear();
+
+    // draw objects
+    for ent in entities {
+        // draw
+        ent.draw(canvas);
+
+        // update entity
+        ent.incrRotation(3.141592/180.0*2.0);
+        ent.updateRadius();
+        ent.updateBrightness();
+    }
+}
+
+// コマンドライン引数
+//   --headless SECONDS  ウィンドウを出さずにSECONDS秒分を録画して終了
+//   --fps N             録画のフレームレート（デフォルト15）
+//   --record-format F   録画の形式 gif（デフォルト、アニメーションGIF）または png（連番PNG）
+// 実行中は F12: スクリーンショット  F10: 録画開始/終了
 fn main() {
-    let sdl2_context = sdl2::init().unwrap();
-    let video_subsystem = sdl2_context.video().unwrap();
+    let args: Vec<String> = env::args().collect();
+    let mut headless: Option<u32> = None;
+    let mut fps: u32 = 15;
+    let mut format = RecordFormat::GIF;
+    let mut i = 1;
+    while i < args.len() {
+        let next = args.get(i+1).and_then(|s| s.parse::<u32>().ok());
+        match args[i].as_str() {
+            "--headless" => { headless = Some(next.unwrap_or(10)); i += 1; }
+            "--fps" => { fps = next.unwrap_or(15); i += 1; }
+            "--record-format" => { format = args.get(i+1).and_then(|s| RecordFormat::fromName(s)).unwrap_or(RecordFormat::GIF); i += 1; }
+            arg => println!("unknown option: {}", arg)
+        }
+        i += 1;
+    }
 
     let w = 960;
     let h = 640;
     let R: f64 = 300.0;
 
-    let window = video_subsystem
-        .window("SDL", w, h)
-        .position_centered()
-        .build()
-        .unwrap();
-
-    let mut canvas = window.into_canvas().build().unwrap();
-    canvas.present();
-
     // object initialize
     let mut entities = Vec::new();
     let N = 32;
@@ -133,6 +165,37 @@ fn main() {
         entities.push(ent);
     }
 
+    if let Some(seconds) = headless {
+        // ソフトウェア描画で画面を作って保存する
+        let surface = Surface::new(w, h, PixelFormatEnum::RGB888).expect("failed to create surface");
+        let mut canvas = surface.into_canvas().expect("failed to create software canvas");
+        let mut recorder = match startRecording(format, fps) {
+            Some(r) => r,
+            None => { return; }
+        };
+        for _ in 0..seconds*60 {
+            drawFrame(&mut canvas, &mut entities);
+            recorder.capture(&canvas).expect("failed to save frame");
+        }
+        stopRecording(recorder);
+        return;
+    }
+
+    let sdl2_context = sdl2::init().unwrap();
+    let video_subsystem = sdl2_context.video().unwrap();
+
+    let window = video_subsystem
+        .window("SDL", w, h)
+        .position_centered()
+        .build()
+        .unwrap();
+
+    let mut canvas = window.into_canvas().build().unwrap();
+    canvas.present();
+
+    let mut screenshot = false;     // F12でスクリーンショット
+    let mut recording: Option<Recorder> = None;    // F10で録画開始/終了
+
     let mut event_pump = sdl2_context.event_pump().unwrap();
     'running: loop {
         for event in event_pump.poll_iter() {
@@ -142,23 +205,37 @@ fn main() {
                     keycode: Some(Keycode::Escape),
                     ..
                 } => break 'running,
+                | Event::KeyDown {
+                    keycode: Some(Keycode::F12), ..
+                } => {
+                    screenshot = true;
+                }
+                | Event::KeyDown {
+                    keycode: Some(Keycode::F10), ..
+                } => {
+                    recording = match recording.take() {
+                        Some(r) => { stopRecording(r); None }
+                        None => startRecording(format, fps)
+                    };
+                }
                 _ => {}
             }
         }
 
-        // clear canvas
-        canvas.set_draw_color(Color::RGB(0, 0, 0));
-        canvas.clear();
-    
-        // draw objects
-        for ent in &mut entities {
-            // draw
-            ent.draw(&mut canvas);
-
-            // update entity
-            ent.incrRotation(3.141592/180.0*2.0);
-            ent.updateRadius();
-            ent.updateBrightness();
+        drawFrame(&mut canvas, &mut entities);
+
+        // F12が押されていたら表示する前に保存
+        if screenshot {
+            match Screenshot::saveScreenshot(&canvas) {
+                Ok(path) => println!("saved {}", path),
+                Err(e) => println!("screenshot failed: {}", e)
+            }
+            screenshot = false;
+        }
+        if let Some(ref mut r) = recording {
+            if let Err(e) = r.capture(&canvas) {
+                println!("recording failed: {}", e);
+            }
         }
 
         // show background image
@@ -166,4 +243,7 @@ fn main() {
 
         ::std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 60));
     }
+    if let Some(r) = recording {
+        stopRecording(r);
+    }
 }
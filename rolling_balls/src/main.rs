@@ -13,6 +13,7 @@
 // *****************************************************************
 #![allow(non_snake_case)]
 extern crate sdl2;
+extern crate capture;
 
 use sdl2::pixels::Color;
 use sdl2::event::Event;
@@ -23,6 +24,11 @@ use sdl2::rect::Rect;
 use sdl2::rect::Point;
 use std::time::Duration;
 use std::env;
+use sdl2::render::{Canvas, RenderTarget, Texture};
+use sdl2::surface::Surface;
+use sdl2::pixels::PixelFormatEnum;
+use capture::Screenshot;
+use capture::Recorder::{Recorder, RecordFormat, startRecording, stopRecording};
 
 struct Ball {
     x: f64, // 現在の位置
@@ -68,10 +74,42 @@ impl Ball {
     }
 }
 
+// 1フレーム分描いて、ボールを動かす
+fn drawFrame<T: RenderTarget>(canvas: &mut Canvas<T>, balls: &mut Vec<Ball>, image_texture: &Texture, IMGW: u32) {
+    canvas.set_draw_color(Color::RGB(0, 0, 0));
+    canvas.clear();
+    
+    // ボールの軌道の直線を描く
+    for ball in balls.iter() {
+        let p1: Point = Point::new(ball.edge_x1, ball.edge_y1);
+        let p2: Point = Point::new(ball.edge_x2, ball.edge_y2);
+
+        // draw_lines()で直線
+        let ps = [p1, p2];
+        canvas.set_draw_color(Color::RGB(128, 128, 128));
+        let _ = canvas.draw_lines(ps.as_ref()); // [Point]から&[Point]を生成する    
+    }
+
+    // ボールを描く
+    for ball in balls.iter_mut() {
+        let dest: Rect = Rect::new(ball.x as i32 - (IMGW as i32) / 2, ball.y as i32 - (IMGW as i32) / 2, IMGW, IMGW);
+        canvas.copy(image_texture, None, Some(dest)).expect("copy texture to canvas failed");
+
+        // ボールの位置更新
+        ball.update();
+    }
+}
+
+// コマンドライン引数
+//   nBalls              ボールの個数
+//   --headless SECONDS  ウィンドウを出さずにSECONDS秒分を録画して終了
+//   --fps N             録画のフレームレート（デフォルト15）
+//   --record-format F   録画の形式 gif（デフォルト、アニメーションGIF）または png（連番PNG）
+// 実行中は F12: スクリーンショット  F10: 録画開始/終了
 fn main() {
     let args: Vec<String> = env::args().collect();
     if args.len()<2 {
-        println!("usage {} nBalls", args[0]);
+        println!("usage {} nBalls [--headless SECONDS] [--fps N] [--record-format gif|png]", args[0]);
         return;
     }
     let nBallsStr = &args[1];
@@ -79,24 +117,25 @@ fn main() {
 
     println!("nBalls={}", nBalls);
 
-    let sdl2_context = sdl2::init().unwrap();
-    let video_subsystem = sdl2_context.video().unwrap();
+    let mut headless: Option<u32> = None;
+    let mut fps: u32 = 15;
+    let mut format = RecordFormat::GIF;
+    let mut i = 2;
+    while i < args.len() {
+        let next = args.get(i+1).and_then(|s| s.parse::<u32>().ok());
+        match args[i].as_str() {
+            "--headless" => { headless = Some(next.unwrap_or(10)); i += 1; }
+            "--fps" => { fps = next.unwrap_or(15); i += 1; }
+            "--record-format" => { format = args.get(i+1).and_then(|s| RecordFormat::fromName(s)).unwrap_or(RecordFormat::GIF); i += 1; }
+            arg => println!("unknown option: {}", arg)
+        }
+        i += 1;
+    }
 
     let WINDOW_WIDTH = 800;
     let WINDOW_HEIGHT = 800;
     let IMGW: u32 = 48;
 
-    let window = video_subsystem
-        .window("Rotating ball illusion", WINDOW_WIDTH, WINDOW_HEIGHT)
-        .position_centered()
-        .build()
-        .unwrap();
-
-    let mut canvas = window.into_canvas().build().unwrap();
-
-    let texture_creator: TextureCreator<_> = canvas.texture_creator();
-    let image_texture = texture_creator.load_texture("assets/neptune_transparent.png").expect("load image failed");
-
     let mut balls: Vec<Ball> = Vec::new();
 
     let pi2 = 3.14159265 * 2.0;
@@ -110,6 +149,41 @@ fn main() {
         ));
     }
 
+    if let Some(seconds) = headless {
+        // ソフトウェア描画で画面を作って保存する
+        let surface = Surface::new(WINDOW_WIDTH, WINDOW_HEIGHT, PixelFormatEnum::RGB888).expect("failed to create surface");
+        let mut canvas = surface.into_canvas().expect("failed to create software canvas");
+        let texture_creator = canvas.texture_creator();
+        let image_texture = texture_creator.load_texture("assets/neptune_transparent.png").expect("load image failed");
+        let mut recorder = match startRecording(format, fps) {
+            Some(r) => r,
+            None => { return; }
+        };
+        for _ in 0..seconds*60 {
+            drawFrame(&mut canvas, &mut balls, &image_texture, IMGW);
+            recorder.capture(&canvas).expect("failed to save frame");
+        }
+        stopRecording(recorder);
+        return;
+    }
+
+    let sdl2_context = sdl2::init().unwrap();
+    let video_subsystem = sdl2_context.video().unwrap();
+
+    let window = video_subsystem
+        .window("Rotating ball illusion", WINDOW_WIDTH, WINDOW_HEIGHT)
+        .position_centered()
+        .build()
+        .unwrap();
+
+    let mut canvas = window.into_canvas().build().unwrap();
+
+    let texture_creator: TextureCreator<_> = canvas.texture_creator();
+    let image_texture = texture_creator.load_texture("assets/neptune_transparent.png").expect("load image failed");
+
+    let mut screenshot = false;     // F12でスクリーンショット
+    let mut recording: Option<Recorder> = None;    // F10で録画開始/終了
+
     let mut event_pump = sdl2_context.event_pump().unwrap();
     'running: loop {
         for event in event_pump.poll_iter() {
@@ -119,38 +193,43 @@ fn main() {
                     keycode: Some(Keycode::Escape),
                     ..
                 } => break 'running,
+                | Event::KeyDown {
+                    keycode: Some(Keycode::F12), ..
+                } => {
+                    screenshot = true;
+                }
+                | Event::KeyDown {
+                    keycode: Some(Keycode::F10), ..
+                } => {
+                    recording = match recording.take() {
+                        Some(r) => { stopRecording(r); None }
+                        None => startRecording(format, fps)
+                    };
+                }
                 _ => {}
             }
         }
 
-        canvas.set_draw_color(Color::RGB(0, 0, 0));
-        canvas.clear();
-        
-        // ボールの軌道の直線を描く
-        for i in 0..nBalls {
-            let idx = i as usize;
-            
-            let p1: Point = Point::new(balls[idx].edge_x1, balls[idx].edge_y1);
-            let p2: Point = Point::new(balls[idx].edge_x2, balls[idx].edge_y2);
-    
-            // draw_lines()で直線
-            let ps = [p1, p2];
-            canvas.set_draw_color(Color::RGB(128, 128, 128));
-            let _ = canvas.draw_lines(ps.as_ref()); // [Point]から&[Point]を生成する    
-        }
-
-        // ボールを描く
-        for i in 0..nBalls {
-            let idx = i as usize;
-            
-            let dest: Rect = Rect::new(balls[idx].x as i32 - (IMGW as i32) / 2, balls[idx].y as i32 - (IMGW as i32) / 2, IMGW, IMGW);
-            canvas.copy(&image_texture, None, Some(dest)).expect("copy texture to canvas failed");
+        drawFrame(&mut canvas, &mut balls, &image_texture, IMGW);
 
-            // ボールの位置更新
-            balls[idx].update();
+        // F12が押されていたら表示する前に保存
+        if screenshot {
+            match Screenshot::saveScreenshot(&canvas) {
+                Ok(path) => println!("saved {}", path),
+                Err(e) => println!("screenshot failed: {}", e)
+            }
+            screenshot = false;
+        }
+        if let Some(ref mut r) = recording {
+            if let Err(e) = r.capture(&canvas) {
+                println!("recording failed: {}", e);
+            }
         }
 
         canvas.present();
         ::std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 60));
     }
+    if let Some(r) = recording {
+        stopRecording(r);
+    }
 }
@@ -24,6 +24,7 @@
 // *****************************************************************
 #![allow(non_snake_case)]
 extern crate sdl2;
+extern crate capture;
 
 use sdl2::pixels::Color;
 use sdl2::event::Event;
@@ -31,6 +32,7 @@ use sdl2::keyboard::Keycode;
 use sdl2::render::TextureCreator;
 use sdl2::image::LoadTexture;
 use std::time::Duration;
+use capture::Screenshot;
 
 fn main() {
     let sdl2_context = sdl2::init().unwrap();
@@ -47,6 +49,8 @@ fn main() {
     let texture_creator: TextureCreator<_> = canvas.texture_creator();
     let image_texture = texture_creator.load_texture("assets/elichika.jpg").expect("load image failed");
 
+    let mut screenshot = false;     // F12でスクリーンショット
+
     let mut event_pump = sdl2_context.event_pump().unwrap();
     'running: loop {
         for event in event_pump.poll_iter() {
@@ -56,6 +60,11 @@ fn main() {
                     keycode: Some(Keycode::Escape),
                     ..
                 } => break 'running,
+                | Event::KeyDown {
+                    keycode: Some(Keycode::F12), ..
+                } => {
+                    screenshot = true;
+                }
                 _ => {}
             }
         }
@@ -65,6 +74,15 @@ fn main() {
         
         canvas.copy(&image_texture, None, None).expect("copy texture to canvas failed");
 
+        // F12が押されていたら表示する前に保存
+        if screenshot {
+            match Screenshot::saveScreenshot(&canvas) {
+                Ok(path) => println!("saved {}", path),
+                Err(e) => println!("screenshot failed: {}", e)
+            }
+            screenshot = false;
+        }
+
         canvas.present();
         ::std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 60));
     }